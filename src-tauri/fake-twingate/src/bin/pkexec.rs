@@ -0,0 +1,20 @@
+//! Passthrough `pkexec` so elevated `twingate start|stop|auth` calls reach the
+//! fake client without a polkit prompt during development.
+
+use std::process::Command;
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let Some(program) = args.next() else {
+        eprintln!("usage: pkexec PROGRAM [ARGUMENTS...]");
+        std::process::exit(127);
+    };
+
+    match Command::new(&program).args(args).status() {
+        Ok(status) => std::process::exit(status.code().unwrap_or(1)),
+        Err(e) => {
+            eprintln!("pkexec: failed to run {}: {}", program, e);
+            std::process::exit(127);
+        }
+    }
+}
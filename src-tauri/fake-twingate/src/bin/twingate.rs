@@ -0,0 +1,5 @@
+use fake_twingate::{main_for, Program};
+
+fn main() {
+    std::process::exit(main_for(Program::Twingate));
+}
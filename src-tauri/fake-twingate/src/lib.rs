@@ -0,0 +1,322 @@
+//! Scenario-driven stand-ins for the `twingate` and `twingate-notifier` CLIs.
+//!
+//! A scenario is a JSON file listing the states the client moves through, e.g.
+//! not running, then starting for a few polls, then waiting for authentication,
+//! then connected with a set of resources. Every invocation of the fake binaries
+//! reads the scenario, answers from the current step and persists its position
+//! in a small state file, so a sequence of separate processes behaves like one
+//! client changing state over time.
+
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Environment variable pointing at the scenario file
+pub const SCENARIO_ENV: &str = "TWINGATE_FAKE_SCENARIO";
+/// Environment variable overriding where the scenario position is stored
+pub const STATE_ENV: &str = "TWINGATE_FAKE_STATE";
+
+/// The CLI being impersonated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Program {
+    Twingate,
+    Notifier,
+}
+
+/// A scripted sequence of client states
+#[derive(Debug, Clone, Deserialize)]
+pub struct Scenario {
+    pub steps: Vec<Step>,
+}
+
+/// One state of the fake client
+#[derive(Debug, Clone, Deserialize)]
+pub struct Step {
+    /// Text printed by `twingate status`
+    pub status: String,
+    /// Number of `twingate status` calls this step lasts before moving on.
+    /// Without it the step holds until one of `advance_on` runs.
+    #[serde(default)]
+    pub calls: Option<u32>,
+    /// Commands (`start`, `stop`, `auth`) that move past this step
+    #[serde(default)]
+    pub advance_on: Vec<String>,
+    /// URL printed by `twingate auth` while in this step
+    #[serde(default)]
+    pub auth_url: Option<String>,
+    /// Output of `twingate-notifier resources`: a JSON object is printed as
+    /// network JSON, a string is printed verbatim. Defaults to the status text.
+    #[serde(default)]
+    pub resources: Option<serde_json::Value>,
+}
+
+/// Position within a scenario, persisted between invocations
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cursor {
+    pub step: usize,
+    pub calls: u32,
+}
+
+/// Result of one fake CLI invocation
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Invocation {
+    pub code: i32,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl Invocation {
+    fn ok(stdout: impl Into<String>) -> Self {
+        Self {
+            code: 0,
+            stdout: stdout.into(),
+            stderr: String::new(),
+        }
+    }
+
+    fn usage(message: impl Into<String>) -> Self {
+        Self {
+            code: 2,
+            stdout: String::new(),
+            stderr: message.into(),
+        }
+    }
+}
+
+impl Scenario {
+    pub fn from_json(text: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(text)
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        Self::from_json(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    fn step(&self, cursor: &Cursor) -> Option<&Step> {
+        self.steps.get(cursor.step).or_else(|| self.steps.last())
+    }
+
+    fn advance(&self, cursor: &mut Cursor) {
+        if cursor.step + 1 < self.steps.len() {
+            cursor.step += 1;
+        }
+        cursor.calls = 0;
+    }
+
+    /// Answer one CLI invocation and move the cursor accordingly
+    pub fn run(&self, cursor: &mut Cursor, program: Program, args: &[&str]) -> Invocation {
+        let Some(step) = self.step(cursor).cloned() else {
+            return Invocation::usage("scenario has no steps");
+        };
+
+        match (program, args) {
+            (Program::Twingate, ["status", ..]) => {
+                cursor.calls += 1;
+                if step.calls.is_some_and(|limit| cursor.calls >= limit) {
+                    self.advance(cursor);
+                }
+                Invocation::ok(format!("{}\n", step.status))
+            }
+            (Program::Twingate, [command @ ("start" | "stop"), ..]) => {
+                if step.advance_on.iter().any(|c| c == command) {
+                    self.advance(cursor);
+                }
+                match *command {
+                    "start" => Invocation::ok("Twingate started\n"),
+                    _ => Invocation::ok("Twingate stopped\n"),
+                }
+            }
+            (Program::Twingate, ["auth", ..]) => {
+                if step.advance_on.iter().any(|c| c == "auth") {
+                    self.advance(cursor);
+                }
+                match &step.auth_url {
+                    Some(url) => Invocation::ok(format!("To authenticate, please visit: {}\n", url)),
+                    None => Invocation::ok("Already authenticated\n"),
+                }
+            }
+            (Program::Twingate, ["resources", ..]) => Invocation::ok(format!("{}\n", step.status)),
+            (Program::Notifier, ["resources", ..]) => match &step.resources {
+                Some(serde_json::Value::String(text)) => Invocation::ok(format!("{}\n", text)),
+                Some(value) => Invocation::ok(format!("{}\n", value)),
+                None => Invocation::ok(format!("{}\n", step.status)),
+            },
+            (Program::Twingate, _) => Invocation::usage("usage: twingate <status|start|stop|auth|resources> [resource]\n"),
+            (Program::Notifier, _) => Invocation::usage("usage: twingate-notifier resources\n"),
+        }
+    }
+}
+
+/// Where the cursor for a scenario is stored between invocations: `state_override`
+/// (the value of `TWINGATE_FAKE_STATE`) if set, otherwise next to the scenario
+pub fn state_path(scenario_path: &Path, state_override: Option<OsString>) -> PathBuf {
+    state_override.map(PathBuf::from).unwrap_or_else(|| {
+        let mut path = scenario_path.as_os_str().to_owned();
+        path.push(".state");
+        PathBuf::from(path)
+    })
+}
+
+fn load_cursor(path: &Path) -> Cursor {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn save_cursor(path: &Path, cursor: &Cursor) -> io::Result<()> {
+    fs::write(path, serde_json::to_string(cursor)?)
+}
+
+/// Entry point shared by the fake binaries; returns the process exit code
+pub fn main_for(program: Program) -> i32 {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let Some(scenario_path) = std::env::var_os(SCENARIO_ENV).map(PathBuf::from) else {
+        eprintln!("{} must point at a scenario file", SCENARIO_ENV);
+        return 2;
+    };
+
+    let scenario = match Scenario::load(&scenario_path) {
+        Ok(scenario) => scenario,
+        Err(e) => {
+            eprintln!("Failed to load scenario {}: {}", scenario_path.display(), e);
+            return 2;
+        }
+    };
+
+    let state_path = state_path(&scenario_path, std::env::var_os(STATE_ENV));
+    let mut cursor = load_cursor(&state_path);
+    let invocation = scenario.run(&mut cursor, program, &args);
+
+    if let Err(e) = save_cursor(&state_path, &cursor) {
+        eprintln!("Failed to save scenario state {}: {}", state_path.display(), e);
+    }
+
+    print!("{}", invocation.stdout);
+    eprint!("{}", invocation.stderr);
+    invocation.code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scenario() -> Scenario {
+        Scenario::from_json(
+            r#"{
+                "steps": [
+                    { "status": "not-running", "advance_on": ["start"] },
+                    { "status": "starting", "calls": 2 },
+                    {
+                        "status": "User authentication is required",
+                        "auth_url": "https://example.twingate.com/auth/abc",
+                        "advance_on": ["auth"]
+                    },
+                    { "status": "online", "resources": { "resources": [] } }
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn status(scenario: &Scenario, cursor: &mut Cursor) -> String {
+        scenario.run(cursor, Program::Twingate, &["status"]).stdout
+    }
+
+    #[test]
+    fn test_step_holds_until_advance_command() {
+        let scenario = scenario();
+        let mut cursor = Cursor::default();
+
+        assert_eq!(status(&scenario, &mut cursor), "not-running\n");
+        assert_eq!(status(&scenario, &mut cursor), "not-running\n");
+
+        let started = scenario.run(&mut cursor, Program::Twingate, &["start"]);
+        assert_eq!(started.code, 0);
+        assert_eq!(cursor, Cursor { step: 1, calls: 0 });
+    }
+
+    #[test]
+    fn test_step_advances_after_call_limit() {
+        let scenario = scenario();
+        let mut cursor = Cursor { step: 1, calls: 0 };
+
+        assert_eq!(status(&scenario, &mut cursor), "starting\n");
+        assert_eq!(status(&scenario, &mut cursor), "starting\n");
+        assert_eq!(status(&scenario, &mut cursor), "User authentication is required\n");
+    }
+
+    #[test]
+    fn test_auth_prints_url_and_advances() {
+        let scenario = scenario();
+        let mut cursor = Cursor { step: 2, calls: 0 };
+
+        let auth = scenario.run(&mut cursor, Program::Twingate, &["auth"]);
+        assert_eq!(
+            auth.stdout,
+            "To authenticate, please visit: https://example.twingate.com/auth/abc\n"
+        );
+        assert_eq!(status(&scenario, &mut cursor), "online\n");
+    }
+
+    #[test]
+    fn test_resources_output() {
+        let scenario = scenario();
+
+        let mut cursor = Cursor { step: 3, calls: 0 };
+        let resources = scenario.run(&mut cursor, Program::Notifier, &["resources"]);
+        assert_eq!(resources.stdout, "{\"resources\":[]}\n");
+
+        let mut cursor = Cursor { step: 1, calls: 0 };
+        let resources = scenario.run(&mut cursor, Program::Notifier, &["resources"]);
+        assert_eq!(resources.stdout, "starting\n");
+        assert_eq!(cursor.calls, 0, "Resource polls should not consume status calls");
+    }
+
+    #[test]
+    fn test_last_step_is_sticky() {
+        let scenario = scenario();
+        let mut cursor = Cursor { step: 3, calls: 0 };
+
+        for _ in 0..5 {
+            assert_eq!(status(&scenario, &mut cursor), "online\n");
+        }
+        scenario.run(&mut cursor, Program::Twingate, &["stop"]);
+        assert_eq!(cursor.step, 3);
+    }
+
+    #[test]
+    fn test_unknown_commands() {
+        let scenario = scenario();
+        let mut cursor = Cursor::default();
+
+        assert_eq!(scenario.run(&mut cursor, Program::Twingate, &["setup"]).code, 2);
+        assert_eq!(scenario.run(&mut cursor, Program::Notifier, &[]).code, 2);
+        assert_eq!(cursor, Cursor::default());
+    }
+
+    #[test]
+    fn test_empty_scenario() {
+        let scenario = Scenario::from_json(r#"{ "steps": [] }"#).unwrap();
+        let mut cursor = Cursor::default();
+
+        assert_eq!(scenario.run(&mut cursor, Program::Twingate, &["status"]).code, 2);
+    }
+
+    #[test]
+    fn test_state_path() {
+        assert_eq!(
+            state_path(Path::new("/tmp/connect.json"), None),
+            PathBuf::from("/tmp/connect.json.state")
+        );
+        assert_eq!(
+            state_path(Path::new("/tmp/connect.json"), Some(OsString::from("/run/fake.state"))),
+            PathBuf::from("/run/fake.state")
+        );
+    }
+}
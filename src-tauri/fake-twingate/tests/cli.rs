@@ -0,0 +1,71 @@
+use std::path::Path;
+use std::process::{Command, Output};
+
+const SCENARIO: &str = r#"{
+    "steps": [
+        { "status": "not-running", "advance_on": ["start"] },
+        { "status": "starting", "calls": 1 },
+        { "status": "online", "resources": { "resources": [] } }
+    ]
+}"#;
+
+fn run(binary: &str, scenario: &Path, args: &[&str]) -> Output {
+    Command::new(binary)
+        .args(args)
+        .env(fake_twingate::SCENARIO_ENV, scenario)
+        .env_remove(fake_twingate::STATE_ENV)
+        .output()
+        .expect("failed to run fake binary")
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn test_binaries_share_scenario_state_across_processes() {
+    let dir = std::env::temp_dir().join(format!("fake-twingate-cli-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let scenario = dir.join("scenario.json");
+    std::fs::write(&scenario, SCENARIO).unwrap();
+
+    let twingate = env!("CARGO_BIN_EXE_twingate");
+    let notifier = env!("CARGO_BIN_EXE_twingate-notifier");
+    let pkexec = env!("CARGO_BIN_EXE_pkexec");
+
+    assert_eq!(stdout(&run(twingate, &scenario, &["status"])), "not-running\n");
+
+    let started = run(pkexec, &scenario, &[twingate, "start"]);
+    assert!(started.status.success());
+    assert_eq!(stdout(&started), "Twingate started\n");
+
+    assert_eq!(stdout(&run(twingate, &scenario, &["status"])), "starting\n");
+    assert_eq!(stdout(&run(twingate, &scenario, &["status"])), "online\n");
+    assert_eq!(stdout(&run(notifier, &scenario, &["resources"])), "{\"resources\":[]}\n");
+
+    let usage = run(twingate, &scenario, &["bogus"]);
+    assert_eq!(usage.status.code(), Some(2));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_missing_scenario_env() {
+    let output = Command::new(env!("CARGO_BIN_EXE_twingate"))
+        .arg("status")
+        .env_remove(fake_twingate::SCENARIO_ENV)
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_shipped_scenarios_parse() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("scenarios");
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        let scenario = fake_twingate::Scenario::load(&path).unwrap();
+        assert!(!scenario.steps.is_empty(), "{} has no steps", path.display());
+    }
+}
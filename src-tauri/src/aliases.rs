@@ -0,0 +1,218 @@
+use crate::config::{load_json, save_json};
+use crate::error::{Result, TwingateError};
+use crate::managers::{StateManager, TrayManager};
+use crate::models::{Alias, Resource};
+use crate::tray::{get_address_from_resource, get_open_url_from_resource};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+const DEFAULT_ALIASES_FILE_NAME: &str = "default-aliases.json";
+/// Identifier from tauri.conf.json, naming the app's config directory
+const APP_IDENTIFIER: &str = "com.twingate-tray";
+
+/// The alias each resource copies and opens by default, by alias address.
+///
+/// A choice is kept while its alias is missing from the resource, and the
+/// client's own default is used until it comes back.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DefaultAliases {
+    pub by_resource: BTreeMap<String, String>,
+}
+
+impl DefaultAliases {
+    /// Load the choices for this app, starting empty if they are missing or invalid
+    pub fn load(app_handle: &AppHandle) -> Self {
+        Self::load_or_default(default_aliases_path(app_handle))
+    }
+
+    /// Load the tray's choices without an app handle, for the CLI when no tray is running
+    pub fn load_standalone() -> Self {
+        let config_dir = config_dir(std::env::var_os("XDG_CONFIG_HOME"), std::env::var_os("HOME"));
+        Self::load_or_default(config_dir.map(|dir| dir.join(APP_IDENTIFIER).join(DEFAULT_ALIASES_FILE_NAME)))
+    }
+
+    fn load_or_default(path: Option<PathBuf>) -> Self {
+        let Some(path) = path else {
+            log::warn!("Could not resolve config directory, starting without default aliases");
+            return Self::default();
+        };
+
+        match Self::load_from(&path) {
+            Ok(aliases) => aliases,
+            Err(e) => {
+                log::warn!("Failed to load {}: {} - starting without default aliases", path.display(), e);
+                eprintln!("Warning: Failed to load {}: {}", path.display(), e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Read a default aliases file; a missing file means none were chosen
+    pub fn load_from(path: &Path) -> Result<Self> {
+        load_json(path)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        save_json(self, path)
+    }
+
+    /// Make the alias with `address` the default, or go back to the client's
+    /// default with `None`
+    pub fn set(&mut self, resource_id: &str, address: Option<&str>) {
+        match address {
+            Some(address) => {
+                self.by_resource.insert(resource_id.to_string(), address.to_string());
+            }
+            None => {
+                self.by_resource.remove(resource_id);
+            }
+        }
+    }
+
+    /// The alias chosen for a resource, if the resource still has it
+    pub fn default_alias<'a>(&self, resource: &'a Resource) -> Option<&'a Alias> {
+        let address = self.by_resource.get(&resource.id)?;
+        find_alias(resource, address)
+    }
+
+    /// Address to copy for a resource: the chosen alias, otherwise the client's
+    pub fn address<'a>(&self, resource: &'a Resource) -> &'a str {
+        match self.default_alias(resource) {
+            Some(alias) => &alias.address,
+            None => get_address_from_resource(resource),
+        }
+    }
+
+    /// URL to open for a resource: the chosen alias's if it has one,
+    /// otherwise the client's
+    pub fn open_url<'a>(&self, resource: &'a Resource) -> Option<&'a str> {
+        self.default_alias(resource)
+            .and_then(|alias| alias_open_url(resource, alias))
+            .or_else(|| get_open_url_from_resource(resource).map(String::as_str))
+    }
+}
+
+/// The resource's alias with `address`
+pub fn find_alias<'a>(resource: &'a Resource, address: &str) -> Option<&'a Alias> {
+    resource
+        .aliases
+        .iter()
+        .find(|alias| !alias.address.is_empty() && alias.address == address)
+}
+
+/// URL an alias opens in a browser, if the resource can be opened in one
+pub fn alias_open_url<'a>(resource: &Resource, alias: &'a Alias) -> Option<&'a str> {
+    Some(alias.open_url.as_str()).filter(|url| resource.can_open_in_browser && !url.is_empty())
+}
+
+/// Location of `default-aliases.json` under the XDG config directory
+pub fn default_aliases_path(app_handle: &AppHandle) -> Option<PathBuf> {
+    app_handle
+        .path()
+        .app_config_dir()
+        .ok()
+        .map(|dir| dir.join(DEFAULT_ALIASES_FILE_NAME))
+}
+
+/// `$XDG_CONFIG_HOME`, or `~/.config` when it is unset or empty
+fn config_dir(xdg_config_home: Option<OsString>, home: Option<OsString>) -> Option<PathBuf> {
+    xdg_config_home
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home.map(|home| PathBuf::from(home).join(".config")))
+}
+
+/// Choose or clear a resource's default alias, save the choice and show it in the menu
+pub async fn set_default_alias(app_handle: &AppHandle, resource_id: &str, address: Option<&str>) -> Result<()> {
+    let aliases = StateManager::with_state_mut(app_handle, |state| {
+        let mut aliases = state.default_aliases().clone();
+        aliases.set(resource_id, address);
+        state.set_default_aliases(aliases.clone());
+        aliases
+    });
+
+    let path = default_aliases_path(app_handle)
+        .ok_or_else(|| TwingateError::config_error("cannot resolve the config directory"))?;
+    aliases.save_to(&path)?;
+    TrayManager::rebuild_tray_now(app_handle).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::resource;
+
+    fn wiki(aliases: &[(&str, &str)]) -> Resource {
+        aliases
+            .iter()
+            .fold(resource("r1").name("Wiki").alias("wiki.corp.internal"), |wiki, (address, open_url)| {
+                wiki.with_alias(address, open_url)
+            })
+            .open_in_browser()
+            .build()
+    }
+
+    #[test]
+    fn test_default_alias_overrides_client_default() {
+        let wiki = wiki(&[
+            ("wiki.corp.internal", "https://wiki.corp.internal"),
+            ("docs.corp.internal", ""),
+            ("kb.corp.internal", "https://kb.corp.internal"),
+        ]);
+        let mut aliases = DefaultAliases::default();
+        assert_eq!(aliases.address(&wiki), "wiki.corp.internal");
+        assert_eq!(aliases.open_url(&wiki), Some("https://wiki.corp.internal"));
+
+        aliases.set("r1", Some("kb.corp.internal"));
+        assert_eq!(aliases.address(&wiki), "kb.corp.internal");
+        assert_eq!(aliases.open_url(&wiki), Some("https://kb.corp.internal"));
+
+        // an alias without its own URL still opens the client's default
+        aliases.set("r1", Some("docs.corp.internal"));
+        assert_eq!(aliases.address(&wiki), "docs.corp.internal");
+        assert_eq!(aliases.open_url(&wiki), Some("https://wiki.corp.internal"));
+
+        aliases.set("r1", None);
+        assert_eq!(aliases.address(&wiki), "wiki.corp.internal");
+    }
+
+    #[test]
+    fn test_missing_default_alias_falls_back() {
+        let wiki = wiki(&[("wiki.corp.internal", "https://wiki.corp.internal")]);
+        let mut aliases = DefaultAliases::default();
+        aliases.set("r1", Some("gone.corp.internal"));
+
+        assert!(aliases.default_alias(&wiki).is_none());
+        assert_eq!(aliases.address(&wiki), "wiki.corp.internal");
+        assert_eq!(aliases.by_resource.get("r1").map(String::as_str), Some("gone.corp.internal"));
+    }
+
+    #[test]
+    fn test_config_dir() {
+        assert_eq!(
+            config_dir(Some("/xdg".into()), Some("/home/user".into())),
+            Some(PathBuf::from("/xdg"))
+        );
+        assert_eq!(
+            config_dir(Some("".into()), Some("/home/user".into())),
+            Some(PathBuf::from("/home/user/.config"))
+        );
+        assert_eq!(config_dir(None, None), None);
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("twingate-tray").join(DEFAULT_ALIASES_FILE_NAME);
+        assert_eq!(DefaultAliases::load_from(&path).unwrap(), DefaultAliases::default());
+
+        let mut aliases = DefaultAliases::default();
+        aliases.set("UmVzb3VyY2U6MQ==", Some("kb.corp.internal"));
+        aliases.save_to(&path).unwrap();
+        assert_eq!(DefaultAliases::load_from(&path).unwrap(), aliases);
+    }
+}
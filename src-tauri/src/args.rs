@@ -0,0 +1,174 @@
+use crate::cli::find_resource;
+use crate::deep_link::{self, DeepLink};
+use crate::dto::ResourceDto;
+use crate::error::Result;
+use crate::managers::{NetworkDataManager, StateManager};
+use crate::tray::MenuAction;
+use std::time::Duration;
+use tauri::AppHandle;
+
+/// How long network data fetched to resolve a resource name stays fresh
+const RESOLVE_CACHE_DURATION: Duration = Duration::from_secs(30);
+
+/// What a `--flag` on the command line asks the running tray to do
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LaunchRequest {
+    /// Open a resource, given by name or id, in the browser
+    Open(String),
+    /// Authenticate to a resource, given by name or id
+    Auth(String),
+    ShowLauncher,
+    Refresh,
+    /// A `twingate-tray://` link handed over by the desktop
+    Link(DeepLink),
+}
+
+impl LaunchRequest {
+    /// Parse tray flags; `args` excludes the program name.
+    ///
+    /// Takes `--open <resource>` as well as `--open=<resource>`, and
+    /// `twingate-tray://` links.
+    pub fn parse_all(args: &[String]) -> std::result::Result<Vec<Self>, String> {
+        let mut requests = Vec::new();
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            if DeepLink::is_link(arg) {
+                requests.push(LaunchRequest::Link(DeepLink::parse(arg)?));
+                continue;
+            }
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag, Some(value.to_string())),
+                None => (arg.as_str(), None),
+            };
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next().cloned())
+                    .filter(|v| !v.is_empty())
+                    .ok_or_else(|| format!("{} needs a resource name", flag))
+            };
+
+            requests.push(match flag {
+                "--open" => LaunchRequest::Open(value()?),
+                "--auth" => LaunchRequest::Auth(value()?),
+                "--show-launcher" if inline_value.is_none() => LaunchRequest::ShowLauncher,
+                "--refresh" if inline_value.is_none() => LaunchRequest::Refresh,
+                _ => return Err(format!("unrecognized argument '{}'", arg)),
+            });
+        }
+        Ok(requests)
+    }
+
+    /// The menu action carrying out this request, looking resources up by name or id
+    pub fn to_action(&self, resources: &[ResourceDto]) -> Result<MenuAction> {
+        Ok(match self {
+            LaunchRequest::Open(name) => MenuAction::OpenInBrowser(find_resource(resources, name)?.id.clone()),
+            LaunchRequest::Auth(name) => MenuAction::Authenticate(find_resource(resources, name)?.id.clone()),
+            LaunchRequest::ShowLauncher => MenuAction::ShowLauncher,
+            LaunchRequest::Refresh => MenuAction::Refresh,
+            LaunchRequest::Link(link) => link.to_action(resources)?,
+        })
+    }
+
+    fn names_resource(&self) -> bool {
+        matches!(self, LaunchRequest::Open(_) | LaunchRequest::Auth(_))
+    }
+}
+
+async fn dispatch(app_handle: &AppHandle, requests: Vec<LaunchRequest>) -> Result<()> {
+    let resources = if requests.iter().any(LaunchRequest::names_resource) {
+        let network_manager = NetworkDataManager::new(app_handle, RESOLVE_CACHE_DURATION);
+        let network = network_manager.get_network_or_error().await?;
+        StateManager::with_state(app_handle, |state| {
+            ResourceDto::list(&network, state.default_aliases(), chrono::Utc::now())
+        })
+    } else {
+        Vec::new()
+    };
+
+    for request in requests {
+        match request {
+            LaunchRequest::Link(link) => deep_link::follow(app_handle, link).await?,
+            request => crate::handle_menu_action(app_handle, request.to_action(&resources)?).await?,
+        }
+    }
+    Ok(())
+}
+
+/// Carry out the tray flags in `argv`, which starts with the program name
+pub fn handle_launch_args(app_handle: &AppHandle, argv: Vec<String>) {
+    let requests = match LaunchRequest::parse_all(argv.get(1..).unwrap_or_default()) {
+        Ok(requests) if requests.is_empty() => return,
+        Ok(requests) => requests,
+        Err(e) => {
+            log::warn!("Ignoring launch arguments {:?}: {}", argv, e);
+            eprintln!("Warning: Ignoring launch arguments: {}", e);
+            return;
+        }
+    };
+
+    println!("Handling launch arguments: {:?}", requests);
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = dispatch(&app_handle, requests).await {
+            eprintln!("Error: Failed to handle launch arguments: {}", e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::TwingateError;
+    use crate::test_support::resource_dto;
+
+    fn parse(line: &str) -> std::result::Result<Vec<LaunchRequest>, String> {
+        let args: Vec<String> = line.split_whitespace().map(str::to_string).collect();
+        LaunchRequest::parse_all(&args)
+    }
+
+    #[test]
+    fn test_parse_flags() {
+        assert_eq!(parse(""), Ok(vec![]));
+        assert_eq!(
+            parse("--refresh --open Wiki --auth=Database --show-launcher"),
+            Ok(vec![
+                LaunchRequest::Refresh,
+                LaunchRequest::Open("Wiki".to_string()),
+                LaunchRequest::Auth("Database".to_string()),
+                LaunchRequest::ShowLauncher,
+            ])
+        );
+        assert_eq!(
+            parse("twingate-tray://auth/r1"),
+            Ok(vec![LaunchRequest::Link(DeepLink::Auth("r1".to_string()))])
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_flags() {
+        assert_eq!(parse("--open"), Err("--open needs a resource name".to_string()));
+        assert_eq!(parse("--auth="), Err("--auth needs a resource name".to_string()));
+        assert_eq!(parse("--refresh=now"), Err("unrecognized argument '--refresh=now'".to_string()));
+        assert_eq!(parse("--verbose"), Err("unrecognized argument '--verbose'".to_string()));
+        assert!(parse("twingate-tray://open/Wiki/extra").is_err());
+    }
+
+    #[test]
+    fn test_to_action_resolves_names() {
+        let resources = vec![resource_dto("r1", "Database"), resource_dto("r2", "Wiki")];
+
+        let open = LaunchRequest::Open("wiki".to_string()).to_action(&resources).unwrap();
+        assert!(matches!(open, MenuAction::OpenInBrowser(id) if id == "r2"));
+
+        let auth = LaunchRequest::Auth("r1".to_string()).to_action(&resources).unwrap();
+        assert!(matches!(auth, MenuAction::Authenticate(id) if id == "r1"));
+
+        assert!(matches!(
+            LaunchRequest::Open("Mail".to_string()).to_action(&resources),
+            Err(TwingateError::ResourceNotFound { .. })
+        ));
+        assert!(matches!(LaunchRequest::Refresh.to_action(&[]), Ok(MenuAction::Refresh)));
+    }
+}
@@ -1,4 +1,6 @@
+use crate::backend::{CliBackend, TwingateBackend};
 use crate::error::{Result, TwingateError};
+use crate::events::{AppEvent, AuthCompleted, AuthStarted};
 use crate::managers::{AuthStateManager, CommandExecutor, NetworkDataManager, StateManager, TrayManager};
 use crate::network::wait_for_service_ready;
 use std::time::Duration;
@@ -13,15 +15,8 @@ async fn rebuild_tray_for_auth_state(app_handle: &AppHandle) -> Result<()> {
     TrayManager::rebuild_tray_now(app_handle).await
 }
 
-pub async fn start_resource_auth(app_handle: &tauri::AppHandle, auth_id: &str) -> Result<()> {
-    log::debug!("Starting resource authentication for auth_id: {}", auth_id);
-    
-    let resource_id = auth_id
-        .split("-")
-        .last()
-        .ok_or_else(|| TwingateError::invalid_resource_id(auth_id))?;
-
-    log::debug!("Extracted resource_id: {}", resource_id);
+pub async fn start_resource_auth(app_handle: &tauri::AppHandle, resource_id: &str) -> Result<()> {
+    log::debug!("Starting resource authentication for resource_id: {}", resource_id);
 
     // Get network data with retry logic to handle transitional states
     let network_manager = NetworkDataManager::new(app_handle, Duration::from_secs(30));
@@ -38,9 +33,15 @@ pub async fn start_resource_auth(app_handle: &tauri::AppHandle, auth_id: &str) -
     let resource_name = &n.resources[idx].name;
     log::debug!("Found resource: {} at index {}", resource_name, idx);
 
+    AppEvent::AuthStarted(AuthStarted {
+        resource_id: Some(resource_id.to_string()),
+        auth_url: None,
+    })
+    .emit(app_handle);
+
     // Execute authentication command with proper error handling
-    let executor = CommandExecutor::new(app_handle);
-    match executor.execute_twingate_elevated(&["auth", resource_name]).await {
+    let backend = CliBackend::new(app_handle);
+    match backend.auth(Some(resource_name)).await {
         Ok(_) => {
             log::debug!("Authentication command executed successfully for resource: {}", resource_name);
             
@@ -48,6 +49,10 @@ pub async fn start_resource_auth(app_handle: &tauri::AppHandle, auth_id: &str) -
             match wait_for_auth_completion(app_handle).await {
                 Ok(_) => {
                     log::debug!("Authentication completed successfully for resource: {}", resource_name);
+                    AppEvent::AuthCompleted(AuthCompleted {
+                        resource_id: Some(resource_id.to_string()),
+                    })
+                    .emit(app_handle);
                     Ok(())
                 }
                 Err(e) => {
@@ -66,8 +71,10 @@ pub async fn start_resource_auth(app_handle: &tauri::AppHandle, auth_id: &str) -
 pub async fn handle_service_auth(app_handle: &tauri::AppHandle) -> Result<()> {
     log::debug!("Checking if service-level authentication is required");
     
+    let backend = CliBackend::new(app_handle);
+
     // Use AuthStateManager to check authentication status
-    match AuthStateManager::check_auth_status(app_handle).await? {
+    match AuthStateManager::check_auth_status(&backend).await? {
         None => {
             log::debug!("Service does not require authentication");
             return Ok(());
@@ -84,7 +91,7 @@ pub async fn handle_service_auth(app_handle: &tauri::AppHandle) -> Result<()> {
     log::info!("Service requires authentication, attempting to get auth URL");
     
     // Try multiple approaches to get the auth URL, with retries
-    if let Some(url) = find_auth_url_with_retry(app_handle).await? {
+    if let Some(url) = find_auth_url_with_retry(&backend).await? {
         return handle_auth_flow(app_handle, url).await;
     }
 
@@ -139,12 +146,13 @@ async fn handle_auth_flow(app_handle: &AppHandle, url: String) -> Result<()> {
     sleep(Duration::from_millis(3000)).await;
 
     // Wait for the service to be ready after authentication
-    match wait_for_service_ready(app_handle, AUTH_TIMEOUT_SECONDS).await {
+    match wait_for_service_ready(&CliBackend::new(app_handle), AUTH_TIMEOUT_SECONDS).await {
         Ok(_) => {
             log::info!("Service is ready after authentication");
             
             // Clear the authenticating state since authentication is complete
             StateManager::update_network(app_handle, None); // This will set status to NotRunning temporarily
+            AppEvent::AuthCompleted(AuthCompleted { resource_id: None }).emit(app_handle);
             
             // Trigger a tray rebuild to reflect the new state
             TrayManager::rebuild_tray_after_delay(app_handle.clone());
@@ -159,34 +167,26 @@ async fn handle_auth_flow(app_handle: &AppHandle, url: String) -> Result<()> {
     }
 }
 
-async fn find_auth_url_with_retry(app_handle: &AppHandle) -> Result<Option<String>> {
+async fn find_auth_url_with_retry<B: TwingateBackend>(backend: &B) -> Result<Option<String>> {
     let mut auth_url: Option<String> = None;
     let max_attempts = 8;
     let mut attempt = 0;
     
-    let executor = CommandExecutor::new(app_handle);
-    
     while attempt < max_attempts && auth_url.is_none() {
         attempt += 1;
         log::debug!("Auth URL detection attempt {} of {}", attempt, max_attempts);
         
         // Check various command outputs for auth URL
-        let outputs_to_check = vec![
-            executor.execute_twingate(&["status"]).await,
-            executor.execute_twingate(&["resources", "list"]).await,
-            executor.execute_twingate(&["auth"]).await,
+        let outputs_to_check = [
+            backend.status().await,
+            backend.resources_list().await,
+            backend.auth(None).await,
         ];
         
-        for output_result in outputs_to_check {
-            if let Ok(output) = output_result {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                let combined = format!("{}\n{}", stdout, stderr);
-                
-                if let Some(url) = AuthStateManager::extract_auth_url(&combined) {
-                    auth_url = Some(url);
-                    break;
-                }
+        for output in outputs_to_check.into_iter().flatten() {
+            if let Some(url) = AuthStateManager::extract_auth_url(&output.combined_lossy()) {
+                auth_url = Some(url);
+                break;
             }
         }
         
@@ -206,7 +206,7 @@ async fn wait_for_auth_completion(app_handle: &tauri::AppHandle) -> Result<()> {
     sleep(Duration::from_millis(AUTH_STATUS_CHECK_DELAY_MS)).await;
     
     // Wait for the service to be ready with a timeout
-    match wait_for_service_ready(app_handle, AUTH_TIMEOUT_SECONDS).await {
+    match wait_for_service_ready(&CliBackend::new(app_handle), AUTH_TIMEOUT_SECONDS).await {
         Ok(_) => {
             log::debug!("Service is ready after authentication");
             
@@ -238,6 +238,8 @@ async fn wait_for_auth_completion(app_handle: &tauri::AppHandle) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backend::scripted::{block_on_paused, BackendCall, ScenarioBackend, ScriptedBackend};
+    use crate::tray::{MenuAction, MenuId};
 
     #[test]
     fn test_constants_values() {
@@ -259,17 +261,93 @@ mod tests {
 
     #[test]
     fn test_resource_id_extraction() {
-        // Test resource ID extraction from auth command IDs
+        // Resource IDs reach start_resource_auth whole, whatever they contain
         let test_cases = vec![
-            ("authenticate-resource-123", "123"),
-            ("authenticate-simple", "simple"),
-            ("authenticate-complex-resource-with-dashes", "dashes"),
-            ("authenticate-", ""),
+            "resource-123",
+            "simple",
+            "complex-resource-with-dashes",
+            "UmVzb3VyY2U6MTIz:with:colons",
+            "",
         ];
 
-        for (auth_id, expected_resource_id) in test_cases {
-            let resource_id = auth_id.split("-").last().unwrap_or_default();
-            assert_eq!(resource_id, expected_resource_id, "Failed for auth_id: {}", auth_id);
+        for resource_id in test_cases {
+            let event_id = MenuId::Authenticate(resource_id.to_string()).encode();
+            match MenuAction::from_event_id(&event_id) {
+                MenuAction::Authenticate(id) => assert_eq!(id, resource_id, "Failed for {}", event_id),
+                other => panic!("Expected Authenticate for {}, got {:?}", event_id, other),
+            }
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_find_auth_url_with_retry_from_status() {
+        let backend = ScriptedBackend::new()
+            .reply(BackendCall::Status, &["authentication is required: visit https://example.twingate.com/auth/xyz"])
+            .reply(BackendCall::ResourcesList, &[""])
+            .reply(BackendCall::Auth, &[""]);
+
+        let url = block_on_paused(find_auth_url_with_retry(&backend)).unwrap();
+
+        assert_eq!(url, Some("https://example.twingate.com/auth/xyz".to_string()));
+        assert_eq!(backend.call_count(BackendCall::Status), 1);
+    }
+
+    #[test]
+    fn test_find_auth_url_with_retry_from_resources_list() {
+        let backend = ScriptedBackend::new()
+            .reply(BackendCall::Status, &["authentication is required"])
+            .reply(BackendCall::ResourcesList, &["Please authenticate: https://example.twingate.com/auth/list"])
+            .reply(BackendCall::Auth, &[""]);
+
+        let url = block_on_paused(find_auth_url_with_retry(&backend)).unwrap();
+
+        assert_eq!(url, Some("https://example.twingate.com/auth/list".to_string()));
+        assert_eq!(backend.call_count(BackendCall::ResourcesList), 1);
+        assert_eq!(backend.call_count(BackendCall::Resources), 0);
+    }
+
+    #[test]
+    fn test_find_auth_url_with_retry_from_auth_prompt_after_retries() {
+        let backend = ScriptedBackend::new()
+            .reply(BackendCall::Status, &["authentication is required"])
+            .fail(BackendCall::ResourcesList, "not ready")
+            .reply(BackendCall::Auth, &["", "", "To authenticate go to: https://example.twingate.com/auth/late"]);
+
+        let url = block_on_paused(find_auth_url_with_retry(&backend)).unwrap();
+
+        assert_eq!(url, Some("https://example.twingate.com/auth/late".to_string()));
+        assert_eq!(backend.call_count(BackendCall::Auth), 3);
+        assert_eq!(backend.auth_targets(), vec![None, None, None]);
+    }
+
+    #[test]
+    fn test_find_auth_url_with_retry_gives_up() {
+        let backend = ScriptedBackend::new()
+            .reply(BackendCall::Status, &["authentication is required"])
+            .reply(BackendCall::ResourcesList, &[""])
+            .reply(BackendCall::Auth, &["no url here"]);
+
+        let url = block_on_paused(find_auth_url_with_retry(&backend)).unwrap();
+
+        assert_eq!(url, None);
+        assert_eq!(backend.call_count(BackendCall::Status), 8);
+    }
+
+    #[test]
+    fn test_find_auth_url_with_retry_scenario() {
+        let backend = ScenarioBackend::shipped("connect-with-auth.json");
+
+        let url = block_on_paused(async {
+            backend.start().await.unwrap();
+            // Ride out the starting phase like the tray does before authenticating
+            wait_for_service_ready(&backend, 10).await.ok();
+            find_auth_url_with_retry(&backend).await
+        })
+        .unwrap();
+
+        assert_eq!(
+            url,
+            Some("https://example.twingate.com/auth/device?code=FAKE-1234".to_string())
+        );
+    }
+}
@@ -0,0 +1,487 @@
+use crate::error::{Result, TwingateError};
+use crate::managers::CommandExecutor;
+use std::future::Future;
+use tauri::AppHandle;
+use tauri_plugin_shell::process::Output;
+
+/// Captured result of a single Twingate client invocation
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandOutput {
+    pub code: Option<i32>,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+}
+
+impl CommandOutput {
+    /// Stdout as UTF-8, failing on invalid output like the raw shell calls did
+    pub fn stdout_str(&self) -> Result<&str> {
+        Ok(std::str::from_utf8(&self.stdout)?)
+    }
+
+    /// Stdout and stderr joined, for scraping messages that may land on either stream
+    pub fn combined_lossy(&self) -> String {
+        format!(
+            "{}\n{}",
+            String::from_utf8_lossy(&self.stdout),
+            String::from_utf8_lossy(&self.stderr)
+        )
+    }
+}
+
+impl From<Output> for CommandOutput {
+    fn from(output: Output) -> Self {
+        Self {
+            code: output.status.code(),
+            stdout: output.stdout,
+            stderr: output.stderr,
+        }
+    }
+}
+
+/// Everything the tray needs from the Twingate client
+pub trait TwingateBackend {
+    /// Current service status (`twingate status`)
+    fn status(&self) -> impl Future<Output = Result<CommandOutput>> + Send;
+
+    /// Network and resource listing (`twingate-notifier resources`)
+    fn resources(&self) -> impl Future<Output = Result<CommandOutput>> + Send;
+
+    /// The client's own resource listing (`twingate resources list`), which
+    /// carries the auth prompt while the service waits for authentication
+    fn resources_list(&self) -> impl Future<Output = Result<CommandOutput>> + Send;
+
+    /// Start the service, failing if the client reports an error
+    fn start(&self) -> impl Future<Output = Result<CommandOutput>> + Send;
+
+    /// Stop the service, failing if the client reports an error
+    fn stop(&self) -> impl Future<Output = Result<CommandOutput>> + Send;
+
+    /// Authenticate to a resource, or with `None` ask the client for the service auth prompt
+    fn auth(&self, resource_name: Option<&str>) -> impl Future<Output = Result<CommandOutput>> + Send;
+}
+
+/// Backend driving the installed `twingate` and `twingate-notifier` binaries
+pub struct CliBackend<'a> {
+    executor: CommandExecutor<'a>,
+}
+
+impl<'a> CliBackend<'a> {
+    pub fn new(app_handle: &'a AppHandle) -> Self {
+        Self {
+            executor: CommandExecutor::new(app_handle),
+        }
+    }
+}
+
+impl TwingateBackend for CliBackend<'_> {
+    async fn status(&self) -> Result<CommandOutput> {
+        Ok(self.executor.execute_twingate(&["status"]).await?.into())
+    }
+
+    async fn resources(&self) -> Result<CommandOutput> {
+        Ok(self
+            .executor
+            .execute("twingate-notifier", &["resources"])
+            .await?
+            .into())
+    }
+
+    async fn resources_list(&self) -> Result<CommandOutput> {
+        Ok(self.executor.execute_twingate(&["resources", "list"]).await?.into())
+    }
+
+    async fn start(&self) -> Result<CommandOutput> {
+        Ok(self.executor.execute_twingate_elevated(&["start"]).await?.into())
+    }
+
+    async fn stop(&self) -> Result<CommandOutput> {
+        Ok(self.executor.execute_twingate_elevated(&["stop"]).await?.into())
+    }
+
+    async fn auth(&self, resource_name: Option<&str>) -> Result<CommandOutput> {
+        let output = match resource_name {
+            Some(name) => self.executor.execute_twingate_elevated(&["auth", name]).await?,
+            None => self.executor.execute_twingate(&["auth"]).await?,
+        };
+        Ok(output.into())
+    }
+}
+
+/// Backend spawning the client binaries directly, for command-line use
+/// without a running tauri app
+pub struct ProcessBackend;
+
+impl ProcessBackend {
+    async fn run(program: &str, args: &[&str]) -> Result<CommandOutput> {
+        log::debug!("Executing command: {} {}", program, args.join(" "));
+
+        let mut command = std::process::Command::new(program);
+        command.args(args);
+        let output = tauri::async_runtime::spawn_blocking(move || command.output())
+            .await
+            .map_err(|e| TwingateError::command_failed(program, -1, e.to_string()))?
+            .map_err(tauri_plugin_shell::Error::from)?;
+
+        Ok(CommandOutput {
+            code: output.status.code(),
+            stdout: output.stdout,
+            stderr: output.stderr,
+        })
+    }
+
+    /// Run `twingate` through pkexec, failing on a non-zero exit like `execute_twingate_elevated`
+    async fn run_elevated(args: &[&str]) -> Result<CommandOutput> {
+        let mut full_args = vec!["twingate"];
+        full_args.extend_from_slice(args);
+
+        let output = Self::run("pkexec", &full_args).await?;
+        if output.code == Some(0) {
+            Ok(output)
+        } else {
+            Err(TwingateError::command_failed(
+                format!("pkexec {}", full_args.join(" ")),
+                output.code.unwrap_or(-1),
+                String::from_utf8_lossy(&output.stderr),
+            ))
+        }
+    }
+}
+
+impl TwingateBackend for ProcessBackend {
+    async fn status(&self) -> Result<CommandOutput> {
+        Self::run("twingate", &["status"]).await
+    }
+
+    async fn resources(&self) -> Result<CommandOutput> {
+        Self::run("twingate-notifier", &["resources"]).await
+    }
+
+    async fn resources_list(&self) -> Result<CommandOutput> {
+        Self::run("twingate", &["resources", "list"]).await
+    }
+
+    async fn start(&self) -> Result<CommandOutput> {
+        Self::run_elevated(&["start"]).await
+    }
+
+    async fn stop(&self) -> Result<CommandOutput> {
+        Self::run_elevated(&["stop"]).await
+    }
+
+    async fn auth(&self, resource_name: Option<&str>) -> Result<CommandOutput> {
+        match resource_name {
+            Some(name) => Self::run_elevated(&["auth", name]).await,
+            None => Self::run("twingate", &["auth"]).await,
+        }
+    }
+}
+
+/// Scripted in-memory backend for exercising retry and auth logic in tests
+#[cfg(test)]
+pub mod scripted {
+    use super::{CommandOutput, TwingateBackend};
+    use crate::error::{Result, TwingateError};
+    use fake_twingate::{Cursor, Program, Scenario};
+    use std::collections::{HashMap, VecDeque};
+    use std::future::Future;
+    use std::sync::Mutex;
+
+    /// Backend calls that can be scripted in tests
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum BackendCall {
+        Status,
+        Resources,
+        ResourcesList,
+        Start,
+        Stop,
+        Auth,
+    }
+
+    /// In-memory backend replaying canned client output.
+    ///
+    /// Replies are consumed in order per call; the last one keeps repeating once
+    /// the queue drains so a script can settle into a steady state.
+    #[derive(Default)]
+    pub struct ScriptedBackend {
+        replies: Mutex<HashMap<BackendCall, VecDeque<std::result::Result<CommandOutput, String>>>>,
+        calls: Mutex<Vec<(BackendCall, Option<String>)>>,
+    }
+
+    impl ScriptedBackend {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Queue successful replies with the given stdout
+        pub fn reply(self, call: BackendCall, stdouts: &[&str]) -> Self {
+            {
+                let mut replies = self.replies.lock().unwrap();
+                let queue = replies.entry(call).or_default();
+                for stdout in stdouts {
+                    queue.push_back(Ok(CommandOutput {
+                        code: Some(0),
+                        stdout: stdout.as_bytes().to_vec(),
+                        stderr: Vec::new(),
+                    }));
+                }
+            }
+            self
+        }
+
+        /// Queue a reply that fails with a command error
+        pub fn fail(self, call: BackendCall, message: &str) -> Self {
+            self.replies
+                .lock()
+                .unwrap()
+                .entry(call)
+                .or_default()
+                .push_back(Err(message.to_string()));
+            self
+        }
+
+        /// Number of times a call was made
+        pub fn call_count(&self, call: BackendCall) -> usize {
+            self.calls
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(c, _)| *c == call)
+                .count()
+        }
+
+        /// Arguments passed to every `auth` call, in order
+        pub fn auth_targets(&self) -> Vec<Option<String>> {
+            self.calls
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(c, _)| *c == BackendCall::Auth)
+                .map(|(_, arg)| arg.clone())
+                .collect()
+        }
+
+        fn next(&self, call: BackendCall, arg: Option<&str>) -> Result<CommandOutput> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push((call, arg.map(str::to_string)));
+
+            let mut replies = self.replies.lock().unwrap();
+            let queue = replies.entry(call).or_default();
+            let reply = if queue.len() > 1 {
+                queue.pop_front()
+            } else {
+                queue.front().cloned()
+            };
+
+            match reply {
+                Some(Ok(output)) => Ok(output),
+                Some(Err(message)) => Err(TwingateError::command_failed(format!("{:?}", call), 1, message)),
+                None => Err(TwingateError::command_failed(
+                    format!("{:?}", call),
+                    -1,
+                    "no scripted reply",
+                )),
+            }
+        }
+    }
+
+    impl TwingateBackend for ScriptedBackend {
+        async fn status(&self) -> Result<CommandOutput> {
+            self.next(BackendCall::Status, None)
+        }
+
+        async fn resources(&self) -> Result<CommandOutput> {
+            self.next(BackendCall::Resources, None)
+        }
+
+        async fn resources_list(&self) -> Result<CommandOutput> {
+            self.next(BackendCall::ResourcesList, None)
+        }
+
+        async fn start(&self) -> Result<CommandOutput> {
+            self.next(BackendCall::Start, None)
+        }
+
+        async fn stop(&self) -> Result<CommandOutput> {
+            self.next(BackendCall::Stop, None)
+        }
+
+        async fn auth(&self, resource_name: Option<&str>) -> Result<CommandOutput> {
+            self.next(BackendCall::Auth, resource_name)
+        }
+    }
+
+    /// Backend answering from a `fake-twingate` scenario, the same engine that
+    /// drives the fake `twingate` binaries, for end-to-end state sequences
+    pub struct ScenarioBackend {
+        scenario: Scenario,
+        cursor: Mutex<Cursor>,
+    }
+
+    impl ScenarioBackend {
+        pub fn from_json(text: &str) -> Self {
+            Self {
+                scenario: Scenario::from_json(text).expect("invalid scenario"),
+                cursor: Mutex::new(Cursor::default()),
+            }
+        }
+
+        /// Load one of the scenarios shipped with `fake-twingate`
+        pub fn shipped(name: &str) -> Self {
+            let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("fake-twingate/scenarios")
+                .join(name);
+            Self {
+                scenario: Scenario::load(&path).expect("invalid scenario"),
+                cursor: Mutex::new(Cursor::default()),
+            }
+        }
+
+        pub fn cursor(&self) -> Cursor {
+            *self.cursor.lock().unwrap()
+        }
+
+        fn invoke(&self, program: Program, args: &[&str], elevated: bool) -> Result<CommandOutput> {
+            let invocation = self
+                .scenario
+                .run(&mut self.cursor.lock().unwrap(), program, args);
+
+            if elevated && invocation.code != 0 {
+                return Err(TwingateError::command_failed(
+                    format!("pkexec twingate {}", args.join(" ")),
+                    invocation.code,
+                    invocation.stderr,
+                ));
+            }
+
+            Ok(CommandOutput {
+                code: Some(invocation.code),
+                stdout: invocation.stdout.into_bytes(),
+                stderr: invocation.stderr.into_bytes(),
+            })
+        }
+    }
+
+    impl TwingateBackend for ScenarioBackend {
+        async fn status(&self) -> Result<CommandOutput> {
+            self.invoke(Program::Twingate, &["status"], false)
+        }
+
+        async fn resources(&self) -> Result<CommandOutput> {
+            self.invoke(Program::Notifier, &["resources"], false)
+        }
+
+        async fn resources_list(&self) -> Result<CommandOutput> {
+            self.invoke(Program::Twingate, &["resources", "list"], false)
+        }
+
+        async fn start(&self) -> Result<CommandOutput> {
+            self.invoke(Program::Twingate, &["start"], true)
+        }
+
+        async fn stop(&self) -> Result<CommandOutput> {
+            self.invoke(Program::Twingate, &["stop"], true)
+        }
+
+        async fn auth(&self, resource_name: Option<&str>) -> Result<CommandOutput> {
+            match resource_name {
+                Some(name) => self.invoke(Program::Twingate, &["auth", name], true),
+                None => self.invoke(Program::Twingate, &["auth"], false),
+            }
+        }
+    }
+
+    /// Run a future on a runtime with paused time, so retry delays elapse instantly
+    pub fn block_on_paused<F: Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .start_paused(true)
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::scripted::{block_on_paused, BackendCall, ScriptedBackend};
+    use super::*;
+    use crate::error::TwingateError;
+
+    #[test]
+    fn test_command_output_stdout_str_invalid_utf8() {
+        let output = CommandOutput {
+            code: Some(0),
+            stdout: vec![0x66, 0xF0],
+            stderr: Vec::new(),
+        };
+        assert!(matches!(output.stdout_str(), Err(TwingateError::InvalidUtf8)));
+    }
+
+    #[test]
+    fn test_command_output_combined_lossy() {
+        let output = CommandOutput {
+            code: Some(1),
+            stdout: b"out".to_vec(),
+            stderr: b"err".to_vec(),
+        };
+        assert_eq!(output.combined_lossy(), "out\nerr");
+    }
+
+    #[test]
+    fn test_scripted_backend_replays_in_order_and_repeats_last() {
+        let backend = ScriptedBackend::new().reply(BackendCall::Status, &["starting", "online"]);
+
+        block_on_paused(async {
+            assert_eq!(backend.status().await.unwrap().stdout, b"starting");
+            assert_eq!(backend.status().await.unwrap().stdout, b"online");
+            assert_eq!(backend.status().await.unwrap().stdout, b"online");
+        });
+
+        assert_eq!(backend.call_count(BackendCall::Status), 3);
+        assert_eq!(backend.call_count(BackendCall::Resources), 0);
+    }
+
+    #[test]
+    fn test_scripted_backend_failures() {
+        let backend = ScriptedBackend::new()
+            .fail(BackendCall::Start, "Permission denied")
+            .reply(BackendCall::Start, &["started"]);
+
+        block_on_paused(async {
+            match backend.start().await {
+                Err(TwingateError::CommandFailed { stderr, .. }) => assert_eq!(stderr, "Permission denied"),
+                other => panic!("Expected CommandFailed, got {:?}", other),
+            }
+            assert!(backend.start().await.is_ok());
+            assert!(backend.stop().await.is_err(), "Unscripted calls should fail");
+        });
+    }
+
+    #[test]
+    fn test_scripted_backend_records_auth_targets() {
+        let backend = ScriptedBackend::new().reply(BackendCall::Auth, &[""]);
+
+        block_on_paused(async {
+            backend.auth(Some("My Server")).await.unwrap();
+            backend.auth(None).await.unwrap();
+        });
+
+        assert_eq!(
+            backend.auth_targets(),
+            vec![Some("My Server".to_string()), None]
+        );
+    }
+
+    #[test]
+    fn test_process_backend_captures_output() {
+        let output = tauri::async_runtime::block_on(ProcessBackend::run("sh", &["-c", "echo out; echo err >&2; exit 3"]))
+            .unwrap();
+        assert_eq!(output.code, Some(3));
+        assert_eq!(output.combined_lossy(), "out\n\nerr\n");
+
+        let missing = tauri::async_runtime::block_on(ProcessBackend::run("twingate-tray-no-such-program", &[]));
+        assert!(matches!(missing, Err(TwingateError::CommandExecutionError { .. })));
+    }
+}
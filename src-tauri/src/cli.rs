@@ -0,0 +1,358 @@
+use crate::aliases::DefaultAliases;
+use crate::backend::{ProcessBackend, TwingateBackend};
+use crate::control::{self, ControlClient, RESOURCES_METHOD, STATUS_METHOD};
+use crate::deep_link::DeepLink;
+use crate::dto::{ResourceDto, StatusDto};
+use crate::error::{Result, TwingateError};
+use crate::network::get_network_data_with_retry;
+use crate::state::AppState;
+use crate::tray::{AUTHENTICATE_ID, COPY_ADDRESS_ID, OPEN_IN_BROWSER_ID, START_SERVICE_ID, STOP_SERVICE_ID};
+use arboard::{Clipboard, SetExtLinux};
+use std::time::{Duration, Instant};
+
+/// Retries when fetching network data without a running tray
+const STANDALONE_MAX_RETRIES: u32 = 3;
+/// How long a standalone `copy` keeps serving the clipboard if no clipboard
+/// manager takes it over; X11 selections vanish when their owner exits
+const CLIPBOARD_HOLD: Duration = Duration::from_secs(10);
+
+const USAGE: &str = "\
+Usage: twingate-tray [COMMAND]
+
+Without a command the tray starts.
+
+Commands:
+  status [--json]     Show the connection status
+  resources [--json]  List the resources you can reach
+  copy <name>         Copy a resource's address to the clipboard
+  open <name>         Open a resource in the browser
+  auth <name>         Authenticate to a resource
+  start               Start the Twingate service
+  stop                Stop the Twingate service
+  help                Show this help
+
+Commands go to the running tray if there is one, otherwise they run the
+Twingate client directly.";
+
+/// A command-line request handled without starting the tray
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Subcommand {
+    Status { json: bool },
+    Resources { json: bool },
+    Copy(String),
+    Open(String),
+    Auth(String),
+    Start,
+    Stop,
+    Help,
+}
+
+impl Subcommand {
+    /// Parse the arguments after the program name.
+    ///
+    /// `None` means the tray should start: there are no arguments, or they are
+    /// `--flags` or `twingate-tray://` links meant for the tray itself.
+    pub fn parse(args: &[String]) -> Option<std::result::Result<Self, String>> {
+        let (command, rest) = args.split_first()?;
+        if (command.starts_with('-') && !matches!(command.as_str(), "-h" | "--help"))
+            || DeepLink::is_link(command)
+        {
+            return None;
+        }
+
+        let json_flag = || match rest {
+            [] => Ok(false),
+            [flag] if flag == "--json" => Ok(true),
+            _ => Err(format!("{} only takes --json", command)),
+        };
+        let resource_name = || match rest {
+            [name] => Ok(name.clone()),
+            _ => Err(format!("{} takes one resource name", command)),
+        };
+        let no_args = |subcommand| match rest {
+            [] => Ok(subcommand),
+            _ => Err(format!("{} takes no arguments", command)),
+        };
+
+        Some(match command.as_str() {
+            "status" => json_flag().map(|json| Subcommand::Status { json }),
+            "resources" => json_flag().map(|json| Subcommand::Resources { json }),
+            "copy" => resource_name().map(Subcommand::Copy),
+            "open" => resource_name().map(Subcommand::Open),
+            "auth" => resource_name().map(Subcommand::Auth),
+            "start" => no_args(Subcommand::Start),
+            "stop" => no_args(Subcommand::Stop),
+            "help" | "-h" | "--help" => Ok(Subcommand::Help),
+            _ => Err(format!("unknown command '{}'", command)),
+        })
+    }
+}
+
+/// Look a resource up by exact name, then id, then case-insensitive name
+pub fn find_resource<'a>(resources: &'a [ResourceDto], query: &str) -> Result<&'a ResourceDto> {
+    if let Some(resource) = resources.iter().find(|r| r.name == query || r.id == query) {
+        return Ok(resource);
+    }
+
+    let mut matches = resources.iter().filter(|r| r.name.eq_ignore_ascii_case(query));
+    match (matches.next(), matches.next()) {
+        (Some(resource), None) => Ok(resource),
+        _ => Err(TwingateError::resource_not_found(query)),
+    }
+}
+
+pub fn format_status(status: &StatusDto) -> String {
+    let mut lines = vec![format!("Status: {}", status.status.as_str())];
+    if let Some(email) = &status.user_email {
+        lines.push(format!("User: {}", email));
+        lines.push(format!("Resources: {}", status.resource_count));
+        let security = if status.security_enabled { "on" } else { "off" };
+        lines.push(format!("Internet Security: {}", security));
+    }
+    if let Some(url) = &status.auth_url {
+        lines.push(format!("Sign in at: {}", url));
+    }
+    if let Some(error) = &status.error {
+        lines.push(format!("Error: {}", error));
+    }
+    lines.join("\n")
+}
+
+/// One resource per line: name, then the address that `copy` would copy
+pub fn format_resources(resources: &[ResourceDto]) -> String {
+    let width = resources.iter().map(|r| r.name.chars().count()).max().unwrap_or(0);
+    resources
+        .iter()
+        .map(|r| format!("{:width$}  {}", r.name, r.address, width = width))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Where subcommands are carried out
+enum Target {
+    Tray(ControlClient),
+    Standalone(ProcessBackend),
+}
+
+impl Target {
+    async fn connect() -> Self {
+        if let Some(path) = control::socket_path() {
+            match ControlClient::connect(&path).await {
+                Ok(client) => return Target::Tray(client),
+                Err(e) => log::debug!("No running tray at {}: {}", path.display(), e),
+            }
+        }
+        Target::Standalone(ProcessBackend)
+    }
+
+    async fn status(&mut self) -> Result<StatusDto> {
+        match self {
+            Target::Tray(client) => Ok(serde_json::from_value(client.call(STATUS_METHOD, None).await?)?),
+            Target::Standalone(backend) => {
+                let mut state = AppState::new();
+                match get_network_data_with_retry(backend, STANDALONE_MAX_RETRIES).await {
+                    Ok(network) => state.update_network(network),
+                    Err(e) => state.set_error(e.to_string()),
+                }
+                Ok(StatusDto::from_state(&state))
+            }
+        }
+    }
+
+    async fn resources(&mut self) -> Result<Vec<ResourceDto>> {
+        match self {
+            Target::Tray(client) => Ok(serde_json::from_value(client.call(RESOURCES_METHOD, None).await?)?),
+            Target::Standalone(backend) => {
+                let network = get_network_data_with_retry(backend, STANDALONE_MAX_RETRIES)
+                    .await?
+                    .ok_or(TwingateError::ServiceNotRunning)?;
+                Ok(ResourceDto::list(&network, &DefaultAliases::load_standalone(), chrono::Utc::now()))
+            }
+        }
+    }
+
+    async fn resource(&mut self, name: &str) -> Result<ResourceDto> {
+        let resources = self.resources().await?;
+        find_resource(&resources, name).cloned()
+    }
+
+    /// Run a tray action, or its standalone equivalent
+    async fn act(&mut self, method: &str, resource: Option<&ResourceDto>) -> Result<()> {
+        match self {
+            Target::Tray(client) => {
+                let params = resource.map(|r| serde_json::json!({ "resource_id": r.id }));
+                client.call(method, params).await?;
+                Ok(())
+            }
+            Target::Standalone(backend) => match (method, resource) {
+                (START_SERVICE_ID, _) => backend.start().await.map(drop),
+                (STOP_SERVICE_ID, _) => backend.stop().await.map(drop),
+                (AUTHENTICATE_ID, Some(r)) => backend.auth(Some(&r.name)).await.map(drop),
+                (COPY_ADDRESS_ID, Some(r)) => {
+                    Clipboard::new()?
+                        .set()
+                        .wait_until(Instant::now() + CLIPBOARD_HOLD)
+                        .text(r.address.clone())?;
+                    Ok(())
+                }
+                (OPEN_IN_BROWSER_ID, Some(r)) => {
+                    let url = r.open_url.as_ref().ok_or_else(|| TwingateError::invalid_resource_id(&r.id))?;
+                    tauri_plugin_opener::open_url(url, None::<&str>)?;
+                    Ok(())
+                }
+                _ => Err(TwingateError::control_error(format!("'{}' needs a running tray", method))),
+            },
+        }
+    }
+}
+
+async fn run_subcommand(subcommand: Subcommand) -> Result<()> {
+    let mut target = Target::connect().await;
+
+    match subcommand {
+        Subcommand::Status { json } => {
+            let status = target.status().await?;
+            match json {
+                true => println!("{}", serde_json::to_string_pretty(&status)?),
+                false => println!("{}", format_status(&status)),
+            }
+        }
+        Subcommand::Resources { json } => {
+            let resources = target.resources().await?;
+            match json {
+                true => println!("{}", serde_json::to_string_pretty(&resources)?),
+                false => println!("{}", format_resources(&resources)),
+            }
+        }
+        Subcommand::Copy(name) => {
+            let resource = target.resource(&name).await?;
+            target.act(COPY_ADDRESS_ID, Some(&resource)).await?;
+            println!("{}", resource.address);
+        }
+        Subcommand::Open(name) => {
+            let resource = target.resource(&name).await?;
+            target.act(OPEN_IN_BROWSER_ID, Some(&resource)).await?;
+            println!("Opened {}", resource.name);
+        }
+        Subcommand::Auth(name) => {
+            let resource = target.resource(&name).await?;
+            target.act(AUTHENTICATE_ID, Some(&resource)).await?;
+            println!("Authenticated to {}", resource.name);
+        }
+        Subcommand::Start => {
+            target.act(START_SERVICE_ID, None).await?;
+            println!("Twingate service started");
+        }
+        Subcommand::Stop => {
+            target.act(STOP_SERVICE_ID, None).await?;
+            println!("Twingate service stopped");
+        }
+        Subcommand::Help => println!("{}", USAGE),
+    }
+    Ok(())
+}
+
+/// Run a subcommand if `args` holds one, returning the process exit code
+pub fn run_cli(args: &[String]) -> Option<i32> {
+    let subcommand = match Subcommand::parse(args)? {
+        Ok(subcommand) => subcommand,
+        Err(e) => {
+            eprintln!("Error: {}\n\n{}", e, USAGE);
+            return Some(2);
+        }
+    };
+
+    match tauri::async_runtime::block_on(run_subcommand(subcommand)) {
+        Ok(()) => Some(0),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            Some(1)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dto::ConnectionStatus;
+    use crate::test_support::resource_dto;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    fn parse(line: &str) -> Option<std::result::Result<Subcommand, String>> {
+        Subcommand::parse(&args(line))
+    }
+
+    #[test]
+    fn test_parse_subcommands() {
+        assert_eq!(parse("status"), Some(Ok(Subcommand::Status { json: false })));
+        assert_eq!(parse("resources --json"), Some(Ok(Subcommand::Resources { json: true })));
+        assert_eq!(parse("copy Database"), Some(Ok(Subcommand::Copy("Database".to_string()))));
+        assert_eq!(parse("stop"), Some(Ok(Subcommand::Stop)));
+        assert_eq!(parse("--help"), Some(Ok(Subcommand::Help)));
+    }
+
+    #[test]
+    fn test_parse_starts_tray_without_subcommand() {
+        assert_eq!(parse(""), None);
+        assert_eq!(parse("--refresh"), None);
+        assert_eq!(parse("twingate-tray://open/Wiki"), None);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(parse("status --yaml"), Some(Err(_))));
+        assert!(matches!(parse("open"), Some(Err(_))));
+        assert!(matches!(parse("auth a b"), Some(Err(_))));
+        assert!(matches!(parse("start now"), Some(Err(_))));
+        assert_eq!(parse("reboot"), Some(Err("unknown command 'reboot'".to_string())));
+    }
+
+    #[test]
+    fn test_find_resource() {
+        let resources = vec![resource_dto("r1", "Database"), resource_dto("r2", "Wiki"), resource_dto("r3", "wiki")];
+
+        assert_eq!(find_resource(&resources, "Database").unwrap().id, "r1");
+        assert_eq!(find_resource(&resources, "database").unwrap().id, "r1");
+        assert_eq!(find_resource(&resources, "r2").unwrap().id, "r2");
+        assert_eq!(find_resource(&resources, "wiki").unwrap().id, "r3");
+        assert!(matches!(
+            find_resource(&resources, "WIKI"),
+            Err(TwingateError::ResourceNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_format_status() {
+        let status = StatusDto {
+            status: ConnectionStatus::Connected,
+            user_email: Some("user@example.com".to_string()),
+            resource_count: 2,
+            security_enabled: false,
+            auth_url: None,
+            error: None,
+        };
+        assert_eq!(
+            format_status(&status),
+            "Status: connected\nUser: user@example.com\nResources: 2\nInternet Security: off"
+        );
+
+        let failed = StatusDto {
+            status: ConnectionStatus::Error,
+            user_email: None,
+            resource_count: 0,
+            security_enabled: false,
+            auth_url: None,
+            error: Some("Service requires authentication".to_string()),
+        };
+        assert_eq!(format_status(&failed), "Status: error\nError: Service requires authentication");
+    }
+
+    #[test]
+    fn test_format_resources() {
+        let resources = vec![resource_dto("db", "Database"), resource_dto("wiki", "Wiki")];
+        assert_eq!(format_resources(&resources), "Database  db.internal\nWiki      wiki.internal");
+    }
+}
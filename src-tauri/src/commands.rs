@@ -1,48 +1,94 @@
+use crate::dto::{ResourceDto, StatusDto};
+use crate::error::Result;
+use crate::journal::{ensure_following, ClientLogs, JournalEntry, LogFilter};
+use crate::launcher;
+use crate::managers::{NetworkDataManager, StateManager};
+use crate::state::ServiceStatus;
+use crate::tray::MenuAction;
+use std::time::Duration;
+use tauri::{AppHandle, State};
+
+/// How long fetched network data answers queries before it is refreshed
+const NETWORK_CACHE_DURATION: Duration = Duration::from_secs(30);
+
+/// Current connection state, refreshed first if the cached data is stale
 #[tauri::command]
-pub fn greet(name: &str) -> String {
-    format!("Hello, {}! You've been greeted from Rust!", name)
+pub async fn get_status(app_handle: AppHandle) -> Result<StatusDto> {
+    let authenticating = StateManager::with_state(&app_handle, |state| {
+        matches!(state.service_status(), ServiceStatus::Authenticating(_))
+    });
+    if !authenticating {
+        let network_manager = NetworkDataManager::new(&app_handle, NETWORK_CACHE_DURATION);
+        if let Err(e) = network_manager.get_cached_or_refresh().await {
+            log::warn!("Reporting cached status, refresh failed: {}", e);
+        }
+    }
+
+    Ok(StateManager::with_state(&app_handle, StatusDto::from_state))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Resources shown in the tray menu; fails when the service is not running
+#[tauri::command]
+pub async fn list_resources(app_handle: AppHandle) -> Result<Vec<ResourceDto>> {
+    let network_manager = NetworkDataManager::new(&app_handle, NETWORK_CACHE_DURATION);
+    let network = network_manager.get_network_or_error().await?;
+    Ok(StateManager::with_state(&app_handle, |state| {
+        ResourceDto::list(&network, state.default_aliases(), chrono::Utc::now())
+    }))
+}
 
-    #[test]
-    fn test_greet_with_name() {
-        let result = greet("Alice");
-        assert_eq!(result, "Hello, Alice! You've been greeted from Rust!");
-    }
+#[tauri::command]
+pub async fn start_service(app_handle: AppHandle) -> Result<()> {
+    crate::handle_menu_action(&app_handle, MenuAction::StartService).await
+}
 
-    #[test]
-    fn test_greet_with_empty_string() {
-        let result = greet("");
-        assert_eq!(result, "Hello, ! You've been greeted from Rust!");
-    }
+#[tauri::command]
+pub async fn stop_service(app_handle: AppHandle) -> Result<()> {
+    crate::handle_menu_action(&app_handle, MenuAction::StopService).await
+}
 
-    #[test]
-    fn test_greet_with_special_characters() {
-        let result = greet("José & María");
-        assert_eq!(result, "Hello, José & María! You've been greeted from Rust!");
-    }
+#[tauri::command]
+pub async fn authenticate_resource(app_handle: AppHandle, resource_id: String) -> Result<()> {
+    crate::handle_menu_action(&app_handle, MenuAction::Authenticate(resource_id)).await
+}
 
-    #[test]
-    fn test_greet_with_unicode() {
-        let result = greet("用户");
-        assert_eq!(result, "Hello, 用户! You've been greeted from Rust!");
-    }
+#[tauri::command]
+pub async fn copy_address(app_handle: AppHandle, resource_id: String) -> Result<()> {
+    crate::handle_menu_action(&app_handle, MenuAction::CopyAddress(resource_id)).await
+}
 
-    #[test]
-    fn test_greet_with_numbers() {
-        let result = greet("User123");
-        assert_eq!(result, "Hello, User123! You've been greeted from Rust!");
-    }
+/// Open the resource in the browser, like its "Open in Browser..." menu entry
+#[tauri::command]
+pub async fn open_resource(app_handle: AppHandle, resource_id: String) -> Result<()> {
+    crate::handle_menu_action(&app_handle, MenuAction::OpenInBrowser(resource_id)).await
+}
 
-    #[test]
-    fn test_greet_return_type() {
-        let result = greet("test");
-        assert!(result.is_ascii());
-        assert!(result.contains("Hello"));
-        assert!(result.contains("test"));
-        assert!(result.contains("Rust"));
-    }
+/// Resources matching the launcher's query, best match first
+#[tauri::command]
+pub async fn search_resources(app_handle: AppHandle, query: String) -> Result<Vec<ResourceDto>> {
+    let network_manager = NetworkDataManager::new(&app_handle, NETWORK_CACHE_DURATION);
+    let network = network_manager.get_network_or_error().await?;
+    Ok(StateManager::with_state(&app_handle, |state| {
+        launcher::search(&network, state.default_aliases(), &query, chrono::Utc::now())
+    }))
+}
+
+#[tauri::command]
+pub fn hide_launcher(app_handle: AppHandle) -> Result<()> {
+    launcher::hide_launcher_window(&app_handle)
+}
+
+/// Start tailing the client journal if needed and apply the window's filter.
+///
+/// Returns the buffered entries matching `filter`; later ones arrive as
+/// `client-log` events.
+#[tauri::command]
+pub fn follow_client_logs(
+    app_handle: AppHandle,
+    logs: State<'_, ClientLogs>,
+    filter: LogFilter,
+) -> Result<Vec<JournalEntry>> {
+    let entries = logs.set_filter(filter);
+    ensure_following(&app_handle)?;
+    Ok(entries)
 }
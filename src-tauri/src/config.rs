@@ -0,0 +1,343 @@
+use crate::error::{Result, TwingateError};
+use crate::grouping::{GroupBy, GroupRule};
+use crate::icons::TrayIconStyle;
+use crate::launch::LaunchTemplate;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+const CONFIG_FILE_NAME: &str = "config.json";
+const DEFAULT_POLL_INTERVAL_SECS: u64 = 30;
+const MIN_POLL_INTERVAL_SECS: u64 = 5;
+const DEFAULT_AUTH_EXPIRY_WARNING_MINS: u64 = 60;
+const DEFAULT_MAX_MENU_ITEMS: usize = 25;
+const DEFAULT_RECENT_RESOURCES: usize = 5;
+const DEFAULT_PROBE_PORTS: [u16; 3] = [443, 80, 22];
+const MIN_PROBE_INTERVAL_SECS: u64 = 30;
+/// Room for at least one entry next to "More..."
+const MIN_MAX_MENU_ITEMS: usize = 2;
+pub const DEFAULT_SERVICE_UNIT: &str = "twingate.service";
+
+/// User settings read from `config.json` in the app config directory
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    /// Seconds between background status polls; 0 disables polling
+    pub poll_interval_secs: u64,
+    /// Show a desktop notification when resources are added or removed
+    pub notify_resource_changes: bool,
+    /// Minutes before a resource's authentication expires to warn about it;
+    /// 0 disables expiry and re-authentication notifications
+    pub auth_expiry_warning_mins: u64,
+    /// Tray icon set: "color", or "symbolic-light" / "symbolic-dark" for
+    /// monochrome icons on dark / light panels
+    pub tray_icon_style: TrayIconStyle,
+    /// systemd unit watched for start/stop/crash; empty disables watching
+    pub service_unit: String,
+    /// Accept JSON-RPC requests on `$XDG_RUNTIME_DIR/twingate-tray.sock`
+    pub control_socket: bool,
+    /// Provide `org.twingate.Tray1` on the session bus
+    pub dbus_service: bool,
+    /// Group resources into submenus: "none", "type", "domain" or "rules"
+    pub group_by: GroupBy,
+    /// Groups for `group_by: "rules"`, tried in order
+    pub group_rules: Vec<GroupRule>,
+    /// Entries per menu level before the rest move to a "More..." submenu;
+    /// 0 shows every entry
+    pub max_menu_items: usize,
+    /// Recently used resources listed above the others; 0 hides the section
+    /// and stops recording uses
+    pub recent_resources: usize,
+    /// Extra commands in resource submenus, e.g. SSH or RDP for some resources
+    pub launch_actions: Vec<LaunchTemplate>,
+    /// TCP ports "Test Connection" tries; empty uses the defaults
+    pub probe_ports: Vec<u16>,
+    /// Seconds between connection tests of pinned resources; 0 disables them
+    pub probe_interval_secs: u64,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval_secs: DEFAULT_POLL_INTERVAL_SECS,
+            notify_resource_changes: true,
+            auth_expiry_warning_mins: DEFAULT_AUTH_EXPIRY_WARNING_MINS,
+            tray_icon_style: TrayIconStyle::default(),
+            service_unit: DEFAULT_SERVICE_UNIT.to_string(),
+            control_socket: true,
+            dbus_service: true,
+            group_by: GroupBy::default(),
+            group_rules: Vec::new(),
+            max_menu_items: DEFAULT_MAX_MENU_ITEMS,
+            recent_resources: DEFAULT_RECENT_RESOURCES,
+            launch_actions: Vec::new(),
+            probe_ports: DEFAULT_PROBE_PORTS.to_vec(),
+            probe_interval_secs: 0,
+        }
+    }
+}
+
+impl AppConfig {
+    /// Load the config for this app, falling back to defaults if it is missing or invalid
+    pub fn load(app_handle: &AppHandle) -> Self {
+        let Some(path) = config_path(app_handle) else {
+            log::warn!("Could not resolve config directory, using default settings");
+            return Self::default();
+        };
+
+        match Self::load_from(&path) {
+            Ok(config) => config,
+            Err(e) => {
+                log::warn!("Failed to load {}: {} - using default settings", path.display(), e);
+                eprintln!("Warning: Failed to load {}: {}", path.display(), e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Read a config file; a missing file yields the defaults
+    pub fn load_from(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Ok(serde_json::from_str(&text)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                log::debug!("No config file at {}, using defaults", path.display());
+                Ok(Self::default())
+            }
+            Err(e) => Err(TwingateError::config_error(format!(
+                "cannot read {}: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+
+    /// Background poll interval, or `None` when polling is disabled
+    pub fn poll_interval(&self) -> Option<Duration> {
+        match self.poll_interval_secs {
+            0 => None,
+            secs => Some(Duration::from_secs(secs.max(MIN_POLL_INTERVAL_SECS))),
+        }
+    }
+
+    /// How long before auth expiry to warn, or `None` when warnings are disabled
+    pub fn auth_expiry_warning(&self) -> Option<Duration> {
+        match self.auth_expiry_warning_mins {
+            0 => None,
+            mins => Some(Duration::from_secs(mins * 60)),
+        }
+    }
+
+    /// Cap on entries per menu level, or `None` when every entry is shown
+    pub fn max_menu_items(&self) -> Option<usize> {
+        match self.max_menu_items {
+            0 => None,
+            max => Some(max.max(MIN_MAX_MENU_ITEMS)),
+        }
+    }
+
+    /// Ports to test connections on
+    pub fn probe_ports(&self) -> Vec<u16> {
+        match self.probe_ports.as_slice() {
+            [] => DEFAULT_PROBE_PORTS.to_vec(),
+            ports => ports.to_vec(),
+        }
+    }
+
+    /// Interval between tests of pinned resources, or `None` when disabled
+    pub fn probe_interval(&self) -> Option<Duration> {
+        match self.probe_interval_secs {
+            0 => None,
+            secs => Some(Duration::from_secs(secs.max(MIN_PROBE_INTERVAL_SECS))),
+        }
+    }
+
+    /// systemd unit to watch, or `None` when watching is disabled
+    pub fn service_unit(&self) -> Option<&str> {
+        match self.service_unit.trim() {
+            "" => None,
+            unit => Some(unit),
+        }
+    }
+}
+
+/// Read a JSON file the tray keeps for itself; a missing file yields the default
+pub fn load_json<T: DeserializeOwned + Default>(path: &Path) -> Result<T> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => Ok(serde_json::from_str(&text)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(T::default()),
+        Err(e) => Err(TwingateError::config_error(format!(
+            "cannot read {}: {}",
+            path.display(),
+            e
+        ))),
+    }
+}
+
+/// Write a JSON file, replacing it in one step so a crash cannot truncate it
+pub fn save_json<T: Serialize>(value: &T, path: &Path) -> Result<()> {
+    let write = || -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let partial = path.with_extension("json.tmp");
+        std::fs::write(&partial, serde_json::to_string_pretty(value)?)?;
+        std::fs::rename(&partial, path)
+    };
+    write().map_err(|e| TwingateError::config_error(format!("cannot write {}: {}", path.display(), e)))
+}
+
+/// Location of `config.json` under the XDG config directory
+pub fn config_path(app_handle: &AppHandle) -> Option<PathBuf> {
+    app_handle
+        .path()
+        .app_config_dir()
+        .ok()
+        .map(|dir| dir.join(CONFIG_FILE_NAME))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_default_config() {
+        let config = AppConfig::default();
+        assert_eq!(config.poll_interval_secs, 30);
+        assert_eq!(config.poll_interval(), Some(Duration::from_secs(30)));
+        assert!(config.notify_resource_changes);
+        assert_eq!(config.auth_expiry_warning(), Some(Duration::from_secs(3600)));
+        assert_eq!(config.tray_icon_style, TrayIconStyle::Color);
+        assert_eq!(config.service_unit(), Some("twingate.service"));
+        assert!(config.control_socket);
+        assert!(config.dbus_service);
+        assert_eq!(config.group_by, GroupBy::None);
+        assert!(config.group_rules.is_empty());
+        assert_eq!(config.max_menu_items(), Some(25));
+        assert_eq!(config.recent_resources, 5);
+        assert_eq!(config.probe_ports(), vec![443, 80, 22]);
+        assert_eq!(config.probe_interval(), None);
+    }
+
+    #[test]
+    fn test_load_missing_file_uses_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = AppConfig::load_from(&dir.path().join("config.json")).unwrap();
+        assert_eq!(config, AppConfig::default());
+    }
+
+    #[test]
+    fn test_load_partial_file_fills_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        fs::write(&path, "{}").unwrap();
+        assert_eq!(AppConfig::load_from(&path).unwrap(), AppConfig::default());
+
+        fs::write(&path, r#"{ "poll_interval_secs": 120, "unknown": true }"#).unwrap();
+        let config = AppConfig::load_from(&path).unwrap();
+        assert_eq!(config.poll_interval_secs, 120);
+        assert!(config.notify_resource_changes);
+
+        fs::write(&path, r#"{ "tray_icon_style": "symbolic-dark" }"#).unwrap();
+        let config = AppConfig::load_from(&path).unwrap();
+        assert_eq!(config.tray_icon_style, TrayIconStyle::SymbolicDark);
+
+        fs::write(
+            &path,
+            r#"{ "group_by": "rules", "group_rules": [{ "group": "Production", "pattern": "\\.prod\\." }] }"#,
+        )
+        .unwrap();
+        let config = AppConfig::load_from(&path).unwrap();
+        assert_eq!(config.group_by, GroupBy::Rules);
+        assert_eq!(
+            config.group_rules,
+            vec![GroupRule {
+                group: "Production".to_string(),
+                pattern: r"\.prod\.".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_load_invalid_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        fs::write(&path, "{ not json").unwrap();
+        assert!(matches!(
+            AppConfig::load_from(&path),
+            Err(TwingateError::JsonError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_poll_interval() {
+        let with_interval = |poll_interval_secs| AppConfig {
+            poll_interval_secs,
+            ..AppConfig::default()
+        };
+
+        let disabled = with_interval(0);
+        assert_eq!(disabled.poll_interval(), None);
+
+        let too_fast = with_interval(1);
+        assert_eq!(too_fast.poll_interval(), Some(Duration::from_secs(5)));
+
+        let slow = with_interval(300);
+        assert_eq!(slow.poll_interval(), Some(Duration::from_secs(300)));
+    }
+
+    #[test]
+    fn test_auth_expiry_warning() {
+        let with_warning = |auth_expiry_warning_mins| AppConfig {
+            auth_expiry_warning_mins,
+            ..AppConfig::default()
+        };
+
+        assert_eq!(with_warning(0).auth_expiry_warning(), None);
+        assert_eq!(with_warning(15).auth_expiry_warning(), Some(Duration::from_secs(900)));
+    }
+
+    #[test]
+    fn test_max_menu_items() {
+        let with_max = |max_menu_items| AppConfig {
+            max_menu_items,
+            ..AppConfig::default()
+        };
+
+        assert_eq!(with_max(0).max_menu_items(), None);
+        assert_eq!(with_max(1).max_menu_items(), Some(2));
+        assert_eq!(with_max(40).max_menu_items(), Some(40));
+    }
+
+    #[test]
+    fn test_probe_settings() {
+        let config = AppConfig {
+            probe_ports: vec![],
+            probe_interval_secs: 5,
+            ..AppConfig::default()
+        };
+        assert_eq!(config.probe_ports(), vec![443, 80, 22]);
+        assert_eq!(config.probe_interval(), Some(Duration::from_secs(30)));
+
+        let config = AppConfig {
+            probe_ports: vec![5432],
+            probe_interval_secs: 600,
+            ..AppConfig::default()
+        };
+        assert_eq!(config.probe_ports(), vec![5432]);
+        assert_eq!(config.probe_interval(), Some(Duration::from_secs(600)));
+    }
+
+    #[test]
+    fn test_service_unit() {
+        let with_unit = |unit: &str| AppConfig {
+            service_unit: unit.to_string(),
+            ..AppConfig::default()
+        };
+
+        assert_eq!(with_unit("").service_unit(), None);
+        assert_eq!(with_unit("  ").service_unit(), None);
+        assert_eq!(with_unit("twingate-custom.service").service_unit(), Some("twingate-custom.service"));
+    }
+}
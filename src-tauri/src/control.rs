@@ -0,0 +1,497 @@
+use crate::commands::{get_status, list_resources};
+use crate::error::{Result, TwingateError};
+use crate::tray::{
+    MenuAction, AUTHENTICATE_ID, CLEAR_DEFAULT_ALIAS_ID, COPY_ADDRESS_ID, COPY_ALIAS_ID, COPY_AUTH_URL_ID,
+    OPEN_ALIAS_ID, OPEN_AUTH_URL_ID, OPEN_IN_BROWSER_ID, PIN_RESOURCE_ID, QUIT_ID, REFRESH_ID, SHOW_CLIENT_LOGS_ID,
+    SHOW_LAUNCHER_ID, SET_DEFAULT_ALIAS_ID, START_SERVICE_ID, STOP_SERVICE_ID, TEST_CONNECTION_ID, UNPIN_RESOURCE_ID,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::future::Future;
+use std::io;
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::AppHandle;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{UnixListener, UnixStream};
+
+const SOCKET_FILE_NAME: &str = "twingate-tray.sock";
+const JSONRPC_VERSION: &str = "2.0";
+
+pub const STATUS_METHOD: &str = "status";
+pub const RESOURCES_METHOD: &str = "resources";
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// Any failure of the tray itself, e.g. the service not running
+const OPERATION_FAILED: i64 = -32000;
+
+/// What a control request asks the tray to do
+#[derive(Debug, Clone)]
+pub enum Call {
+    Status,
+    Resources,
+    Action(MenuAction),
+}
+
+#[derive(Deserialize)]
+struct ResourceParams {
+    resource_id: String,
+}
+
+#[derive(Deserialize)]
+struct AliasParams {
+    resource_id: String,
+    address: String,
+}
+
+impl Call {
+    /// Map a method name to a call; actions use their menu ids as method names
+    pub fn parse(method: &str, params: Option<Value>) -> std::result::Result<Self, RpcError> {
+        let resource_id = || -> std::result::Result<String, RpcError> {
+            let params = params.clone().unwrap_or(Value::Null);
+            serde_json::from_value::<ResourceParams>(params)
+                .map(|p| p.resource_id)
+                .map_err(|e| RpcError::new(INVALID_PARAMS, format!("{} expects {{\"resource_id\": ...}}: {}", method, e)))
+        };
+
+        let alias = || -> std::result::Result<(String, String), RpcError> {
+            let params = params.clone().unwrap_or(Value::Null);
+            serde_json::from_value::<AliasParams>(params)
+                .map(|p| (p.resource_id, p.address))
+                .map_err(|e| {
+                    RpcError::new(
+                        INVALID_PARAMS,
+                        format!("{} expects {{\"resource_id\": ..., \"address\": ...}}: {}", method, e),
+                    )
+                })
+        };
+
+        let action = match method {
+            STATUS_METHOD => return Ok(Call::Status),
+            RESOURCES_METHOD => return Ok(Call::Resources),
+            START_SERVICE_ID => MenuAction::StartService,
+            STOP_SERVICE_ID => MenuAction::StopService,
+            COPY_ADDRESS_ID => MenuAction::CopyAddress(resource_id()?),
+            AUTHENTICATE_ID => MenuAction::Authenticate(resource_id()?),
+            OPEN_IN_BROWSER_ID => MenuAction::OpenInBrowser(resource_id()?),
+            PIN_RESOURCE_ID => MenuAction::PinResource(resource_id()?),
+            UNPIN_RESOURCE_ID => MenuAction::UnpinResource(resource_id()?),
+            COPY_ALIAS_ID => {
+                let (resource_id, address) = alias()?;
+                MenuAction::CopyAlias(resource_id, address)
+            }
+            OPEN_ALIAS_ID => {
+                let (resource_id, address) = alias()?;
+                MenuAction::OpenAlias(resource_id, address)
+            }
+            SET_DEFAULT_ALIAS_ID => {
+                let (resource_id, address) = alias()?;
+                MenuAction::SetDefaultAlias(resource_id, address)
+            }
+            CLEAR_DEFAULT_ALIAS_ID => MenuAction::ClearDefaultAlias(resource_id()?),
+            TEST_CONNECTION_ID => MenuAction::TestConnection(resource_id()?),
+            OPEN_AUTH_URL_ID => MenuAction::OpenAuthUrl,
+            COPY_AUTH_URL_ID => MenuAction::CopyAuthUrl,
+            SHOW_CLIENT_LOGS_ID => MenuAction::ShowClientLogs,
+            SHOW_LAUNCHER_ID => MenuAction::ShowLauncher,
+            REFRESH_ID => MenuAction::Refresh,
+            QUIT_ID => MenuAction::Quit,
+            _ => return Err(RpcError::new(METHOD_NOT_FOUND, format!("Unknown method '{}'", method))),
+        };
+        Ok(Call::Action(action))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Request {
+    jsonrpc: String,
+    /// Absent for notifications, which get no response
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Outcome {
+    Result { result: Value },
+    Error { error: RpcError },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Response {
+    pub jsonrpc: String,
+    pub id: Value,
+    #[serde(flatten)]
+    pub outcome: Outcome,
+}
+
+impl Response {
+    fn new(id: Value, outcome: Outcome) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id,
+            outcome,
+        }
+    }
+
+    fn error(id: Value, error: RpcError) -> Self {
+        Self::new(id, Outcome::Error { error })
+    }
+}
+
+/// Answer one line of JSON-RPC; notifications yield no response
+pub async fn handle_line<H, F>(line: &str, handler: &H) -> Option<Response>
+where
+    H: Fn(Call) -> F,
+    F: Future<Output = Result<Value>>,
+{
+    let request: Request = match serde_json::from_str::<Value>(line) {
+        Ok(value) => match serde_json::from_value(value) {
+            Ok(request) => request,
+            Err(e) => return Some(Response::error(Value::Null, RpcError::new(INVALID_REQUEST, e.to_string()))),
+        },
+        Err(e) => return Some(Response::error(Value::Null, RpcError::new(PARSE_ERROR, e.to_string()))),
+    };
+
+    let id = request.id.clone().unwrap_or(Value::Null);
+    let outcome = if request.jsonrpc != JSONRPC_VERSION {
+        Outcome::Error {
+            error: RpcError::new(INVALID_REQUEST, format!("Unsupported jsonrpc version '{}'", request.jsonrpc)),
+        }
+    } else {
+        match Call::parse(&request.method, request.params) {
+            Ok(call) => match handler(call).await {
+                Ok(result) => Outcome::Result { result },
+                Err(e) => Outcome::Error {
+                    error: RpcError::new(OPERATION_FAILED, e.to_string()),
+                },
+            },
+            Err(error) => Outcome::Error { error },
+        }
+    };
+
+    request.id.map(|_| Response::new(id, outcome))
+}
+
+/// Socket path under `$XDG_RUNTIME_DIR`, or `None` if it is not set
+pub fn socket_path() -> Option<PathBuf> {
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| PathBuf::from(dir).join(SOCKET_FILE_NAME))
+}
+
+/// Bind the socket, replacing one left behind by a previous run.
+///
+/// The socket is made owner-only; connections from other users are also
+/// refused by peer credentials in case they got in before the chmod.
+pub fn bind(path: &Path) -> io::Result<UnixListener> {
+    match std::fs::remove_file(path) {
+        Ok(()) => log::debug!("Removed stale control socket {}", path.display()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+
+    let listener = UnixListener::bind(path)?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    Ok(listener)
+}
+
+fn current_uid() -> io::Result<u32> {
+    Ok(std::fs::metadata("/proc/self")?.uid())
+}
+
+/// Accept connections until the listener fails, answering each line with `handler`
+pub async fn serve<H, F>(listener: UnixListener, handler: H) -> io::Result<()>
+where
+    H: Fn(Call) -> F + Send + Sync + 'static,
+    F: Future<Output = Result<Value>> + Send,
+{
+    let uid = current_uid()?;
+    let handler = Arc::new(handler);
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        match stream.peer_cred() {
+            Ok(cred) if cred.uid() == uid => {}
+            Ok(cred) => {
+                log::warn!("Refusing control connection from uid {}", cred.uid());
+                continue;
+            }
+            Err(e) => {
+                log::warn!("Refusing control connection without credentials: {}", e);
+                continue;
+            }
+        }
+
+        let handler = handler.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = handle_connection(stream, handler.as_ref()).await {
+                log::debug!("Control connection closed: {}", e);
+            }
+        });
+    }
+}
+
+async fn handle_connection<H, F>(stream: UnixStream, handler: &H) -> io::Result<()>
+where
+    H: Fn(Call) -> F,
+    F: Future<Output = Result<Value>>,
+{
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = handle_line(&line, handler).await {
+            let mut json = serde_json::to_string(&response)?;
+            json.push('\n');
+            writer.write_all(json.as_bytes()).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Connection to a running tray's control socket
+pub struct ControlClient {
+    lines: Lines<BufReader<OwnedReadHalf>>,
+    writer: OwnedWriteHalf,
+    next_id: u64,
+}
+
+impl ControlClient {
+    /// Connect to the socket; fails if no tray is listening on it
+    pub async fn connect(path: &Path) -> io::Result<Self> {
+        let (reader, writer) = UnixStream::connect(path).await?.into_split();
+        Ok(Self {
+            lines: BufReader::new(reader).lines(),
+            writer,
+            next_id: 1,
+        })
+    }
+
+    /// Send one request and wait for its result
+    pub async fn call(&mut self, method: &str, params: Option<Value>) -> Result<Value> {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let mut request = serde_json::json!({ "jsonrpc": JSONRPC_VERSION, "id": id, "method": method });
+        if let Some(params) = params {
+            request["params"] = params;
+        }
+        let mut line = request.to_string();
+        line.push('\n');
+
+        let transport_error = |e: io::Error| TwingateError::control_error(e.to_string());
+        self.writer.write_all(line.as_bytes()).await.map_err(transport_error)?;
+        let reply = self
+            .lines
+            .next_line()
+            .await
+            .map_err(transport_error)?
+            .ok_or_else(|| TwingateError::control_error("the tray closed the connection"))?;
+
+        match serde_json::from_str::<Response>(&reply)?.outcome {
+            Outcome::Result { result } => Ok(result),
+            Outcome::Error { error } => Err(TwingateError::control_error(error.message)),
+        }
+    }
+}
+
+async fn execute(app_handle: AppHandle, call: Call) -> Result<Value> {
+    log::debug!("Control request: {:?}", call);
+    Ok(match call {
+        Call::Status => serde_json::to_value(get_status(app_handle).await?)?,
+        Call::Resources => serde_json::to_value(list_resources(app_handle).await?)?,
+        Call::Action(action) => {
+            crate::handle_menu_action(&app_handle, action).await?;
+            Value::Null
+        }
+    })
+}
+
+/// Listen for control requests on the user's runtime socket
+pub fn spawn_control_socket(app_handle: AppHandle) {
+    let Some(path) = socket_path() else {
+        log::warn!("XDG_RUNTIME_DIR is not set, control socket disabled");
+        return;
+    };
+
+    tauri::async_runtime::spawn(async move {
+        let listener = match bind(&path) {
+            Ok(listener) => listener,
+            Err(e) => {
+                log::warn!("Cannot listen on {}: {}", path.display(), e);
+                eprintln!("Warning: Control socket unavailable: {}", e);
+                return;
+            }
+        };
+        log::info!("Listening for control requests on {}", path.display());
+
+        let result = serve(listener, move |call| execute(app_handle.clone(), call)).await;
+        if let Err(e) = result {
+            log::warn!("Control socket stopped: {}", e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::TwingateError;
+    use serde_json::json;
+    use tauri::async_runtime::block_on;
+
+    async fn stand_in(call: Call) -> Result<Value> {
+        match call {
+            Call::Status => Ok(json!({ "status": "connected" })),
+            Call::Resources => Ok(json!([])),
+            Call::Action(MenuAction::CopyAddress(id)) => Err(TwingateError::resource_not_found(id)),
+            Call::Action(_) => Ok(Value::Null),
+        }
+    }
+
+    fn respond(line: &str) -> Option<Value> {
+        block_on(handle_line(line, &stand_in)).map(|response| serde_json::to_value(response).unwrap())
+    }
+
+    #[test]
+    fn test_parse_calls() {
+        assert!(matches!(Call::parse("status", None), Ok(Call::Status)));
+        assert!(matches!(
+            Call::parse("start_service", Some(json!({}))),
+            Ok(Call::Action(MenuAction::StartService))
+        ));
+        assert!(matches!(
+            Call::parse("open_in_browser", Some(json!({ "resource_id": "r1" }))),
+            Ok(Call::Action(MenuAction::OpenInBrowser(id))) if id == "r1"
+        ));
+        assert!(matches!(
+            Call::parse("set_default_alias", Some(json!({ "resource_id": "r1", "address": "kb.corp" }))),
+            Ok(Call::Action(MenuAction::SetDefaultAlias(id, address))) if id == "r1" && address == "kb.corp"
+        ));
+        assert_eq!(
+            Call::parse("copy_alias", Some(json!({ "resource_id": "r1" }))).unwrap_err().code,
+            INVALID_PARAMS
+        );
+
+        assert_eq!(Call::parse("copy_address", None).unwrap_err().code, INVALID_PARAMS);
+        assert_eq!(
+            Call::parse("copy_address", Some(json!({ "id": "r1" }))).unwrap_err().code,
+            INVALID_PARAMS
+        );
+        assert_eq!(Call::parse("resource", None).unwrap_err().code, METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn test_handle_line() {
+        let ok = respond(r#"{"jsonrpc":"2.0","id":1,"method":"status"}"#).unwrap();
+        assert_eq!(ok, json!({ "jsonrpc": "2.0", "id": 1, "result": { "status": "connected" } }));
+
+        let action = respond(r#"{"jsonrpc":"2.0","id":"a","method":"stop_service"}"#).unwrap();
+        assert_eq!(action, json!({ "jsonrpc": "2.0", "id": "a", "result": null }));
+
+        let failed = respond(r#"{"jsonrpc":"2.0","id":2,"method":"copy_address","params":{"resource_id":"r9"}}"#)
+            .unwrap();
+        assert_eq!(failed["error"]["code"], OPERATION_FAILED);
+        assert_eq!(failed["error"]["message"], "Resource 'r9' not found");
+
+        let unknown = respond(r#"{"jsonrpc":"2.0","id":3,"method":"reboot"}"#).unwrap();
+        assert_eq!(unknown["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(unknown["id"], 3);
+    }
+
+    #[test]
+    fn test_handle_invalid_lines() {
+        let garbage = respond("{ not json").unwrap();
+        assert_eq!(garbage["error"]["code"], PARSE_ERROR);
+        assert_eq!(garbage["id"], Value::Null);
+
+        let no_method = respond(r#"{"jsonrpc":"2.0","id":1}"#).unwrap();
+        assert_eq!(no_method["error"]["code"], INVALID_REQUEST);
+
+        let old_version = respond(r#"{"jsonrpc":"1.0","id":1,"method":"status"}"#).unwrap();
+        assert_eq!(old_version["error"]["code"], INVALID_REQUEST);
+
+        assert_eq!(respond(r#"{"jsonrpc":"2.0","method":"stop_service"}"#), None);
+    }
+
+    #[test]
+    fn test_client_calls() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SOCKET_FILE_NAME);
+
+        let (status, failed) = block_on(async {
+            tauri::async_runtime::spawn(serve(bind(&path).unwrap(), stand_in));
+
+            let mut client = ControlClient::connect(&path).await.unwrap();
+            let status = client.call(STATUS_METHOD, None).await.unwrap();
+            let failed = client
+                .call(COPY_ADDRESS_ID, Some(json!({ "resource_id": "r9" })))
+                .await
+                .unwrap_err();
+            (status, failed)
+        });
+
+        assert_eq!(status, json!({ "status": "connected" }));
+        assert_eq!(failed.to_string(), "Control request failed: Resource 'r9' not found");
+    }
+
+    #[test]
+    fn test_client_without_tray() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = block_on(ControlClient::connect(&dir.path().join(SOCKET_FILE_NAME)));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_socket_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SOCKET_FILE_NAME);
+        std::fs::write(&path, "stale").unwrap();
+
+        let response = block_on(async {
+            let listener = bind(&path).unwrap();
+            tauri::async_runtime::spawn(serve(listener, stand_in));
+
+            let mut stream = UnixStream::connect(&path).await.unwrap();
+            stream
+                .write_all(b"{\"jsonrpc\":\"2.0\",\"method\":\"quit\"}\n{\"jsonrpc\":\"2.0\",\"id\":7,\"method\":\"resources\"}\n")
+                .await
+                .unwrap();
+            let mut lines = BufReader::new(stream).lines();
+            lines.next_line().await.unwrap().unwrap()
+        });
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(
+            serde_json::from_str::<Value>(&response).unwrap(),
+            json!({ "jsonrpc": "2.0", "id": 7, "result": [] })
+        );
+    }
+}
@@ -0,0 +1,402 @@
+use crate::dto::{ConnectionStatus, StatusDto};
+use crate::error::Result;
+use crate::events::{NetworkUpdated, NETWORK_UPDATED_EVENT, STATUS_CHANGED_EVENT};
+use crate::managers::StateManager;
+use crate::tray::MenuAction;
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use std::sync::Mutex;
+use tauri::{AppHandle, Listener};
+use tokio::sync::mpsc;
+use zbus::object_server::{InterfaceRef, SignalContext};
+use zbus::{fdo, interface, ConnectionBuilder};
+
+pub const SERVICE_NAME: &str = "org.twingate.Tray1";
+pub const OBJECT_PATH: &str = "/org/twingate/Tray1";
+
+/// What the D-Bus object reads from and asks of the tray
+pub trait TrayHost: Send + Sync + 'static {
+    fn status(&self) -> StatusDto;
+
+    fn run(&self, action: MenuAction) -> impl Future<Output = Result<()>> + Send;
+}
+
+impl TrayHost for AppHandle {
+    fn status(&self) -> StatusDto {
+        StateManager::with_state(self, StatusDto::from_state)
+    }
+
+    async fn run(&self, action: MenuAction) -> Result<()> {
+        crate::handle_menu_action(self, action).await
+    }
+}
+
+/// The `org.twingate.Tray1` object; properties are read live from the host
+pub struct TrayService<H> {
+    host: H,
+}
+
+impl<H: TrayHost> TrayService<H> {
+    pub fn new(host: H) -> Self {
+        Self { host }
+    }
+
+    async fn run(&self, action: MenuAction) -> fdo::Result<()> {
+        self.host.run(action).await.map_err(|e| fdo::Error::Failed(e.to_string()))
+    }
+}
+
+#[interface(name = "org.twingate.Tray1")]
+impl<H: TrayHost> TrayService<H> {
+    async fn start(&self) -> fdo::Result<()> {
+        self.run(MenuAction::StartService).await
+    }
+
+    async fn stop(&self) -> fdo::Result<()> {
+        self.run(MenuAction::StopService).await
+    }
+
+    async fn authenticate(&self, resource_id: String) -> fdo::Result<()> {
+        self.run(MenuAction::Authenticate(resource_id)).await
+    }
+
+    async fn copy_address(&self, resource_id: String) -> fdo::Result<()> {
+        self.run(MenuAction::CopyAddress(resource_id)).await
+    }
+
+    async fn open_resource(&self, resource_id: String) -> fdo::Result<()> {
+        self.run(MenuAction::OpenInBrowser(resource_id)).await
+    }
+
+    async fn refresh(&self) -> fdo::Result<()> {
+        self.run(MenuAction::Refresh).await
+    }
+
+    /// `connected`, `disconnected`, `authenticating` or `error`
+    #[zbus(property)]
+    fn status(&self) -> String {
+        self.host.status().status.as_str().to_string()
+    }
+
+    /// Empty while not connected
+    #[zbus(property)]
+    fn user_email(&self) -> String {
+        self.host.status().user_email.unwrap_or_default()
+    }
+
+    #[zbus(property)]
+    fn resource_count(&self) -> u32 {
+        self.host.status().resource_count.try_into().unwrap_or(u32::MAX)
+    }
+
+    /// Sign-in URL while authenticating, otherwise empty
+    #[zbus(property)]
+    fn auth_url(&self) -> String {
+        self.host.status().auth_url.unwrap_or_default()
+    }
+
+    #[zbus(signal, name = "StatusChanged")]
+    async fn emit_status_changed(ctxt: &SignalContext<'_>, status: &str) -> zbus::Result<()>;
+
+    /// Names of the resources that appeared or disappeared
+    #[zbus(signal, name = "ResourcesChanged")]
+    async fn emit_resources_changed(ctxt: &SignalContext<'_>, added: &[String], removed: &[String]) -> zbus::Result<()>;
+}
+
+/// Tells D-Bus clients about changes to the tray state
+pub struct Publisher<H: TrayHost> {
+    iface: InterfaceRef<TrayService<H>>,
+    last_status: Mutex<ConnectionStatus>,
+}
+
+impl<H: TrayHost> Publisher<H> {
+    pub fn new(iface: InterfaceRef<TrayService<H>>, status: &StatusDto) -> Self {
+        Self {
+            iface,
+            last_status: Mutex::new(status.status),
+        }
+    }
+
+    /// Announce new property values, and `StatusChanged` if the status itself moved
+    pub async fn status_changed(&self, status: &StatusDto) -> zbus::Result<()> {
+        let ctxt = self.iface.signal_context();
+        {
+            let service = self.iface.get().await;
+            service.status_changed(ctxt).await?;
+            service.user_email_changed(ctxt).await?;
+            service.resource_count_changed(ctxt).await?;
+            service.auth_url_changed(ctxt).await?;
+        }
+
+        let moved = {
+            let mut last_status = self.last_status.lock().unwrap();
+            std::mem::replace(&mut *last_status, status.status) != status.status
+        };
+        if moved {
+            TrayService::<H>::emit_status_changed(ctxt, status.status.as_str()).await?;
+        }
+        Ok(())
+    }
+
+    /// Announce `ResourcesChanged` with the resources the update added or
+    /// removed, if any
+    pub async fn network_updated(&self, update: &NetworkUpdated) -> zbus::Result<()> {
+        if update.added.is_empty() && update.removed.is_empty() {
+            return Ok(());
+        }
+        TrayService::<H>::emit_resources_changed(self.iface.signal_context(), &update.added, &update.removed).await
+    }
+}
+
+enum Update {
+    Status(StatusDto),
+    Network(NetworkUpdated),
+}
+
+/// Queue the payload of every `event` as an update
+fn forward<T: DeserializeOwned + 'static>(
+    app_handle: &AppHandle,
+    event: &'static str,
+    updates: mpsc::UnboundedSender<Update>,
+    wrap: fn(T) -> Update,
+) {
+    app_handle.listen_any(event, move |e| match serde_json::from_str(e.payload()) {
+        Ok(payload) => {
+            let _ = updates.send(wrap(payload));
+        }
+        Err(e) => log::warn!("Cannot forward {} event to D-Bus: {}", event, e),
+    });
+}
+
+/// Own `org.twingate.Tray1` on the session bus and keep its clients up to date
+pub fn spawn_dbus_service(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let connection = match ConnectionBuilder::session()
+            .and_then(|builder| builder.name(SERVICE_NAME))
+            .and_then(|builder| builder.serve_at(OBJECT_PATH, TrayService::new(app_handle.clone())))
+        {
+            Ok(builder) => builder.build().await,
+            Err(e) => Err(e),
+        };
+        let connection = match connection {
+            Ok(connection) => connection,
+            Err(e) => {
+                log::warn!("Cannot provide {} on the session bus: {}", SERVICE_NAME, e);
+                eprintln!("Warning: D-Bus service unavailable: {}", e);
+                return;
+            }
+        };
+
+        let iface = match connection
+            .object_server()
+            .interface::<_, TrayService<AppHandle>>(OBJECT_PATH)
+            .await
+        {
+            Ok(iface) => iface,
+            Err(e) => {
+                log::warn!("D-Bus service object missing: {}", e);
+                return;
+            }
+        };
+        log::info!("Providing {} on the session bus", SERVICE_NAME);
+
+        let publisher = Publisher::new(iface, &app_handle.status());
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        forward(&app_handle, STATUS_CHANGED_EVENT, tx.clone(), Update::Status);
+        forward(&app_handle, NETWORK_UPDATED_EVENT, tx, Update::Network);
+
+        // One task publishes in order, so clients never see a stale status last
+        while let Some(update) = rx.recv().await {
+            let result = match update {
+                Update::Status(status) => publisher.status_changed(&status).await,
+                Update::Network(update) => publisher.network_updated(&update).await,
+            };
+            if let Err(e) = result {
+                log::warn!("Failed to publish tray state on D-Bus: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::TwingateError;
+    use crate::systemd::private_bus::PrivateBus;
+    use crate::test_support::resource_dto;
+    use futures_util::StreamExt;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tauri::async_runtime::block_on;
+    use zbus::{proxy, CacheProperties, Connection};
+
+    const SIGNAL_TIMEOUT: Duration = Duration::from_secs(5);
+
+    #[proxy(
+        interface = "org.twingate.Tray1",
+        default_service = "org.twingate.Tray1",
+        default_path = "/org/twingate/Tray1"
+    )]
+    trait Tray {
+        fn start(&self) -> zbus::Result<()>;
+        fn copy_address(&self, resource_id: &str) -> zbus::Result<()>;
+        fn refresh(&self) -> zbus::Result<()>;
+
+        #[zbus(property)]
+        fn status(&self) -> zbus::Result<String>;
+        #[zbus(property)]
+        fn user_email(&self) -> zbus::Result<String>;
+        #[zbus(property)]
+        fn resource_count(&self) -> zbus::Result<u32>;
+        #[zbus(property)]
+        fn auth_url(&self) -> zbus::Result<String>;
+
+        #[zbus(signal, name = "StatusChanged")]
+        fn status_signal(&self, status: String) -> zbus::Result<()>;
+        #[zbus(signal, name = "ResourcesChanged")]
+        fn resources_signal(&self, added: Vec<String>, removed: Vec<String>) -> zbus::Result<()>;
+    }
+
+    /// Stand-in tray recording the actions it was asked to run
+    #[derive(Default)]
+    struct StandInHost {
+        status: Mutex<Option<StatusDto>>,
+        actions: Mutex<Vec<String>>,
+    }
+
+    impl TrayHost for Arc<StandInHost> {
+        fn status(&self) -> StatusDto {
+            self.status.lock().unwrap().clone().unwrap_or_else(disconnected)
+        }
+
+        async fn run(&self, action: MenuAction) -> Result<()> {
+            if action.resource_id() == Some("missing") {
+                return Err(TwingateError::resource_not_found("missing"));
+            }
+            self.actions.lock().unwrap().push(action.name().to_string());
+            Ok(())
+        }
+    }
+
+    fn disconnected() -> StatusDto {
+        StatusDto {
+            status: ConnectionStatus::Disconnected,
+            user_email: None,
+            resource_count: 0,
+            security_enabled: false,
+            auth_url: None,
+            error: None,
+        }
+    }
+
+    async fn serve(bus: &PrivateBus, host: Arc<StandInHost>) -> Publisher<Arc<StandInHost>> {
+        let connection = ConnectionBuilder::address(bus.address.as_str())
+            .unwrap()
+            .name(SERVICE_NAME)
+            .unwrap()
+            .serve_at(OBJECT_PATH, TrayService::new(host.clone()))
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+        let iface = connection
+            .object_server()
+            .interface::<_, TrayService<Arc<StandInHost>>>(OBJECT_PATH)
+            .await
+            .unwrap();
+        Publisher::new(iface, &host.status())
+    }
+
+    async fn proxy(client: &Connection) -> TrayProxy<'_> {
+        TrayProxy::builder(client)
+            .cache_properties(CacheProperties::No)
+            .build()
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn test_service_on_private_bus() {
+        let Some(bus) = PrivateBus::start() else {
+            return;
+        };
+        let host = Arc::new(StandInHost::default());
+        let _publisher = block_on(serve(&bus, host.clone()));
+        let client = block_on(bus.connect());
+        let tray = block_on(proxy(&client));
+
+        assert_eq!(block_on(tray.status()).unwrap(), "disconnected");
+        assert_eq!(block_on(tray.user_email()).unwrap(), "");
+
+        *host.status.lock().unwrap() = Some(StatusDto {
+            status: ConnectionStatus::Connected,
+            user_email: Some("user@example.com".to_string()),
+            resource_count: 3,
+            ..disconnected()
+        });
+        assert_eq!(block_on(tray.status()).unwrap(), "connected");
+        assert_eq!(block_on(tray.resource_count()).unwrap(), 3);
+        assert_eq!(block_on(tray.auth_url()).unwrap(), "");
+
+        block_on(tray.start()).unwrap();
+        block_on(tray.copy_address("r1")).unwrap();
+        block_on(tray.refresh()).unwrap();
+        assert_eq!(*host.actions.lock().unwrap(), vec!["start_service", "copy_address", "refresh"]);
+
+        let error = block_on(tray.copy_address("missing")).unwrap_err();
+        assert!(error.to_string().contains("Resource 'missing' not found"), "{}", error);
+    }
+
+    #[test]
+    fn test_signals_on_private_bus() {
+        let Some(bus) = PrivateBus::start() else {
+            return;
+        };
+        let host = Arc::new(StandInHost::default());
+        let publisher = block_on(serve(&bus, host.clone()));
+        let client = block_on(bus.connect());
+        let tray = block_on(proxy(&client));
+        let mut status_signals = block_on(tray.receive_status_signal()).unwrap();
+        let mut resource_signals = block_on(tray.receive_resources_signal()).unwrap();
+
+        let connected = StatusDto {
+            status: ConnectionStatus::Connected,
+            ..disconnected()
+        };
+        *host.status.lock().unwrap() = Some(connected.clone());
+        block_on(publisher.status_changed(&connected)).unwrap();
+        block_on(publisher.status_changed(&connected)).unwrap();
+        // connecting lists every resource, but adds none
+        block_on(publisher.network_updated(&NetworkUpdated {
+            resources: vec![resource_dto("r1", "Database")],
+            added: vec![],
+            removed: vec![],
+        }))
+        .unwrap();
+        block_on(publisher.network_updated(&NetworkUpdated {
+            resources: vec![resource_dto("r2", "Wiki")],
+            added: vec!["Wiki".to_string()],
+            removed: vec!["Database".to_string()],
+        }))
+        .unwrap();
+        block_on(publisher.status_changed(&disconnected())).unwrap();
+
+        let next = |signals: &mut StatusChangedStream<'_>| {
+            let signal = block_on(async { tokio::time::timeout(SIGNAL_TIMEOUT, signals.next()).await })
+                .unwrap()
+                .unwrap();
+            signal.args().unwrap().status
+        };
+        assert_eq!(next(&mut status_signals), "connected");
+        assert_eq!(next(&mut status_signals), "disconnected", "unchanged status is not announced twice");
+
+        let mut next_resources = || {
+            let signal = block_on(async { tokio::time::timeout(SIGNAL_TIMEOUT, resource_signals.next()).await })
+                .unwrap()
+                .unwrap();
+            let args = signal.args().unwrap();
+            (args.added, args.removed)
+        };
+        assert_eq!(next_resources(), (vec!["Wiki".to_string()], vec!["Database".to_string()]));
+    }
+}
@@ -0,0 +1,211 @@
+use crate::backend::CliBackend;
+use crate::cli::find_resource;
+use crate::dto::ResourceDto;
+use crate::error::{Result, TwingateError};
+use crate::managers::{NetworkDataManager, StateManager};
+use crate::models::Network;
+use crate::network::wait_for_service_ready;
+use crate::tray::MenuAction;
+use std::time::Duration;
+use tauri::AppHandle;
+
+/// URL scheme registered for the tray in its .desktop file
+pub const SCHEME: &str = "twingate-tray";
+/// Longest link accepted, before percent-decoding
+const MAX_LINK_LENGTH: usize = 512;
+/// How long to wait for the service to connect when a link starts it
+const SERVICE_READY_TIMEOUT_SECS: u64 = 60;
+
+/// A parsed `twingate-tray://` link
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeepLink {
+    /// `twingate-tray://open/<resource-name>`
+    Open(String),
+    /// `twingate-tray://auth/<resource-id>`
+    Auth(String),
+}
+
+impl DeepLink {
+    /// Whether `arg` uses the tray's URL scheme, valid or not
+    pub fn is_link(arg: &str) -> bool {
+        arg.get(..SCHEME.len() + 1)
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(&format!("{}:", SCHEME)))
+    }
+
+    /// Parse a link, rejecting anything but exactly one action and one
+    /// percent-encoded name or id
+    pub fn parse(link: &str) -> std::result::Result<Self, String> {
+        if link.len() > MAX_LINK_LENGTH {
+            return Err(format!("link is longer than {} bytes", MAX_LINK_LENGTH));
+        }
+        let rest = link
+            .split_once("://")
+            .filter(|(scheme, _)| scheme.eq_ignore_ascii_case(SCHEME))
+            .map(|(_, rest)| rest)
+            .ok_or_else(|| format!("link must start with {}://", SCHEME))?;
+        if rest.contains(['?', '#']) {
+            return Err("link must not have a query or fragment".to_string());
+        }
+
+        let (action, target) = rest
+            .split_once('/')
+            .ok_or_else(|| "link has no resource".to_string())?;
+        if target.is_empty() || target.contains('/') {
+            return Err("link must name exactly one resource".to_string());
+        }
+        let target = percent_decode(target)?;
+        if target.trim().is_empty() || target.chars().any(char::is_control) {
+            return Err("link resource is empty or has control characters".to_string());
+        }
+
+        match action {
+            "open" => Ok(DeepLink::Open(target)),
+            "auth" if is_resource_id(&target) => Ok(DeepLink::Auth(target)),
+            "auth" => Err(format!("'{}' is not a resource id", target)),
+            _ => Err(format!("unknown link action '{}'", action)),
+        }
+    }
+
+    /// The menu action this link ends in; `auth` links only take resource ids
+    pub fn to_action(&self, resources: &[ResourceDto]) -> Result<MenuAction> {
+        Ok(match self {
+            DeepLink::Open(name) => MenuAction::OpenInBrowser(find_resource(resources, name)?.id.clone()),
+            DeepLink::Auth(id) => {
+                let resource = resources
+                    .iter()
+                    .find(|r| &r.id == id)
+                    .ok_or_else(|| TwingateError::resource_not_found(id))?;
+                MenuAction::Authenticate(resource.id.clone())
+            }
+        })
+    }
+}
+
+/// Resource ids are base64, optionally URL-safe
+fn is_resource_id(id: &str) -> bool {
+    id.chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '=' | '-' | '_'))
+}
+
+fn percent_decode(text: &str) -> std::result::Result<String, String> {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let byte = text
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| "link has a malformed percent escape".to_string())?;
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).map_err(|_| "link is not valid UTF-8".to_string())
+}
+
+/// Network data, starting the service first if it is not running
+async fn running_network(app_handle: &AppHandle) -> Result<Network> {
+    if let Some(network) = NetworkDataManager::new(app_handle, Duration::ZERO)
+        .get_cached_or_refresh()
+        .await?
+    {
+        return Ok(network);
+    }
+
+    println!("Starting Twingate service to follow link");
+    crate::handle_menu_action(app_handle, MenuAction::StartService).await?;
+    wait_for_service_ready(&CliBackend::new(app_handle), SERVICE_READY_TIMEOUT_SECS).await?;
+    NetworkDataManager::new(app_handle, Duration::ZERO)
+        .get_network_or_error()
+        .await
+}
+
+/// Follow a link, starting the service and authenticating to the resource first if needed
+pub async fn follow(app_handle: &AppHandle, link: DeepLink) -> Result<()> {
+    let network = running_network(app_handle).await?;
+    let resources = StateManager::with_state(app_handle, |state| {
+        ResourceDto::list(&network, state.default_aliases(), chrono::Utc::now())
+    });
+    let action = link.to_action(&resources)?;
+
+    if let MenuAction::OpenInBrowser(id) = &action {
+        if resources.iter().any(|r| &r.id == id && r.auth_required) {
+            println!("Authenticating to resource {} before opening it", id);
+            crate::handle_menu_action(app_handle, MenuAction::Authenticate(id.clone())).await?;
+        }
+    }
+    crate::handle_menu_action(app_handle, action).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::resource_dto;
+
+    #[test]
+    fn test_parse_links() {
+        assert_eq!(
+            DeepLink::parse("twingate-tray://open/Wiki"),
+            Ok(DeepLink::Open("Wiki".to_string()))
+        );
+        assert_eq!(
+            DeepLink::parse("Twingate-Tray://open/Build%20Server%20%E2%9C%93"),
+            Ok(DeepLink::Open("Build Server ✓".to_string()))
+        );
+        assert_eq!(
+            DeepLink::parse("twingate-tray://auth/UmVzb3VyY2U6MQ%3D%3D"),
+            Ok(DeepLink::Auth("UmVzb3VyY2U6MQ==".to_string()))
+        );
+        assert!(DeepLink::is_link("TWINGATE-TRAY://open/x"));
+        assert!(!DeepLink::is_link("twingate-trayx://open/x"));
+        assert!(!DeepLink::is_link("--open"));
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_links() {
+        let rejected = [
+            "https://open/Wiki",
+            "twingate-tray:open/Wiki",
+            "twingate-tray://open",
+            "twingate-tray://open/",
+            "twingate-tray://open/Wiki/extra",
+            "twingate-tray://open/Wiki?x=1",
+            "twingate-tray://open/Wiki#top",
+            "twingate-tray://OPEN/Wiki",
+            "twingate-tray://copy/Wiki",
+            "twingate-tray://user@open/Wiki",
+            "twingate-tray://open/%20",
+            "twingate-tray://open/Wiki%0A",
+            "twingate-tray://open/%zz",
+            "twingate-tray://open/%C3",
+            "twingate-tray://auth/Not%20an%20id",
+        ];
+        for link in rejected {
+            assert!(DeepLink::parse(link).is_err(), "accepted {}", link);
+        }
+
+        let long = format!("twingate-tray://open/{}", "a".repeat(MAX_LINK_LENGTH));
+        assert!(DeepLink::parse(&long).is_err());
+    }
+
+    #[test]
+    fn test_to_action_resolves_resources() {
+        let resources = vec![resource_dto("r1", "Database"), resource_dto("r2", "Wiki")];
+
+        let open = DeepLink::Open("Wiki".to_string()).to_action(&resources).unwrap();
+        assert!(matches!(open, MenuAction::OpenInBrowser(id) if id == "r2"));
+
+        let auth = DeepLink::Auth("r1".to_string()).to_action(&resources).unwrap();
+        assert!(matches!(auth, MenuAction::Authenticate(id) if id == "r1"));
+
+        // auth links take ids only, never names
+        assert!(matches!(
+            DeepLink::Auth("Database".to_string()).to_action(&resources),
+            Err(TwingateError::ResourceNotFound { .. })
+        ));
+    }
+}
@@ -0,0 +1,221 @@
+use crate::aliases::DefaultAliases;
+use crate::models::{Network, Resource};
+use crate::state::{AppState, ServiceStatus};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Overall connection state reported to clients of the tray
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionStatus {
+    Connected,
+    Disconnected,
+    Authenticating,
+    Error,
+}
+
+impl ConnectionStatus {
+    /// An authentication flow in progress wins over a failed refresh
+    pub fn from_state(state: &AppState) -> Self {
+        match state.service_status() {
+            ServiceStatus::Authenticating(_) => ConnectionStatus::Authenticating,
+            _ if state.last_error().is_some() => ConnectionStatus::Error,
+            ServiceStatus::Connected => ConnectionStatus::Connected,
+            ServiceStatus::NotRunning => ConnectionStatus::Disconnected,
+        }
+    }
+
+    /// The serialized name, e.g. `connected`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConnectionStatus::Connected => "connected",
+            ConnectionStatus::Disconnected => "disconnected",
+            ConnectionStatus::Authenticating => "authenticating",
+            ConnectionStatus::Error => "error",
+        }
+    }
+}
+
+/// Snapshot of the tray state for the frontend and other clients
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusDto {
+    pub status: ConnectionStatus,
+    pub user_email: Option<String>,
+    /// Resources the client shows to the user
+    pub resource_count: usize,
+    pub security_enabled: bool,
+    pub auth_url: Option<String>,
+    pub error: Option<String>,
+}
+
+impl StatusDto {
+    pub fn from_state(state: &AppState) -> Self {
+        let network = state.network();
+
+        Self {
+            status: ConnectionStatus::from_state(state),
+            user_email: network.map(|n| n.user.email.clone()),
+            resource_count: network.map_or(0, |n| visible(n).count()),
+            security_enabled: network.is_some_and(|n| n.internet_security.mode > 0),
+            auth_url: state.auth_url().map(str::to_string),
+            error: state.last_error().map(str::to_string),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AliasDto {
+    pub address: String,
+    pub open_url: String,
+}
+
+/// A resource as shown in the tray menu
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceDto {
+    pub id: String,
+    pub name: String,
+    /// Address copied: the chosen default alias, otherwise the client's alias or raw address
+    pub address: String,
+    pub aliases: Vec<AliasDto>,
+    pub resource_type: String,
+    /// URL opened by "Open in Browser", following the chosen default alias
+    pub open_url: Option<String>,
+    pub auth_required: bool,
+    /// RFC 3339 time authentication runs out, if ever authenticated
+    pub auth_expires_at: Option<String>,
+}
+
+impl ResourceDto {
+    pub fn from_resource(resource: &Resource, default_aliases: &DefaultAliases, now: DateTime<Utc>) -> Self {
+        Self {
+            id: resource.id.clone(),
+            name: resource.name.clone(),
+            address: default_aliases.address(resource).to_string(),
+            aliases: resource
+                .aliases
+                .iter()
+                .map(|alias| AliasDto {
+                    address: alias.address.clone(),
+                    open_url: alias.open_url.clone(),
+                })
+                .collect(),
+            resource_type: resource.resource_type.clone(),
+            open_url: default_aliases.open_url(resource).map(str::to_string),
+            auth_required: resource.requires_auth(now),
+            auth_expires_at: resource.auth_expires_at.map(|expires_at| expires_at.to_rfc3339()),
+        }
+    }
+
+    /// The resources of `network` the client shows to the user
+    pub fn list(network: &Network, default_aliases: &DefaultAliases, now: DateTime<Utc>) -> Vec<Self> {
+        visible(network)
+            .map(|resource| Self::from_resource(resource, default_aliases, now))
+            .collect()
+    }
+}
+
+/// Resources the client shows to the user, in the client's order
+pub fn visible(network: &Network) -> impl Iterator<Item = &Resource> {
+    network.resources.iter().filter(|r| r.client_visibility != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{network, resource, ResourceBuilder};
+
+    fn now() -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000, 0).unwrap()
+    }
+
+    fn database(id: &str) -> ResourceBuilder {
+        resource(id)
+            .name("Database")
+            .address("10.0.0.5")
+            .alias("db.internal")
+            .with_alias("db.internal", "https://db.internal")
+            .open_in_browser()
+    }
+
+    #[test]
+    fn test_status_from_state() {
+        let mut state = AppState::new();
+        let disconnected = StatusDto::from_state(&state);
+        assert_eq!(disconnected.status, ConnectionStatus::Disconnected);
+        assert_eq!(disconnected.user_email, None);
+        assert_eq!(disconnected.resource_count, 0);
+
+        let resources = vec![database("r1").build(), database("r2").hidden().build()];
+        state.update_network(Some(network(resources).internet_security(1).build()));
+        let connected = StatusDto::from_state(&state);
+        assert_eq!(connected.status, ConnectionStatus::Connected);
+        assert_eq!(connected.user_email.as_deref(), Some("user@example.com"));
+        assert_eq!(connected.resource_count, 1);
+        assert!(connected.security_enabled);
+
+        state.set_error("status poll failed".to_string());
+        let failed = StatusDto::from_state(&state);
+        assert_eq!(failed.status, ConnectionStatus::Error);
+        assert_eq!(failed.error.as_deref(), Some("status poll failed"));
+
+        state.set_authenticating("https://auth.example.com".to_string());
+        let authenticating = StatusDto::from_state(&state);
+        assert_eq!(authenticating.status, ConnectionStatus::Authenticating);
+        assert_eq!(authenticating.auth_url.as_deref(), Some("https://auth.example.com"));
+    }
+
+    #[test]
+    fn test_resource_dto() {
+        let authenticated = database("r1").auth_expires_at(DateTime::from_timestamp(1_700_003_600, 0));
+        let dto = ResourceDto::from_resource(&authenticated.build(), &DefaultAliases::default(), now());
+
+        assert_eq!(dto.address, "db.internal");
+        assert_eq!(dto.open_url.as_deref(), Some("https://db.internal"));
+        assert!(!dto.auth_required);
+        assert_eq!(dto.auth_expires_at.as_deref(), Some("2023-11-14T23:13:20+00:00"));
+
+        let never_authenticated = resource("r1").alias("db.internal").build();
+        let dto = ResourceDto::from_resource(&never_authenticated, &DefaultAliases::default(), now());
+        assert!(dto.auth_required);
+        assert_eq!(dto.auth_expires_at, None);
+        assert_eq!(dto.open_url, None);
+    }
+
+    #[test]
+    fn test_resource_dto_uses_default_alias() {
+        let resource = database("r1")
+            .with_alias("db-replica.internal", "https://db-replica.internal")
+            .build();
+        let mut default_aliases = DefaultAliases::default();
+        default_aliases.set("r1", Some("db-replica.internal"));
+
+        let dto = ResourceDto::from_resource(&resource, &default_aliases, now());
+        assert_eq!(dto.address, "db-replica.internal");
+        assert_eq!(dto.open_url.as_deref(), Some("https://db-replica.internal"));
+        assert_eq!(dto.aliases.len(), 2);
+
+        default_aliases.set("r1", None);
+        let dto = ResourceDto::from_resource(&resource, &default_aliases, now());
+        assert_eq!(dto.address, "db.internal");
+        assert_eq!(dto.open_url.as_deref(), Some("https://db.internal"));
+    }
+
+    #[test]
+    fn test_resource_list_skips_hidden() {
+        let network = network(vec![database("r1").build(), database("r2").hidden().build()]).build();
+
+        let ids: Vec<_> = ResourceDto::list(&network, &DefaultAliases::default(), now())
+            .into_iter()
+            .map(|r| r.id)
+            .collect();
+        assert_eq!(ids, vec!["r1"]);
+    }
+
+    #[test]
+    fn test_status_serialization() {
+        let json = serde_json::to_value(StatusDto::from_state(&AppState::new())).unwrap();
+        assert_eq!(json["status"], "disconnected");
+        assert_eq!(json["status"], ConnectionStatus::Disconnected.as_str());
+        assert_eq!(json["resource_count"], 0);
+    }
+}
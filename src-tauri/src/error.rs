@@ -59,6 +59,22 @@ pub enum TwingateError {
         source: tauri::Error,
     },
     
+    #[error("D-Bus call failed: {source}")]
+    DbusError {
+        #[from]
+        source: zbus::Error,
+    },
+    
+    
+    #[error("Control request failed: {details}")]
+    ControlError { details: String },
+    
+    #[error("Launch action '{label}' failed: {details}")]
+    LaunchFailed { label: String, details: String },
+    
+    // Configuration errors
+    #[error("Configuration error: {details}")]
+    ConfigError { details: String },
     
     // Retry and timeout errors
     #[error("Operation timed out after {attempts} attempts")]
@@ -94,6 +110,13 @@ impl From<tauri_plugin_opener::Error> for TwingateError {
     }
 }
 
+/// Commands hand errors to the frontend as their message
+impl serde::Serialize for TwingateError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
 // Helper methods for common error scenarios
 impl TwingateError {
     pub fn command_failed(command: impl Into<String>, code: i32, stderr: impl Into<String>) -> Self {
@@ -112,6 +135,25 @@ impl TwingateError {
         Self::InvalidResourceId { id: id.into() }
     }
     
+    pub fn config_error(details: impl Into<String>) -> Self {
+        Self::ConfigError {
+            details: details.into(),
+        }
+    }
+
+    pub fn control_error(details: impl Into<String>) -> Self {
+        Self::ControlError {
+            details: details.into(),
+        }
+    }
+    
+    pub fn launch_failed(label: impl Into<String>, details: impl Into<String>) -> Self {
+        Self::LaunchFailed {
+            label: label.into(),
+            details: details.into(),
+        }
+    }
+    
     
 }
 
@@ -216,6 +258,19 @@ mod tests {
         assert_eq!(error.to_string(), "Clipboard operation failed: Failed to access clipboard");
     }
 
+    #[test]
+    fn test_config_error_helper() {
+        let error = TwingateError::config_error("cannot read config.json");
+        assert_eq!(error.to_string(), "Configuration error: cannot read config.json");
+    }
+
+    #[test]
+    fn test_from_dbus_error() {
+        let error: TwingateError = zbus::Error::Failure("no notification daemon".to_string()).into();
+        assert!(matches!(error, TwingateError::DbusError { .. }));
+        assert!(error.to_string().starts_with("D-Bus call failed:"));
+    }
+
     #[test]
     fn test_invalid_utf8_error() {
         let error = TwingateError::InvalidUtf8;
@@ -291,4 +346,10 @@ mod tests {
         // Test that the error chain is preserved
         assert!(twingate_error.source().is_some());
     }
+
+    #[test]
+    fn test_error_serializes_as_message() {
+        let error = TwingateError::resource_not_found("res-1");
+        assert_eq!(serde_json::to_string(&error).unwrap(), r#""Resource 'res-1' not found""#);
+    }
 }
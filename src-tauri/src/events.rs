@@ -0,0 +1,152 @@
+use crate::dto::{ResourceDto, StatusDto};
+use crate::notifications::ResourceChanges;
+use crate::state::AppState;
+use crate::tray::MenuAction;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+pub const STATUS_CHANGED_EVENT: &str = "status-changed";
+pub const NETWORK_UPDATED_EVENT: &str = "network-updated";
+pub const AUTH_STARTED_EVENT: &str = "auth-started";
+pub const AUTH_COMPLETED_EVENT: &str = "auth-completed";
+pub const OPERATION_FAILED_EVENT: &str = "operation-failed";
+
+/// Resources after a refresh, with the names that appeared or disappeared
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkUpdated {
+    /// Visible resources; empty while the client is not connected
+    pub resources: Vec<ResourceDto>,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl NetworkUpdated {
+    pub fn from_state(state: &AppState, changes: ResourceChanges) -> Self {
+        Self {
+            resources: state
+                .network()
+                .map(|network| ResourceDto::list(network, state.default_aliases(), Utc::now()))
+                .unwrap_or_default(),
+            added: changes.added,
+            removed: changes.removed,
+        }
+    }
+}
+
+/// An authentication flow was started, for the client or a single resource
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthStarted {
+    pub resource_id: Option<String>,
+    /// Sign-in URL, when the tray opens it rather than the client
+    pub auth_url: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthCompleted {
+    pub resource_id: Option<String>,
+}
+
+/// A menu action or command failed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperationFailed {
+    /// Menu id of the action, e.g. `start_service` or `copy_address`
+    pub operation: String,
+    pub resource_id: Option<String>,
+    pub message: String,
+}
+
+impl OperationFailed {
+    pub fn new(action: &MenuAction, message: String) -> Self {
+        Self {
+            operation: action.name().to_string(),
+            resource_id: action.resource_id().map(str::to_string),
+            message,
+        }
+    }
+}
+
+/// Events emitted to every webview window, each with its own payload
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum AppEvent {
+    StatusChanged(StatusDto),
+    NetworkUpdated(NetworkUpdated),
+    AuthStarted(AuthStarted),
+    AuthCompleted(AuthCompleted),
+    OperationFailed(OperationFailed),
+}
+
+impl AppEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AppEvent::StatusChanged(_) => STATUS_CHANGED_EVENT,
+            AppEvent::NetworkUpdated(_) => NETWORK_UPDATED_EVENT,
+            AppEvent::AuthStarted(_) => AUTH_STARTED_EVENT,
+            AppEvent::AuthCompleted(_) => AUTH_COMPLETED_EVENT,
+            AppEvent::OperationFailed(_) => OPERATION_FAILED_EVENT,
+        }
+    }
+
+    /// Emit to all windows; a failure is only logged since nobody may be listening
+    pub fn emit(&self, app_handle: &AppHandle) {
+        log::debug!("Emitting {} event", self.name());
+        if let Err(e) = app_handle.emit(self.name(), self) {
+            log::warn!("Failed to emit {} event: {}", self.name(), e);
+        }
+    }
+}
+
+/// Emit `status-changed` if the status reported to the frontend differs
+pub fn emit_status_if_changed(app_handle: &AppHandle, before: &StatusDto, after: StatusDto) {
+    if *before != after {
+        AppEvent::StatusChanged(after).emit(app_handle);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{network, resource};
+
+    #[test]
+    fn test_event_payload_is_unwrapped() {
+        let event = AppEvent::StatusChanged(StatusDto::from_state(&AppState::new()));
+        assert_eq!(event.name(), "status-changed");
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["status"], "disconnected");
+    }
+
+    #[test]
+    fn test_network_updated_from_state() {
+        let mut state = AppState::new();
+        let disconnected = NetworkUpdated::from_state(&state, ResourceChanges::default());
+        assert!(disconnected.resources.is_empty());
+
+        state.update_network(Some(network(vec![resource("r1").name("Database").build()]).build()));
+        let changes = ResourceChanges {
+            added: vec!["Database".to_string()],
+            removed: vec![],
+        };
+        let event = AppEvent::NetworkUpdated(NetworkUpdated::from_state(&state, changes));
+        assert_eq!(event.name(), "network-updated");
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["resources"][0]["id"], "r1");
+        assert_eq!(json["added"][0], "Database");
+    }
+
+    #[test]
+    fn test_operation_failed_names_the_action() {
+        let failed = OperationFailed::new(&MenuAction::CopyAddress("r1".to_string()), "no clipboard".to_string());
+        assert_eq!(failed.operation, "copy_address");
+        assert_eq!(failed.resource_id.as_deref(), Some("r1"));
+
+        let failed = OperationFailed::new(&MenuAction::StartService, "exit status 1".to_string());
+        let json = serde_json::to_value(AppEvent::OperationFailed(failed)).unwrap();
+        assert_eq!(json["operation"], "start_service");
+        assert_eq!(json["resource_id"], serde_json::Value::Null);
+        assert_eq!(json["message"], "exit status 1");
+    }
+}
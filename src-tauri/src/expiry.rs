@@ -0,0 +1,327 @@
+use crate::auth::start_resource_auth;
+use crate::managers::StateManager;
+use crate::models::Network;
+use crate::notifications::{escape_markup, send_actionable_notification, NotificationAction};
+use crate::utils::format_duration_short;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::AppHandle;
+use tokio::time::{interval, MissedTickBehavior};
+
+/// How often resource expiry times are compared against the clock
+const EXPIRY_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+const AUTHENTICATE_ACTION: NotificationAction = NotificationAction {
+    key: "authenticate",
+    label: "Authenticate now",
+};
+
+/// A reason to prompt the user to re-authenticate to a resource
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExpiryAlert {
+    /// Authentication runs out within the warning window
+    Expiring {
+        resource_id: String,
+        name: String,
+        expires_at: DateTime<Utc>,
+    },
+    /// The resource went from authenticated to requiring authentication
+    AuthRequired { resource_id: String, name: String },
+}
+
+impl ExpiryAlert {
+    pub fn resource_id(&self) -> &str {
+        match self {
+            ExpiryAlert::Expiring { resource_id, .. } | ExpiryAlert::AuthRequired { resource_id, .. } => {
+                resource_id
+            }
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        match self {
+            ExpiryAlert::Expiring { .. } => "Twingate authentication expiring".to_string(),
+            ExpiryAlert::AuthRequired { .. } => "Twingate authentication required".to_string(),
+        }
+    }
+
+    pub fn body(&self, now: DateTime<Utc>) -> String {
+        match self {
+            ExpiryAlert::Expiring { name, expires_at, .. } => format!(
+                "Access to {} expires in {}.",
+                escape_markup(name),
+                format_duration_short(*expires_at - now)
+            ),
+            ExpiryAlert::AuthRequired { name, .. } => {
+                format!("Authenticate to keep using {}.", escape_markup(name))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TrackedAuth {
+    expires_at: Option<DateTime<Utc>>,
+    warned: bool,
+    requires_auth: bool,
+}
+
+/// Remembers what has been announced per resource so each expiry warns once
+#[derive(Debug, Default)]
+pub struct ExpiryTracker {
+    resources: HashMap<String, TrackedAuth>,
+}
+
+impl ExpiryTracker {
+    /// Compare resource expiry times against `now` and return alerts not
+    /// raised before.
+    ///
+    /// Resources that already require authentication when first seen are not
+    /// announced; only a change from authenticated to required is.
+    pub fn check(&mut self, network: Option<&Network>, now: DateTime<Utc>, warn_before: Duration) -> Vec<ExpiryAlert> {
+        let Some(network) = network else {
+            self.resources.clear();
+            return Vec::new();
+        };
+
+        let warn_before = chrono::Duration::from_std(warn_before).unwrap_or(chrono::Duration::MAX);
+        let mut alerts = Vec::new();
+        let mut tracked = HashMap::new();
+
+        for resource in network.resources.iter().filter(|r| r.client_visibility != 0) {
+            let previous = self.resources.get(&resource.id);
+            let expires_at = resource.auth_expires_at;
+            let requires_auth = resource.requires_auth(now);
+
+            let mut entry = match previous {
+                Some(previous) if previous.expires_at == expires_at => *previous,
+                _ => TrackedAuth {
+                    expires_at,
+                    warned: false,
+                    requires_auth: previous.is_some_and(|p| p.requires_auth),
+                },
+            };
+
+            if requires_auth {
+                if previous.is_some_and(|p| !p.requires_auth) {
+                    alerts.push(ExpiryAlert::AuthRequired {
+                        resource_id: resource.id.clone(),
+                        name: resource.name.clone(),
+                    });
+                }
+                entry.requires_auth = true;
+            } else if let Some(expires_at) = expires_at {
+                if !entry.warned && expires_at - now <= warn_before {
+                    alerts.push(ExpiryAlert::Expiring {
+                        resource_id: resource.id.clone(),
+                        name: resource.name.clone(),
+                        expires_at,
+                    });
+                    entry.warned = true;
+                }
+                entry.requires_auth = false;
+            }
+
+            tracked.insert(resource.id.clone(), entry);
+        }
+
+        self.resources = tracked;
+        alerts
+    }
+}
+
+/// Show an expiry alert and start authentication if the user asks for it
+fn notify_expiry(app_handle: &AppHandle, alert: ExpiryAlert, now: DateTime<Utc>) {
+    println!("{}: {}", alert.summary(), alert.body(now));
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        match send_actionable_notification(&alert.summary(), &alert.body(now), AUTHENTICATE_ACTION).await {
+            Ok(true) => {
+                log::debug!("Authenticating {} from expiry notification", alert.resource_id());
+                if let Err(e) = start_resource_auth(&app_handle, alert.resource_id()).await {
+                    eprintln!(
+                        "Error: Failed to start authentication for resource {}: {}",
+                        alert.resource_id(),
+                        e
+                    );
+                }
+            }
+            Ok(false) => log::debug!("Expiry notification for {} dismissed", alert.resource_id()),
+            Err(e) => log::warn!("Failed to show auth expiry notification: {}", e),
+        }
+    });
+}
+
+/// Periodically check resource auth expiry and warn `warn_before` ahead of it
+pub fn spawn_expiry_watcher(app_handle: AppHandle, warn_before: Duration) {
+    log::info!("Warning about resource auth expiry {:?} ahead", warn_before);
+
+    tauri::async_runtime::spawn(async move {
+        let mut tracker = ExpiryTracker::default();
+        let mut ticker = interval(EXPIRY_CHECK_INTERVAL);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+
+            let now = Utc::now();
+            let alerts = StateManager::with_state(&app_handle, |state| {
+                tracker.check(state.network(), now, warn_before)
+            });
+
+            for alert in alerts {
+                notify_expiry(&app_handle, alert, now);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{auth_expiry_from_timestamp, Resource};
+    use crate::test_support::{network, resource};
+
+    const HOUR_MS: i64 = 60 * 60 * 1000;
+    const NOW: i64 = 1_700_000_000_000;
+    const WARN_BEFORE: Duration = Duration::from_secs(60 * 60);
+
+    fn at(timestamp_ms: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp_millis(timestamp_ms).unwrap()
+    }
+
+    fn expiring(id: &str, auth_expires_at: i64) -> Resource {
+        resource(id)
+            .name(&format!("Resource {}", id))
+            .auth_expires_at(auth_expiry_from_timestamp(auth_expires_at))
+            .build()
+    }
+
+    #[test]
+    fn test_warns_once_inside_window() {
+        let mut tracker = ExpiryTracker::default();
+        let net = network(vec![expiring("r1", NOW + 3 * HOUR_MS)]).build();
+
+        assert!(tracker.check(Some(&net), at(NOW), WARN_BEFORE).is_empty());
+
+        let later = NOW + 2 * HOUR_MS + 1;
+        let alerts = tracker.check(Some(&net), at(later), WARN_BEFORE);
+        assert_eq!(
+            alerts,
+            vec![ExpiryAlert::Expiring {
+                resource_id: "r1".to_string(),
+                name: "Resource r1".to_string(),
+                expires_at: at(NOW + 3 * HOUR_MS),
+            }]
+        );
+
+        assert!(tracker.check(Some(&net), at(later + 60_000), WARN_BEFORE).is_empty());
+    }
+
+    #[test]
+    fn test_fires_again_when_auth_becomes_required() {
+        let mut tracker = ExpiryTracker::default();
+        let expires_at = NOW + 30 * 60 * 1000;
+        let net = network(vec![expiring("r1", expires_at)]).build();
+
+        assert_eq!(tracker.check(Some(&net), at(NOW), WARN_BEFORE).len(), 1);
+
+        // The expiry time passes
+        let alerts = tracker.check(Some(&net), at(expires_at + 1), WARN_BEFORE);
+        assert!(matches!(alerts.as_slice(), [ExpiryAlert::AuthRequired { .. }]));
+
+        // The client then reports the resource as unauthenticated
+        let net = network(vec![expiring("r1", 0)]).build();
+        assert!(tracker.check(Some(&net), at(expires_at + 60_000), WARN_BEFORE).is_empty());
+    }
+
+    #[test]
+    fn test_flip_to_zero_fires_auth_required() {
+        let mut tracker = ExpiryTracker::default();
+        tracker.check(Some(&network(vec![expiring("r1", NOW + 10 * HOUR_MS)]).build()), at(NOW), WARN_BEFORE);
+
+        let alerts = tracker.check(Some(&network(vec![expiring("r1", 0)]).build()), at(NOW), WARN_BEFORE);
+
+        assert_eq!(
+            alerts,
+            vec![ExpiryAlert::AuthRequired {
+                resource_id: "r1".to_string(),
+                name: "Resource r1".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_ignores_resources_requiring_auth_when_first_seen() {
+        let mut tracker = ExpiryTracker::default();
+        let net = network(vec![expiring("r1", 0), expiring("r2", NOW - HOUR_MS)]).build();
+
+        assert!(tracker.check(Some(&net), at(NOW), WARN_BEFORE).is_empty());
+        assert!(tracker.check(Some(&net), at(NOW + HOUR_MS), WARN_BEFORE).is_empty());
+    }
+
+    #[test]
+    fn test_reauthentication_rearms_warning() {
+        let mut tracker = ExpiryTracker::default();
+        let soon = NOW + 10 * 60 * 1000;
+        assert_eq!(tracker.check(Some(&network(vec![expiring("r1", soon)]).build()), at(NOW), WARN_BEFORE).len(), 1);
+        assert_eq!(tracker.check(Some(&network(vec![expiring("r1", 0)]).build()), at(NOW), WARN_BEFORE).len(), 1);
+
+        // Authenticated again with a fresh expiry, then that one nears its end
+        let renewed = NOW + 8 * HOUR_MS;
+        let net = network(vec![expiring("r1", renewed)]).build();
+        assert!(tracker.check(Some(&net), at(NOW), WARN_BEFORE).is_empty());
+        assert_eq!(tracker.check(Some(&net), at(renewed - HOUR_MS / 2), WARN_BEFORE).len(), 1);
+    }
+
+    #[test]
+    fn test_disconnect_forgets_resources() {
+        let mut tracker = ExpiryTracker::default();
+        tracker.check(Some(&network(vec![expiring("r1", NOW + 10 * HOUR_MS)]).build()), at(NOW), WARN_BEFORE);
+
+        assert!(tracker.check(None, at(NOW), WARN_BEFORE).is_empty());
+        assert!(tracker.check(Some(&network(vec![expiring("r1", 0)]).build()), at(NOW), WARN_BEFORE).is_empty());
+    }
+
+    #[test]
+    fn test_hidden_resources_are_ignored() {
+        let mut tracker = ExpiryTracker::default();
+        let hidden = resource("r1")
+            .auth_expires_at(auth_expiry_from_timestamp(NOW + 60_000))
+            .hidden()
+            .build();
+
+        assert!(tracker.check(Some(&network(vec![hidden]).build()), at(NOW), WARN_BEFORE).is_empty());
+    }
+
+    #[test]
+    fn test_alert_text() {
+        let expiring = ExpiryAlert::Expiring {
+            resource_id: "r1".to_string(),
+            name: "Database".to_string(),
+            expires_at: at(NOW + 3 * HOUR_MS + 12 * 60 * 1000),
+        };
+        assert_eq!(expiring.resource_id(), "r1");
+        assert_eq!(expiring.summary(), "Twingate authentication expiring");
+        assert_eq!(expiring.body(at(NOW)), "Access to Database expires in 3h 12m.");
+
+        let required = ExpiryAlert::AuthRequired {
+            resource_id: "r2".to_string(),
+            name: "Wiki".to_string(),
+        };
+        assert_eq!(required.resource_id(), "r2");
+        assert_eq!(required.summary(), "Twingate authentication required");
+        assert_eq!(required.body(at(NOW)), "Authenticate to keep using Wiki.");
+
+        let marked_up = ExpiryAlert::AuthRequired {
+            resource_id: "r3".to_string(),
+            name: "<b>R&D</b>".to_string(),
+        };
+        assert_eq!(
+            marked_up.body(at(NOW)),
+            "Authenticate to keep using &lt;b&gt;R&amp;D&lt;/b&gt;."
+        );
+    }
+}
@@ -0,0 +1,229 @@
+use crate::config::AppConfig;
+use crate::models::Resource;
+use crate::tray::get_address_from_resource;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Group for resources no strategy places anywhere else; always listed last
+pub const OTHER_GROUP: &str = "Other";
+
+/// How the connected menu groups resources into submenus
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GroupBy {
+    /// One flat list
+    #[default]
+    None,
+    /// By resource type, e.g. DNS, IP or CIDR
+    Type,
+    /// By the domain an address belongs to, e.g. `prod.corp.internal` for
+    /// `db.prod.corp.internal`
+    Domain,
+    /// By the first of the configured `group_rules` that matches
+    Rules,
+}
+
+/// Puts resources whose name or address matches `pattern` into `group`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupRule {
+    pub group: String,
+    pub pattern: String,
+}
+
+/// Grouping settings from the config, with the rule patterns compiled
+#[derive(Debug, Clone)]
+pub struct Grouping {
+    pub group_by: GroupBy,
+    rules: Vec<(String, Regex)>,
+    /// Most entries on one menu level before the rest move to "More..."
+    pub max_items: Option<usize>,
+}
+
+impl Default for Grouping {
+    fn default() -> Self {
+        Self::from_config(&AppConfig::default())
+    }
+}
+
+impl Grouping {
+    /// Compile the config's rules, skipping any with an invalid pattern
+    pub fn from_config(config: &AppConfig) -> Self {
+        let rules = config
+            .group_rules
+            .iter()
+            .filter_map(|rule| match Regex::new(&rule.pattern) {
+                Ok(regex) => Some((rule.group.clone(), regex)),
+                Err(e) => {
+                    log::warn!("Ignoring group rule for '{}': {}", rule.group, e);
+                    eprintln!("Warning: Ignoring group rule for '{}': {}", rule.group, e);
+                    None
+                }
+            })
+            .collect();
+
+        Self {
+            group_by: config.group_by,
+            rules,
+            max_items: config.max_menu_items(),
+        }
+    }
+
+    /// The group a resource belongs to, `None` for the "Other" group
+    fn group_of(&self, resource: &Resource) -> Option<String> {
+        let address = get_address_from_resource(resource);
+        match self.group_by {
+            GroupBy::None => None,
+            GroupBy::Type => Some(resource.resource_type.clone()).filter(|t| !t.is_empty()),
+            GroupBy::Domain => parent_domain(address),
+            GroupBy::Rules => self
+                .rules
+                .iter()
+                .find(|(_, regex)| regex.is_match(&resource.name) || regex.is_match(address))
+                .map(|(group, _)| group.clone()),
+        }
+    }
+
+    /// Resources by group in alphabetical order with "Other" last, keeping
+    /// the client's order within each group; `None` when not grouping
+    pub fn group<'a>(&self, resources: &[&'a Resource]) -> Option<Vec<(String, Vec<&'a Resource>)>> {
+        if self.group_by == GroupBy::None {
+            return None;
+        }
+
+        let mut groups: Vec<(Option<String>, Vec<&'a Resource>)> = Vec::new();
+        for &resource in resources {
+            let key = self.group_of(resource);
+            match groups.iter_mut().find(|(group, _)| *group == key) {
+                Some((_, members)) => members.push(resource),
+                None => groups.push((key, vec![resource])),
+            }
+        }
+
+        // None sorts first, so compare the other way round to put it last
+        groups.sort_by(|(a, _), (b, _)| match (a, b) {
+            (Some(a), Some(b)) => a.to_lowercase().cmp(&b.to_lowercase()),
+            _ => b.cmp(a),
+        });
+        Some(
+            groups
+                .into_iter()
+                .map(|(group, members)| (group.unwrap_or_else(|| OTHER_GROUP.to_string()), members))
+                .collect(),
+        )
+    }
+}
+
+/// The domain a DNS address sits in; an address with only two labels is its
+/// own domain, and IP addresses and ranges have none
+fn parent_domain(address: &str) -> Option<String> {
+    let address = address.trim_start_matches("*.").trim_end_matches('.').to_lowercase();
+    if address.contains([':', '/']) || !address.chars().any(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    match address.split_once('.') {
+        Some((_, parent)) if parent.contains('.') => Some(parent.to_string()),
+        Some(_) => Some(address),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    fn resource(name: &str, address: &str, resource_type: &str) -> Resource {
+        test_support::resource(&name.to_lowercase())
+            .name(name)
+            .address(address)
+            .resource_type(resource_type)
+            .build()
+    }
+
+    fn grouping(group_by: GroupBy, rules: &[(&str, &str)]) -> Grouping {
+        Grouping::from_config(&AppConfig {
+            group_by,
+            group_rules: rules
+                .iter()
+                .map(|(group, pattern)| GroupRule {
+                    group: group.to_string(),
+                    pattern: pattern.to_string(),
+                })
+                .collect(),
+            ..AppConfig::default()
+        })
+    }
+
+    fn group_names(grouping: &Grouping, resources: &[Resource]) -> Vec<(String, Vec<String>)> {
+        let resources: Vec<&Resource> = resources.iter().collect();
+        grouping
+            .group(&resources)
+            .unwrap()
+            .into_iter()
+            .map(|(group, members)| (group, members.iter().map(|r| r.name.clone()).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parent_domain() {
+        assert_eq!(parent_domain("db.prod.corp.internal"), Some("prod.corp.internal".to_string()));
+        assert_eq!(parent_domain("*.corp.internal"), Some("corp.internal".to_string()));
+        assert_eq!(parent_domain("Wiki.Example.com."), Some("example.com".to_string()));
+        assert_eq!(parent_domain("example.com"), Some("example.com".to_string()));
+        assert_eq!(parent_domain("localhost"), None);
+        assert_eq!(parent_domain("10.0.0.5"), None);
+        assert_eq!(parent_domain("10.0.0.0/8"), None);
+        assert_eq!(parent_domain("fd00::1"), None);
+    }
+
+    #[test]
+    fn test_group_by_type_and_domain() {
+        let resources = vec![
+            resource("Wiki", "wiki.corp.internal", "DNS"),
+            resource("Router", "10.0.0.1", "IP"),
+            resource("Db", "db.corp.internal", "DNS"),
+            resource("Grafana", "grafana.apps.example.com", "DNS"),
+        ];
+
+        assert!(grouping(GroupBy::None, &[]).group(&[]).is_none());
+        assert_eq!(
+            group_names(&grouping(GroupBy::Type, &[]), &resources),
+            vec![
+                ("DNS".to_string(), vec!["Wiki".to_string(), "Db".to_string(), "Grafana".to_string()]),
+                ("IP".to_string(), vec!["Router".to_string()]),
+            ]
+        );
+        assert_eq!(
+            group_names(&grouping(GroupBy::Domain, &[]), &resources),
+            vec![
+                ("apps.example.com".to_string(), vec!["Grafana".to_string()]),
+                ("corp.internal".to_string(), vec!["Wiki".to_string(), "Db".to_string()]),
+                ("Other".to_string(), vec!["Router".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_group_by_rules() {
+        let resources = vec![
+            resource("Prod DB", "db.prod.internal", "DNS"),
+            resource("Staging DB", "db.staging.internal", "DNS"),
+            resource("Printer", "10.1.0.9", "IP"),
+        ];
+        let rules = grouping(
+            GroupBy::Rules,
+            &[("Production", r"\.prod\."), ("Broken", "("), ("Databases", "(?i)db")],
+        );
+
+        // the first matching rule wins and invalid patterns are skipped
+        assert_eq!(
+            group_names(&rules, &resources),
+            vec![
+                ("Databases".to_string(), vec!["Staging DB".to_string()]),
+                ("Production".to_string(), vec!["Prod DB".to_string()]),
+                ("Other".to_string(), vec!["Printer".to_string()]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use crate::dto::ConnectionStatus;
+use crate::error::Result;
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use tauri::image::Image;
+
+/// Which set of tray icons to use
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TrayIconStyle {
+    /// Coloured icons that read on any panel
+    #[default]
+    Color,
+    /// Monochrome white icons for dark panels
+    SymbolicLight,
+    /// Monochrome dark icons for light panels
+    SymbolicDark,
+}
+
+/// Connection state shown by the tray icon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayIconKind {
+    Connected,
+    Disconnected,
+    Authenticating,
+    Error,
+}
+
+impl TrayIconKind {
+    /// Pick the icon for the current state
+    pub fn from_state(state: &AppState) -> Self {
+        match ConnectionStatus::from_state(state) {
+            ConnectionStatus::Connected => TrayIconKind::Connected,
+            ConnectionStatus::Disconnected => TrayIconKind::Disconnected,
+            ConnectionStatus::Authenticating => TrayIconKind::Authenticating,
+            ConnectionStatus::Error => TrayIconKind::Error,
+        }
+    }
+
+    pub fn tooltip(&self) -> &'static str {
+        match self {
+            TrayIconKind::Connected => "Twingate: Connected",
+            TrayIconKind::Disconnected => "Twingate: Disconnected",
+            TrayIconKind::Authenticating => "Twingate: Authenticating",
+            TrayIconKind::Error => "Twingate: Unable to reach the client",
+        }
+    }
+
+    fn png(&self, style: TrayIconStyle) -> &'static [u8] {
+        use TrayIconKind::*;
+        use TrayIconStyle::*;
+
+        match (self, style) {
+            (Connected, Color) => include_bytes!("../icons/tray/connected.png"),
+            (Connected, SymbolicLight) => include_bytes!("../icons/tray/connected-symbolic-light.png"),
+            (Connected, SymbolicDark) => include_bytes!("../icons/tray/connected-symbolic-dark.png"),
+            (Disconnected, Color) => include_bytes!("../icons/tray/disconnected.png"),
+            (Disconnected, SymbolicLight) => include_bytes!("../icons/tray/disconnected-symbolic-light.png"),
+            (Disconnected, SymbolicDark) => include_bytes!("../icons/tray/disconnected-symbolic-dark.png"),
+            (Authenticating, Color) => include_bytes!("../icons/tray/authenticating.png"),
+            (Authenticating, SymbolicLight) => include_bytes!("../icons/tray/authenticating-symbolic-light.png"),
+            (Authenticating, SymbolicDark) => include_bytes!("../icons/tray/authenticating-symbolic-dark.png"),
+            (Error, Color) => include_bytes!("../icons/tray/error.png"),
+            (Error, SymbolicLight) => include_bytes!("../icons/tray/error-symbolic-light.png"),
+            (Error, SymbolicDark) => include_bytes!("../icons/tray/error-symbolic-dark.png"),
+        }
+    }
+
+    /// Decode the embedded icon for this state in the given style
+    pub fn image(&self, style: TrayIconStyle) -> Result<Image<'static>> {
+        Ok(Image::from_bytes(self.png(style))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::ServiceStatus;
+    use crate::test_support::network;
+
+    #[test]
+    fn test_icon_kind_from_state() {
+        let mut state = AppState::new();
+        assert_eq!(TrayIconKind::from_state(&state), TrayIconKind::Disconnected);
+
+        state.update_network(Some(network(vec![]).build()));
+        assert_eq!(TrayIconKind::from_state(&state), TrayIconKind::Connected);
+
+        state.set_error("status poll failed".to_string());
+        assert_eq!(TrayIconKind::from_state(&state), TrayIconKind::Error);
+
+        state.set_authenticating("https://auth.example.com".to_string());
+        assert_eq!(TrayIconKind::from_state(&state), TrayIconKind::Authenticating);
+
+        state.update_network(None);
+        assert_eq!(TrayIconKind::from_state(&state), TrayIconKind::Disconnected);
+    }
+
+    #[test]
+    fn test_error_icon_wins_over_stale_connection() {
+        let mut state = AppState::new();
+        state.update_network(Some(network(vec![]).build()));
+        state.set_error("status poll failed".to_string());
+
+        assert_eq!(state.service_status(), &ServiceStatus::Connected);
+        assert_eq!(TrayIconKind::from_state(&state), TrayIconKind::Error);
+    }
+
+    #[test]
+    fn test_all_icons_decode() {
+        let kinds = [
+            TrayIconKind::Connected,
+            TrayIconKind::Disconnected,
+            TrayIconKind::Authenticating,
+            TrayIconKind::Error,
+        ];
+        let styles = [
+            TrayIconStyle::Color,
+            TrayIconStyle::SymbolicLight,
+            TrayIconStyle::SymbolicDark,
+        ];
+
+        for kind in kinds {
+            for style in styles {
+                let image = kind.image(style).unwrap();
+                assert_eq!((image.width(), image.height()), (64, 64), "{:?} {:?}", kind, style);
+            }
+        }
+    }
+
+    #[test]
+    fn test_icon_style_names() {
+        let style: TrayIconStyle = serde_json::from_str(r#""symbolic-light""#).unwrap();
+        assert_eq!(style, TrayIconStyle::SymbolicLight);
+        assert_eq!(serde_json::to_string(&TrayIconStyle::Color).unwrap(), r#""color""#);
+    }
+}
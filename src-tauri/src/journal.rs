@@ -0,0 +1,464 @@
+use crate::config::{AppConfig, DEFAULT_SERVICE_UNIT};
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+use tauri_plugin_shell::process::{CommandChild, CommandEvent};
+use tauri_plugin_shell::ShellExt;
+
+pub const LOGS_WINDOW_LABEL: &str = "logs";
+/// Emitted to the logs window for each new entry passing its filter
+pub const CLIENT_LOG_EVENT: &str = "client-log";
+/// Entries kept for re-filtering; older ones are dropped
+const MAX_BUFFERED_ENTRIES: usize = 2000;
+/// Journal lines loaded when the window opens
+const HISTORY_LINES: &str = "500";
+const JOURNALCTL_IDENTIFIER: &str = "journalctl";
+
+/// Journal priority, most severe first
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warning,
+    Notice,
+    #[default]
+    Info,
+    Debug,
+}
+
+impl LogLevel {
+    /// Map a syslog priority (0 emerg .. 7 debug) to a level
+    pub fn from_priority(priority: u8) -> Self {
+        match priority {
+            0..=3 => LogLevel::Error,
+            4 => LogLevel::Warning,
+            5 => LogLevel::Notice,
+            6 => LogLevel::Info,
+            _ => LogLevel::Debug,
+        }
+    }
+}
+
+/// One line of the client journal
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct JournalEntry {
+    #[serde(serialize_with = "serialize_rfc3339")]
+    pub timestamp: DateTime<Utc>,
+    pub level: LogLevel,
+    pub identifier: Option<String>,
+    pub pid: Option<u32>,
+    pub message: String,
+    /// Position in the journal, to pick up after this entry when following again
+    #[serde(skip)]
+    pub cursor: Option<String>,
+}
+
+impl JournalEntry {
+    /// Parse one line of `journalctl -o json` output
+    pub fn parse(line: &str) -> Option<Self> {
+        let fields: Value = serde_json::from_str(line).ok()?;
+        let field = |name: &str| fields.get(name).and_then(Value::as_str);
+
+        let timestamp = field("__REALTIME_TIMESTAMP")
+            .and_then(|micros| micros.parse().ok())
+            .and_then(DateTime::from_timestamp_micros)?;
+        let level = field("PRIORITY")
+            .and_then(|priority| priority.parse().ok())
+            .map(LogLevel::from_priority)
+            .unwrap_or_default();
+
+        Some(Self {
+            timestamp,
+            level,
+            identifier: field("SYSLOG_IDENTIFIER").map(str::to_string),
+            pid: field("_PID").and_then(|pid| pid.parse().ok()),
+            message: journal_message(fields.get("MESSAGE")?)?,
+            cursor: field("__CURSOR").map(str::to_string),
+        })
+    }
+
+    /// An entry describing something journalctl itself reported
+    fn from_journalctl(level: LogLevel, message: impl Into<String>) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            level,
+            identifier: Some(JOURNALCTL_IDENTIFIER.to_string()),
+            pid: None,
+            message: message.into(),
+            cursor: None,
+        }
+    }
+}
+
+fn serialize_rfc3339<S: serde::Serializer>(
+    timestamp: &DateTime<Utc>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_str(&timestamp.to_rfc3339())
+}
+
+/// Messages with non-UTF-8 or control bytes are exported as byte arrays
+fn journal_message(message: &Value) -> Option<String> {
+    match message {
+        Value::String(text) => Some(text.clone()),
+        Value::Array(bytes) => {
+            let bytes = bytes
+                .iter()
+                .map(|b| b.as_u64().and_then(|b| u8::try_from(b).ok()))
+                .collect::<Option<Vec<_>>>()?;
+            Some(String::from_utf8_lossy(&bytes).into_owned())
+        }
+        _ => None,
+    }
+}
+
+/// What the logs window asks to see
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct LogFilter {
+    /// Least severe level shown
+    pub level: LogLevel,
+    /// Case-insensitive text to look for in the message or identifier
+    pub search: String,
+}
+
+impl LogFilter {
+    pub fn matches(&self, entry: &JournalEntry) -> bool {
+        if entry.level > self.level {
+            return false;
+        }
+
+        let search = self.search.trim().to_lowercase();
+        search.is_empty()
+            || entry.message.to_lowercase().contains(&search)
+            || entry
+                .identifier
+                .as_ref()
+                .is_some_and(|identifier| identifier.to_lowercase().contains(&search))
+    }
+}
+
+/// Recent journal entries and the filter the logs window applies to them
+#[derive(Debug, Default)]
+pub struct LogBuffer {
+    entries: VecDeque<JournalEntry>,
+    filter: LogFilter,
+    /// Cursor of the last journal entry read
+    cursor: Option<String>,
+}
+
+impl LogBuffer {
+    /// Store an entry, returning it if the current filter lets it through
+    pub fn push(&mut self, entry: JournalEntry) -> Option<JournalEntry> {
+        if self.entries.len() == MAX_BUFFERED_ENTRIES {
+            self.entries.pop_front();
+        }
+        if let Some(cursor) = &entry.cursor {
+            self.cursor = Some(cursor.clone());
+        }
+        let shown = self.filter.matches(&entry).then(|| entry.clone());
+        self.entries.push_back(entry);
+        shown
+    }
+
+    /// Switch filters and return the buffered entries that pass the new one
+    pub fn set_filter(&mut self, filter: LogFilter) -> Vec<JournalEntry> {
+        self.filter = filter;
+        self.entries
+            .iter()
+            .filter(|entry| self.filter.matches(entry))
+            .cloned()
+            .collect()
+    }
+
+    pub fn cursor(&self) -> Option<&str> {
+        self.cursor.as_deref()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.cursor = None;
+    }
+}
+
+/// The journal follower and what it has read so far
+#[derive(Default)]
+pub struct ClientLogs {
+    buffer: Mutex<LogBuffer>,
+    follower: Mutex<Option<CommandChild>>,
+}
+
+impl ClientLogs {
+    /// Apply `filter` and return the matching entries read so far
+    pub fn set_filter(&self, filter: LogFilter) -> Vec<JournalEntry> {
+        self.buffer.lock().unwrap().set_filter(filter)
+    }
+
+    fn push(&self, entry: JournalEntry) -> Option<JournalEntry> {
+        self.buffer.lock().unwrap().push(entry)
+    }
+
+    /// Stop following and forget what was read, so reopening reloads history
+    pub fn stop(&self) {
+        if let Some(child) = self.follower.lock().unwrap().take() {
+            log::debug!("Stopping journal follower (pid {})", child.pid());
+            if let Err(e) = child.kill() {
+                log::warn!("Failed to stop journalctl: {}", e);
+            }
+        }
+        self.buffer.lock().unwrap().clear();
+    }
+}
+
+/// Arguments following `unit`: after the entry at `resume_after` when one was
+/// read already, so a restarted follower does not load history twice,
+/// otherwise starting with the most recent history
+fn journalctl_args(unit: &str, resume_after: Option<&str>) -> Vec<String> {
+    let mut args = vec!["--unit", unit, "--follow", "--output", "json", "--no-pager"];
+    match resume_after {
+        Some(cursor) => args.extend(["--after-cursor", cursor]),
+        None => args.extend(["--lines", HISTORY_LINES]),
+    }
+    args.into_iter().map(str::to_string).collect()
+}
+
+/// Start tailing the client unit's journal unless already doing so
+pub fn ensure_following(app_handle: &AppHandle) -> Result<()> {
+    let logs = app_handle.state::<ClientLogs>();
+    let mut follower = logs.follower.lock().unwrap();
+    if follower.is_some() {
+        return Ok(());
+    }
+
+    let unit = app_handle
+        .try_state::<AppConfig>()
+        .and_then(|config| config.service_unit().map(str::to_string))
+        .unwrap_or_else(|| DEFAULT_SERVICE_UNIT.to_string());
+    log::debug!("Following journal for {}", unit);
+
+    let resume_after = logs.buffer.lock().unwrap().cursor().map(str::to_string);
+    let (mut events, child) = app_handle
+        .shell()
+        .command("journalctl")
+        .args(journalctl_args(&unit, resume_after.as_deref()))
+        .spawn()?;
+    let pid = child.pid();
+    *follower = Some(child);
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let logs = app_handle.state::<ClientLogs>();
+
+        while let Some(event) = events.recv().await {
+            let entry = match event {
+                CommandEvent::Stdout(line) => match JournalEntry::parse(&String::from_utf8_lossy(&line)) {
+                    Some(entry) => entry,
+                    None => continue,
+                },
+                CommandEvent::Stderr(line) => JournalEntry::from_journalctl(
+                    LogLevel::Warning,
+                    String::from_utf8_lossy(&line).trim_end(),
+                ),
+                CommandEvent::Error(e) => JournalEntry::from_journalctl(LogLevel::Error, e),
+                CommandEvent::Terminated(payload) => {
+                    log::debug!("journalctl exited: {:?}", payload);
+                    let mut follower = logs.follower.lock().unwrap();
+                    // A newer follower may have replaced this one already
+                    if follower.as_ref().is_some_and(|child| child.pid() == pid) {
+                        follower.take();
+                    }
+                    drop(follower);
+                    match payload.code {
+                        Some(0) | None => break,
+                        Some(code) => JournalEntry::from_journalctl(
+                            LogLevel::Error,
+                            format!("journalctl exited with code {}", code),
+                        ),
+                    }
+                }
+                _ => continue,
+            };
+
+            if let Some(entry) = logs.push(entry) {
+                if let Err(e) = app_handle.emit_to(LOGS_WINDOW_LABEL, CLIENT_LOG_EVENT, entry) {
+                    log::warn!("Failed to send log entry to window: {}", e);
+                }
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Show the client logs window, creating it on first use
+pub fn show_logs_window(app_handle: &AppHandle) -> Result<()> {
+    if let Some(window) = app_handle.get_webview_window(LOGS_WINDOW_LABEL) {
+        window.show()?;
+        window.set_focus()?;
+        return Ok(());
+    }
+
+    let window = WebviewWindowBuilder::new(
+        app_handle,
+        LOGS_WINDOW_LABEL,
+        WebviewUrl::App("index.html#/logs".into()),
+    )
+    .title("Twingate Client Logs")
+    .inner_size(900.0, 600.0)
+    .build()?;
+
+    let app_handle = app_handle.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Destroyed = event {
+            app_handle.state::<ClientLogs>().stop();
+        }
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JOURNAL_LINE: &str = r#"{"__CURSOR":"s=1a2b;i=3c","__REALTIME_TIMESTAMP":"1700000000123456","PRIORITY":"3","SYSLOG_IDENTIFIER":"twingated","_PID":"812","MESSAGE":"Connection to controller failed","_SYSTEMD_UNIT":"twingate.service"}"#;
+
+    fn entry(level: LogLevel, message: &str) -> JournalEntry {
+        JournalEntry {
+            timestamp: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            level,
+            identifier: Some("twingated".to_string()),
+            pid: Some(812),
+            message: message.to_string(),
+            cursor: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_journal_line() {
+        let entry = JournalEntry::parse(JOURNAL_LINE).unwrap();
+
+        assert_eq!(entry.timestamp.timestamp_micros(), 1_700_000_000_123_456);
+        assert_eq!(entry.level, LogLevel::Error);
+        assert_eq!(entry.identifier.as_deref(), Some("twingated"));
+        assert_eq!(entry.pid, Some(812));
+        assert_eq!(entry.message, "Connection to controller failed");
+        assert_eq!(entry.cursor.as_deref(), Some("s=1a2b;i=3c"));
+    }
+
+    #[test]
+    fn test_entry_serializes_for_window() {
+        let json = serde_json::to_value(entry(LogLevel::Warning, "slow")).unwrap();
+
+        assert_eq!(json["timestamp"], "2023-11-14T22:13:20+00:00");
+        assert_eq!(json["level"], "warning");
+        assert_eq!(json["pid"], 812);
+    }
+
+    #[test]
+    fn test_parse_binary_message() {
+        let line = r#"{"__REALTIME_TIMESTAMP":"1700000000000000","MESSAGE":[104,105,27,91,48,109]}"#;
+        let entry = JournalEntry::parse(line).unwrap();
+
+        assert_eq!(entry.message, "hi\u{1b}[0m");
+        assert_eq!(entry.level, LogLevel::Info, "Missing priority defaults to info");
+        assert_eq!(entry.identifier, None);
+    }
+
+    #[test]
+    fn test_parse_rejects_incomplete_lines() {
+        assert_eq!(JournalEntry::parse(""), None);
+        assert_eq!(JournalEntry::parse("-- No entries --"), None);
+        assert_eq!(JournalEntry::parse(r#"{"MESSAGE":"no timestamp"}"#), None);
+        assert_eq!(JournalEntry::parse(r#"{"__REALTIME_TIMESTAMP":"1700000000000000"}"#), None);
+    }
+
+    #[test]
+    fn test_level_from_priority() {
+        assert_eq!(LogLevel::from_priority(0), LogLevel::Error);
+        assert_eq!(LogLevel::from_priority(4), LogLevel::Warning);
+        assert_eq!(LogLevel::from_priority(5), LogLevel::Notice);
+        assert_eq!(LogLevel::from_priority(6), LogLevel::Info);
+        assert_eq!(LogLevel::from_priority(7), LogLevel::Debug);
+    }
+
+    #[test]
+    fn test_filter_by_level_and_search() {
+        let filter = LogFilter {
+            level: LogLevel::Warning,
+            search: "CONTROLLER".to_string(),
+        };
+
+        assert!(filter.matches(&entry(LogLevel::Error, "Connection to controller failed")));
+        assert!(!filter.matches(&entry(LogLevel::Info, "Connected to controller")));
+        assert!(!filter.matches(&entry(LogLevel::Warning, "Resource list refreshed")));
+
+        let by_identifier = LogFilter {
+            level: LogLevel::Debug,
+            search: "twingated".to_string(),
+        };
+        assert!(by_identifier.matches(&entry(LogLevel::Debug, "anything")));
+    }
+
+    #[test]
+    fn test_log_filter_from_window() {
+        let filter: LogFilter = serde_json::from_str(r#"{ "level": "warning" }"#).unwrap();
+        assert_eq!(filter.level, LogLevel::Warning);
+        assert!(filter.search.is_empty());
+    }
+
+    #[test]
+    fn test_log_buffer_refilters_history() {
+        let mut buffer = LogBuffer::default();
+        assert!(buffer.push(entry(LogLevel::Debug, "verbose")).is_none());
+        assert!(buffer.push(entry(LogLevel::Error, "broken")).is_some());
+
+        let all = buffer.set_filter(LogFilter {
+            level: LogLevel::Debug,
+            search: String::new(),
+        });
+        assert_eq!(all.len(), 2);
+
+        let errors = buffer.set_filter(LogFilter {
+            level: LogLevel::Error,
+            search: String::new(),
+        });
+        assert_eq!(errors, vec![entry(LogLevel::Error, "broken")]);
+    }
+
+    #[test]
+    fn test_log_buffer_drops_oldest() {
+        let mut buffer = LogBuffer::default();
+        for i in 0..MAX_BUFFERED_ENTRIES + 5 {
+            buffer.push(entry(LogLevel::Info, &format!("line {}", i)));
+        }
+
+        let entries = buffer.set_filter(LogFilter::default());
+        assert_eq!(entries.len(), MAX_BUFFERED_ENTRIES);
+        assert_eq!(entries[0].message, "line 5");
+    }
+
+    #[test]
+    fn test_restarted_follower_resumes_after_last_entry() {
+        let mut buffer = LogBuffer::default();
+        assert_eq!(
+            journalctl_args("twingate.service", buffer.cursor()),
+            ["--unit", "twingate.service", "--follow", "--output", "json", "--no-pager", "--lines", HISTORY_LINES]
+        );
+
+        buffer.push(JournalEntry::parse(JOURNAL_LINE).unwrap());
+        buffer.push(JournalEntry::from_journalctl(LogLevel::Error, "journalctl exited with code 1"));
+        assert_eq!(buffer.cursor(), Some("s=1a2b;i=3c"));
+        assert_eq!(
+            journalctl_args("twingate.service", buffer.cursor()),
+            ["--unit", "twingate.service", "--follow", "--output", "json", "--no-pager", "--after-cursor", "s=1a2b;i=3c"]
+        );
+
+        buffer.clear();
+        assert_eq!(buffer.cursor(), None);
+    }
+}
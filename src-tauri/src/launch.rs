@@ -0,0 +1,384 @@
+use crate::aliases::DefaultAliases;
+use crate::config::AppConfig;
+use crate::error::{Result, TwingateError};
+use crate::managers::{CommandExecutor, NetworkDataManager, StateManager};
+use crate::models::Resource;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+/// A command offered in the submenu of matching resources, e.g.
+/// `["x-terminal-emulator", "-e", "ssh", "{address}"]` labelled "SSH"
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LaunchTemplate {
+    pub label: String,
+    /// Program followed by its arguments; placeholders are only allowed in
+    /// the arguments, and each argument stays a single argument once expanded
+    pub command: Vec<String>,
+    /// Resource types the action is offered for, case-insensitive; empty for any
+    #[serde(default)]
+    pub resource_types: Vec<String>,
+    /// Only offer the action for resources whose address matches
+    #[serde(default)]
+    pub address_pattern: Option<String>,
+}
+
+/// Resource field substituted for `{name}` in a template argument
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Placeholder {
+    /// The default alias if the resource has one, otherwise its address
+    Address,
+    Name,
+    Id,
+    Type,
+    OpenUrl,
+}
+
+impl Placeholder {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "address" => Some(Placeholder::Address),
+            "name" => Some(Placeholder::Name),
+            "id" => Some(Placeholder::Id),
+            "type" => Some(Placeholder::Type),
+            "open_url" => Some(Placeholder::OpenUrl),
+            _ => None,
+        }
+    }
+
+    /// `None` when the resource has nothing to fill in, like a resource that
+    /// cannot be opened in a browser for `{open_url}`
+    fn value<'a>(self, resource: &'a Resource, default_aliases: &DefaultAliases) -> Option<&'a str> {
+        match self {
+            Placeholder::Address => Some(default_aliases.address(resource)),
+            Placeholder::Name => Some(resource.name.as_str()),
+            Placeholder::Id => Some(resource.id.as_str()),
+            Placeholder::Type => Some(resource.resource_type.as_str()),
+            Placeholder::OpenUrl => default_aliases.open_url(resource),
+        }
+        .filter(|value| !value.is_empty())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token<'a> {
+    Text(&'a str),
+    Placeholder(&'a str),
+}
+
+/// Split an argument into text and `{placeholder}` names; braces around
+/// anything but lowercase letters and underscores are plain text
+fn tokens(arg: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = arg;
+    let mut text_start = 0;
+    let mut offset = 0;
+    while let Some(open) = rest.find('{') {
+        let after = &rest[open + 1..];
+        let name = after
+            .find('}')
+            .map(|close| &after[..close])
+            .filter(|name| !name.is_empty() && name.chars().all(|c| c.is_ascii_lowercase() || c == '_'));
+        match name {
+            Some(name) => {
+                if text_start < offset + open {
+                    tokens.push(Token::Text(&arg[text_start..offset + open]));
+                }
+                tokens.push(Token::Placeholder(name));
+                let consumed = open + name.len() + 2;
+                offset += consumed;
+                text_start = offset;
+                rest = &rest[consumed..];
+            }
+            None => {
+                offset += open + 1;
+                rest = after;
+            }
+        }
+    }
+    if text_start < arg.len() {
+        tokens.push(Token::Text(&arg[text_start..]));
+    }
+    tokens
+}
+
+/// A launch template checked and ready to run
+#[derive(Debug, Clone)]
+pub struct LaunchAction {
+    pub label: String,
+    program: String,
+    args: Vec<String>,
+    resource_types: Vec<String>,
+    address_pattern: Option<Regex>,
+}
+
+impl LaunchAction {
+    fn compile(template: &LaunchTemplate) -> std::result::Result<Self, String> {
+        let label = template.label.trim();
+        if label.is_empty() {
+            return Err("the label is empty".to_string());
+        }
+        let Some((program, args)) = template.command.split_first() else {
+            return Err("the command is empty".to_string());
+        };
+        if program.is_empty() || tokens(program).iter().any(|t| matches!(t, Token::Placeholder(_))) {
+            return Err("the program has to be a fixed name or path".to_string());
+        }
+        for arg in args {
+            for token in tokens(arg) {
+                if let Token::Placeholder(name) = token {
+                    if Placeholder::parse(name).is_none() {
+                        return Err(format!("unknown placeholder {{{}}}", name));
+                    }
+                }
+            }
+        }
+        let address_pattern = template
+            .address_pattern
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            label: label.to_string(),
+            program: program.clone(),
+            args: args.to_vec(),
+            resource_types: template.resource_types.clone(),
+            address_pattern,
+        })
+    }
+
+    /// Whether the action is offered for a resource: its type and address
+    /// fit, and it has a value for every placeholder
+    pub fn applies_to(&self, resource: &Resource, default_aliases: &DefaultAliases) -> bool {
+        let type_matches = self.resource_types.is_empty()
+            || self
+                .resource_types
+                .iter()
+                .any(|t| t.eq_ignore_ascii_case(&resource.resource_type));
+        let address_matches = self.address_pattern.as_ref().is_none_or(|pattern| {
+            pattern.is_match(&resource.address) || pattern.is_match(default_aliases.address(resource))
+        });
+        let placeholders_filled = self.args.iter().flat_map(|arg| tokens(arg)).all(|token| match token {
+            Token::Placeholder(name) => Placeholder::parse(name).is_some_and(|p| p.value(resource, default_aliases).is_some()),
+            Token::Text(_) => true,
+        });
+
+        type_matches && address_matches && placeholders_filled
+    }
+
+    /// The arguments with the resource's fields filled in.
+    ///
+    /// Values go into their argument verbatim and never through a shell, but
+    /// one starting an argument with `-` could still be taken for an option,
+    /// so those are refused.
+    pub fn args_for(&self, resource: &Resource, default_aliases: &DefaultAliases) -> Result<Vec<String>> {
+        self.args
+            .iter()
+            .map(|arg| {
+                let mut expanded = String::new();
+                for (position, token) in tokens(arg).into_iter().enumerate() {
+                    match token {
+                        Token::Text(text) => expanded.push_str(text),
+                        Token::Placeholder(name) => {
+                            let value = Placeholder::parse(name)
+                                .and_then(|p| p.value(resource, default_aliases))
+                                .ok_or_else(|| {
+                                    TwingateError::launch_failed(
+                                        &self.label,
+                                        format!("{} has no value for {{{}}}", resource.name, name),
+                                    )
+                                })?;
+                            if position == 0 && value.starts_with('-') {
+                                return Err(TwingateError::launch_failed(
+                                    &self.label,
+                                    format!("refusing to pass '{}' where it could be read as an option", value),
+                                ));
+                            }
+                            expanded.push_str(value);
+                        }
+                    }
+                }
+                Ok(expanded)
+            })
+            .collect()
+    }
+}
+
+/// The configured launch actions; menu ids refer to them by position
+#[derive(Debug, Clone, Default)]
+pub struct LaunchActions {
+    actions: Vec<LaunchAction>,
+}
+
+impl LaunchActions {
+    /// Check the config's templates, skipping any that are invalid
+    pub fn from_config(config: &AppConfig) -> Self {
+        let actions = config
+            .launch_actions
+            .iter()
+            .filter_map(|template| match LaunchAction::compile(template) {
+                Ok(action) => Some(action),
+                Err(e) => {
+                    log::warn!("Ignoring launch action '{}': {}", template.label, e);
+                    eprintln!("Warning: Ignoring launch action '{}': {}", template.label, e);
+                    None
+                }
+            })
+            .collect();
+
+        Self { actions }
+    }
+
+    pub fn get(&self, index: usize) -> Option<&LaunchAction> {
+        self.actions.get(index)
+    }
+
+    /// Actions offered for a resource with their positions, in config order
+    pub fn for_resource<'a>(
+        &'a self,
+        resource: &'a Resource,
+        default_aliases: &'a DefaultAliases,
+    ) -> impl Iterator<Item = (usize, &'a LaunchAction)> {
+        self.actions
+            .iter()
+            .enumerate()
+            .filter(move |(_, action)| action.applies_to(resource, default_aliases))
+    }
+}
+
+/// Run the launch action at `index` for a resource
+pub async fn run(app_handle: &AppHandle, resource_id: &str, index: usize) -> Result<()> {
+    let (action, default_aliases) = StateManager::with_state(app_handle, |state| {
+        let layout = state.menu_layout();
+        (layout.launch_actions.get(index).cloned(), layout.default_aliases.clone())
+    });
+    let action = action.ok_or_else(|| TwingateError::config_error(format!("there is no launch action {}", index)))?;
+
+    let network_manager = NetworkDataManager::new(app_handle, std::time::Duration::from_secs(30));
+    let network = network_manager.get_network_or_error().await?;
+    let resource = network
+        .resources
+        .iter()
+        .find(|r| r.id == resource_id)
+        .ok_or_else(|| TwingateError::resource_not_found(resource_id))?;
+
+    if !action.applies_to(resource, &default_aliases) {
+        return Err(TwingateError::launch_failed(
+            &action.label,
+            format!("not available for {}", resource.name),
+        ));
+    }
+    let args = action.args_for(resource, &default_aliases)?;
+
+    println!("Launching '{}' for {}", action.label, resource.name);
+    CommandExecutor::new(app_handle).launch(&action.program, &args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, ResourceBuilder};
+
+    fn resource(name: &str, address: &str, resource_type: &str) -> ResourceBuilder {
+        test_support::resource(&name.to_lowercase())
+            .name(name)
+            .address(address)
+            .resource_type(resource_type)
+    }
+
+    fn template(label: &str, command: &[&str], resource_types: &[&str], address_pattern: Option<&str>) -> LaunchTemplate {
+        LaunchTemplate {
+            label: label.to_string(),
+            command: command.iter().map(|s| s.to_string()).collect(),
+            resource_types: resource_types.iter().map(|s| s.to_string()).collect(),
+            address_pattern: address_pattern.map(str::to_string),
+        }
+    }
+
+    fn actions(templates: Vec<LaunchTemplate>) -> LaunchActions {
+        LaunchActions::from_config(&AppConfig {
+            launch_actions: templates,
+            ..AppConfig::default()
+        })
+    }
+
+    fn labels(actions: &LaunchActions, resource: &Resource) -> Vec<String> {
+        actions
+            .for_resource(resource, &DefaultAliases::default())
+            .map(|(_, a)| a.label.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_tokens() {
+        assert_eq!(
+            tokens("/v:{address}"),
+            vec![Token::Text("/v:"), Token::Placeholder("address")]
+        );
+        assert_eq!(
+            tokens("{name}@{address}"),
+            vec![Token::Placeholder("name"), Token::Text("@"), Token::Placeholder("address")]
+        );
+        assert_eq!(tokens("{print $1} {}"), vec![Token::Text("{print $1} {}")]);
+        assert_eq!(tokens(""), vec![]);
+    }
+
+    #[test]
+    fn test_invalid_templates_are_skipped() {
+        let actions = actions(vec![
+            template("SSH", &["ssh", "{address}"], &[], None),
+            template("", &["ssh", "{address}"], &[], None),
+            template("Empty", &[], &[], None),
+            template("Program", &["{name}", "--help"], &[], None),
+            template("Typo", &["ssh", "{adress}"], &[], None),
+            template("Pattern", &["ssh", "{address}"], &[], Some("(")),
+            template("psql", &["psql", "-h", "{address}"], &[], None),
+        ]);
+
+        let db = resource("Db", "db.corp.internal", "DNS").build();
+        assert_eq!(labels(&actions, &db), vec!["SSH", "psql"]);
+        assert_eq!(actions.get(1).map(|a| a.label.as_str()), Some("psql"));
+    }
+
+    #[test]
+    fn test_actions_match_type_address_and_placeholders() {
+        let actions = actions(vec![
+            template("RDP", &["xfreerdp", "/v:{address}"], &["ip", "cidr"], None),
+            template("psql", &["psql", "-h", "{address}"], &[], Some(r"^(db|pg)\.")),
+            template("Browse", &["firefox", "{open_url}"], &[], None),
+        ]);
+
+        let router = resource("Router", "10.0.0.1", "IP").build();
+        let db = resource("Db", "10.0.0.9", "DNS").alias("pg.corp.internal").build();
+        let wiki = resource("Wiki", "wiki.corp.internal", "DNS")
+            .with_alias("wiki.corp.internal", "https://wiki.corp.internal")
+            .open_in_browser()
+            .build();
+
+        assert_eq!(labels(&actions, &router), vec!["RDP"]);
+        assert_eq!(labels(&actions, &db), vec!["psql"]);
+        assert_eq!(labels(&actions, &wiki), vec!["Browse"]);
+    }
+
+    #[test]
+    fn test_args_are_expanded_without_splitting() {
+        let actions = actions(vec![template(
+            "SSH",
+            &["x-terminal-emulator", "-e", "ssh", "{address}", "-o", "Tag={name}; {type}"],
+            &[],
+            None,
+        )]);
+        let action = actions.get(0).unwrap();
+
+        let mut db = resource("Db $(reboot)", "db.corp.internal", "DNS").build();
+        assert_eq!(
+            action.args_for(&db, &DefaultAliases::default()).unwrap(),
+            vec!["-e", "ssh", "db.corp.internal", "-o", "Tag=Db $(reboot); DNS"]
+        );
+
+        db.address = "-oProxyCommand=reboot".to_string();
+        assert!(matches!(action.args_for(&db, &DefaultAliases::default()), Err(TwingateError::LaunchFailed { .. })));
+    }
+}
@@ -0,0 +1,205 @@
+use crate::aliases::DefaultAliases;
+use crate::dto::{visible, ResourceDto};
+use crate::error::{Result, TwingateError};
+use crate::models::{Network, Resource};
+use chrono::{DateTime, Utc};
+use tauri::{AppHandle, Manager, WebviewWindow, WindowEvent};
+
+/// Label of the launcher window from tauri.conf.json
+pub const LAUNCHER_WINDOW_LABEL: &str = "main";
+/// Most results the launcher lists for a query
+const MAX_RESULTS: usize = 50;
+
+/// Bonus for each matched character
+const MATCH_SCORE: i64 = 1;
+/// Bonus for a character matched right after the previous one
+const CONSECUTIVE_BONUS: i64 = 5;
+/// Bonus for a character matched at the start of a word
+const WORD_START_BONUS: i64 = 8;
+/// Bonus when the term appears unbroken in the text
+const SUBSTRING_BONUS: i64 = 20;
+/// Bonus when the text starts with the term
+const PREFIX_BONUS: i64 = 30;
+/// Bonus for matching the name rather than an address
+const NAME_BONUS: i64 = 10;
+
+/// Score `term` as a case-insensitive subsequence of `text`, higher is better.
+///
+/// `None` when some character of `term` does not occur in order in `text`.
+pub fn fuzzy_score(term: &str, text: &str) -> Option<i64> {
+    let term: Vec<char> = term.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let Some(&first) = term.first() else {
+        return Some(0);
+    };
+
+    // Matching greedily from the first occurrence can miss a tighter match
+    // later on, so try every place the term could start
+    text.iter()
+        .enumerate()
+        .filter(|(_, &c)| c == first)
+        .filter_map(|(start, _)| score_from(&term, &text, start))
+        .max()
+}
+
+fn score_from(term: &[char], text: &[char], start: usize) -> Option<i64> {
+    let mut score = 0;
+    let mut pos = start;
+    let mut last_match: Option<usize> = None;
+    for &wanted in term {
+        let found = pos + text[pos..].iter().position(|&c| c == wanted)?;
+        score += MATCH_SCORE;
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += WORD_START_BONUS;
+        }
+        match last_match {
+            Some(last) if found == last + 1 => score += CONSECUTIVE_BONUS,
+            Some(last) => score -= (found - last - 1) as i64,
+            None => {}
+        }
+        last_match = Some(found);
+        pos = found + 1;
+    }
+
+    let unbroken = last_match.is_some_and(|last| last + 1 - start == term.len());
+    if unbroken && start == 0 {
+        score += PREFIX_BONUS;
+    } else if unbroken {
+        score += SUBSTRING_BONUS;
+    }
+    Some(score)
+}
+
+/// Best score of `term` over the resource's name, address, alias and alias addresses
+fn best_field_score(term: &str, resource: &Resource) -> Option<i64> {
+    let name = fuzzy_score(term, &resource.name).map(|score| score + NAME_BONUS);
+    let addresses = std::iter::once(&resource.address)
+        .chain(resource.alias.as_ref())
+        .chain(resource.aliases.iter().map(|alias| &alias.address))
+        .filter_map(|address| fuzzy_score(term, address));
+
+    name.into_iter().chain(addresses).max()
+}
+
+/// Score of a resource for a whitespace-separated query; every term has to match
+fn resource_score(terms: &[&str], resource: &Resource) -> Option<i64> {
+    terms.iter().map(|term| best_field_score(term, resource)).sum()
+}
+
+/// Visible resources matching `query`, best match first.
+///
+/// An empty query lists every resource by name.
+pub fn search(network: &Network, default_aliases: &DefaultAliases, query: &str, now: DateTime<Utc>) -> Vec<ResourceDto> {
+    let terms: Vec<&str> = query.split_whitespace().collect();
+    let mut scored: Vec<(i64, &Resource)> = visible(network)
+        .filter_map(|resource| resource_score(&terms, resource).map(|score| (score, resource)))
+        .collect();
+
+    scored.sort_by(|(a_score, a), (b_score, b)| {
+        b_score
+            .cmp(a_score)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+    scored
+        .into_iter()
+        .take(MAX_RESULTS)
+        .map(|(_, resource)| ResourceDto::from_resource(resource, default_aliases, now))
+        .collect()
+}
+
+fn launcher_window(app_handle: &AppHandle) -> Result<WebviewWindow> {
+    app_handle
+        .get_webview_window(LAUNCHER_WINDOW_LABEL)
+        .ok_or_else(|| TwingateError::config_error("the launcher window is missing"))
+}
+
+/// Bring up the launcher window
+pub fn show_launcher_window(app_handle: &AppHandle) -> Result<()> {
+    let window = launcher_window(app_handle)?;
+    window.unminimize()?;
+    window.center()?;
+    window.show()?;
+    window.set_focus()?;
+    Ok(())
+}
+
+pub fn hide_launcher_window(app_handle: &AppHandle) -> Result<()> {
+    launcher_window(app_handle)?.hide()?;
+    Ok(())
+}
+
+/// Hide the launcher instead of destroying it when the window manager closes it
+pub fn keep_launcher_on_close(app_handle: &AppHandle) -> Result<()> {
+    let window = launcher_window(app_handle)?;
+    let hidden = window.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::CloseRequested { api, .. } = event {
+            api.prevent_close();
+            if let Err(e) = hidden.hide() {
+                log::warn!("Failed to hide launcher window: {}", e);
+            }
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{network, resource};
+
+    fn search_ids(network: &Network, query: &str) -> Vec<String> {
+        search(network, &DefaultAliases::default(), query, Utc::now()).into_iter().map(|r| r.id).collect()
+    }
+
+    #[test]
+    fn test_fuzzy_score_ranks_tighter_matches_higher() {
+        assert_eq!(fuzzy_score("xyz", "Database"), None);
+        assert_eq!(fuzzy_score("bd", "db"), None);
+
+        let prefix = fuzzy_score("data", "Database").unwrap();
+        let substring = fuzzy_score("base", "Database").unwrap();
+        let scattered = fuzzy_score("dtbs", "Database").unwrap();
+        assert!(prefix > substring);
+        assert!(substring > scattered);
+
+        // word starts beat letters buried inside words
+        assert!(fuzzy_score("bs", "Build Server").unwrap() > fuzzy_score("bs", "Lambdas").unwrap());
+        // the unbroken match later on wins over the scattered one at the start
+        let unbroken = fuzzy_score("corp", "x.corp").unwrap();
+        assert!(unbroken > fuzzy_score("corp", "c-o-r-p").unwrap());
+        assert_eq!(fuzzy_score("corp", "c-o-r-p.corp"), Some(unbroken));
+    }
+
+    #[test]
+    fn test_search_matches_names_addresses_and_aliases() {
+        let wiki = resource("r1").name("Wiki").address("10.0.0.5").alias("wiki.corp.internal");
+        let grafana = resource("r2")
+            .name("Dashboards")
+            .address("grafana.prod.svc")
+            .with_alias("metrics.corp.internal", "");
+        let hidden = resource("r3").name("Hidden Wiki").address("hidden.corp.internal").hidden();
+        let network = network(vec![wiki.build(), grafana.build(), hidden.build()]).build();
+
+        assert_eq!(search_ids(&network, ""), vec!["r2", "r1"]);
+        assert_eq!(search_ids(&network, "wiki"), vec!["r1"]);
+        assert_eq!(search_ids(&network, "10.0"), vec!["r1"]);
+        assert_eq!(search_ids(&network, "grafana"), vec!["r2"]);
+        assert_eq!(search_ids(&network, "metrics"), vec!["r2"]);
+        assert_eq!(search_ids(&network, "corp"), vec!["r2", "r1"]);
+        // every term has to match, each in any field
+        assert_eq!(search_ids(&network, "dash prod"), vec!["r2"]);
+        assert_eq!(search_ids(&network, "wiki prod"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_search_prefers_name_matches() {
+        let network = network(vec![
+            resource("r1").name("Jenkins").address("build.corp.internal").build(),
+            resource("r2").name("Build Cache").address("cache.corp.internal").build(),
+        ])
+        .build();
+
+        assert_eq!(search_ids(&network, "build"), vec!["r2", "r1"]);
+    }
+}
@@ -2,20 +2,52 @@ use arboard::Clipboard;
 use tauri::{tray::TrayIconBuilder, AppHandle, Manager};
 use tauri_plugin_single_instance::init as single_instance_init;
 
+mod aliases;
+mod args;
 mod auth;
+mod backend;
+mod cli;
 mod commands;
+mod config;
+mod control;
+mod dbus_service;
+mod deep_link;
+mod dto;
 mod error;
+mod events;
+mod expiry;
+mod grouping;
+mod icons;
+mod journal;
+mod launch;
+mod launcher;
 mod managers;
+mod menu;
 mod models;
+mod monitor;
 mod network;
+mod notifications;
+mod pins;
+mod probe;
+mod recent;
 mod state;
+mod systemd;
+#[cfg(test)]
+mod test_support;
 mod tray;
 mod utils;
 
 use auth::{handle_service_auth, start_resource_auth};
-use commands::greet;
+use backend::{CliBackend, TwingateBackend};
+use commands::{
+    authenticate_resource, copy_address, follow_client_logs, get_status, hide_launcher, list_resources,
+    open_resource, search_resources, start_service, stop_service,
+};
+use config::AppConfig;
 use error::{Result, TwingateError};
-use managers::{CommandExecutor, NetworkDataManager, StateManager, TrayManager};
+use events::{AppEvent, OperationFailed};
+use icons::TrayIconKind;
+use managers::{NetworkDataManager, StateManager, TrayManager};
 use network::get_network_data_with_retry;
 use state::AppState;
 use std::sync::Mutex;
@@ -23,16 +55,17 @@ use std::sync::Mutex;
 // Compatibility type alias for gradual migration
 type AppStateType = Mutex<AppState>;
 use tray::{
-    build_tray_menu, build_disconnected_menu, get_address_from_resource, get_open_url_from_resource, MenuAction, AUTHENTICATE_ID, COPY_ADDRESS_ID,
+    build_tray_menu, build_disconnected_menu, MenuAction,
     TWINGATE_TRAY_ID,
 };
 
-async fn handle_copy_address(app_handle: &AppHandle, address_id: &str) -> Result<()> {
-    let resource_id = address_id.split("-").last().ok_or_else(|| {
-        eprintln!("Error: Invalid address ID format: {}", address_id);
-        TwingateError::invalid_resource_id(address_id)
-    })?;
+fn alias_not_found(resource_id: &str, alias: &str) -> TwingateError {
+    eprintln!("Error: Alias {} not found for resource: {}", alias, resource_id);
+    TwingateError::resource_not_found(format!("{} alias {}", resource_id, alias))
+}
 
+/// Copy the address of `alias`, or the resource's default address without one
+async fn handle_copy_address(app_handle: &AppHandle, resource_id: &str, alias: Option<&str>) -> Result<()> {
     // Use NetworkDataManager to get network data with caching
     let network_manager = NetworkDataManager::new(app_handle, std::time::Duration::from_secs(30));
     let n = network_manager.get_network_or_error().await?;
@@ -51,7 +84,17 @@ async fn handle_copy_address(app_handle: &AppHandle, address_id: &str) -> Result
         e
     })?;
 
-    let address = get_address_from_resource(&n.resources[idx]);
+    let resource = &n.resources[idx];
+    let default_aliases = StateManager::with_state(app_handle, |state| state.default_aliases().clone());
+    let address = match alias {
+        Some(alias) => {
+            aliases::find_alias(resource, alias)
+                .ok_or_else(|| alias_not_found(resource_id, alias))?
+                .address
+                .as_str()
+        }
+        None => default_aliases.address(resource),
+    };
     clipboard.set_text(address).map_err(|e| {
         eprintln!("Error: Failed to copy address to clipboard: {}", e);
         e
@@ -61,7 +104,8 @@ async fn handle_copy_address(app_handle: &AppHandle, address_id: &str) -> Result
     Ok(())
 }
 
-async fn handle_open_in_browser(app_handle: &AppHandle, resource_id: &str) -> Result<()> {
+/// Open the URL of `alias`, or the resource's default URL without one
+async fn handle_open_in_browser(app_handle: &AppHandle, resource_id: &str, alias: Option<&str>) -> Result<()> {
     // Use NetworkDataManager to get network data with caching
     let network_manager = NetworkDataManager::new(app_handle, std::time::Duration::from_secs(30));
     let n = network_manager.get_network_or_error().await?;
@@ -75,7 +119,15 @@ async fn handle_open_in_browser(app_handle: &AppHandle, resource_id: &str) -> Re
             TwingateError::resource_not_found(resource_id)
         })?;
 
-    let open_url = get_open_url_from_resource(resource).ok_or_else(|| {
+    let default_aliases = StateManager::with_state(app_handle, |state| state.default_aliases().clone());
+    let open_url = match alias {
+        Some(alias) => {
+            let alias = aliases::find_alias(resource, alias).ok_or_else(|| alias_not_found(resource_id, alias))?;
+            aliases::alias_open_url(resource, alias)
+        }
+        None => default_aliases.open_url(resource),
+    };
+    let open_url = open_url.ok_or_else(|| {
         eprintln!("Error: Resource does not support opening in browser: {}", resource_id);
         TwingateError::invalid_resource_id(resource_id)
     })?;
@@ -140,7 +192,47 @@ async fn handle_copy_auth_url(app_handle: &AppHandle) -> Result<()> {
 
 
 
+/// Fetch network data now, ignoring the cache, and rebuild the tray
+async fn handle_refresh(app_handle: &AppHandle) -> Result<()> {
+    let authenticating = StateManager::with_state(app_handle, |state| {
+        matches!(state.service_status(), crate::state::ServiceStatus::Authenticating(_))
+    });
+    if authenticating {
+        println!("Authentication in progress - skipping refresh");
+        return Ok(());
+    }
+
+    NetworkDataManager::new(app_handle, std::time::Duration::ZERO)
+        .get_cached_or_refresh()
+        .await?;
+    TrayManager::rebuild_tray_now(app_handle).await
+}
+
+/// Run a menu action, and remember the resources it used. A failure is
+/// shown with the error icon until the next successful refresh, and told to
+/// the frontend with `operation-failed`
 async fn handle_menu_action(app_handle: &AppHandle, action: MenuAction) -> Result<()> {
+    run_menu_action(app_handle, action.clone()).await.inspect_err(|e| {
+        AppEvent::OperationFailed(OperationFailed::new(&action, e.to_string())).emit(app_handle);
+        StateManager::update_state(app_handle, |state| state.set_error(e.to_string()));
+        if let Err(e) = TrayManager::update_icon(app_handle) {
+            log::warn!("Failed to show error tray icon: {}", e);
+        }
+    })?;
+
+    if let MenuAction::CopyAddress(resource_id)
+    | MenuAction::OpenInBrowser(resource_id)
+    | MenuAction::Authenticate(resource_id)
+    | MenuAction::CopyAlias(resource_id, _)
+    | MenuAction::OpenAlias(resource_id, _)
+    | MenuAction::Launch(resource_id, _) = &action
+    {
+        recent::record_use(app_handle, resource_id).await;
+    }
+    Ok(())
+}
+
+async fn run_menu_action(app_handle: &AppHandle, action: MenuAction) -> Result<()> {
     match action {
         MenuAction::Quit => {
             println!("Quit menu item clicked - exiting application");
@@ -148,8 +240,8 @@ async fn handle_menu_action(app_handle: &AppHandle, action: MenuAction) -> Resul
         }
         MenuAction::StartService => {
             println!("Starting Twingate service...");
-            let executor = CommandExecutor::new(app_handle);
-            match executor.execute_twingate_elevated(&["start"]).await {
+            let backend = CliBackend::new(app_handle);
+            match backend.start().await {
                 Ok(output) => {
                     println!("Successfully started Twingate service");
                     println!("Output: {}", String::from_utf8_lossy(&output.stdout));
@@ -185,8 +277,8 @@ async fn handle_menu_action(app_handle: &AppHandle, action: MenuAction) -> Resul
         }
         MenuAction::StopService => {
             println!("Stopping Twingate service...");
-            let executor = CommandExecutor::new(app_handle);
-            match executor.execute_twingate_elevated(&["stop"]).await {
+            let backend = CliBackend::new(app_handle);
+            match backend.stop().await {
                 Ok(output) => {
                     println!("Successfully stopped Twingate service");
                     println!("Output: {}", String::from_utf8_lossy(&output.stdout));
@@ -201,13 +293,11 @@ async fn handle_menu_action(app_handle: &AppHandle, action: MenuAction) -> Resul
 
         MenuAction::CopyAddress(resource_id) => {
             println!("Copying address for resource: {}", resource_id);
-            let address_id = format!("{}-{}", COPY_ADDRESS_ID, resource_id);
-            handle_copy_address(app_handle, &address_id).await?;
+            handle_copy_address(app_handle, &resource_id, None).await?;
         }
         MenuAction::Authenticate(resource_id) => {
             println!("Starting authentication for resource: {}", resource_id);
-            let auth_id = format!("{}-{}", AUTHENTICATE_ID, resource_id);
-            start_resource_auth(app_handle, &auth_id)
+            start_resource_auth(app_handle, &resource_id)
                 .await
                 .map_err(|e| {
                     eprintln!(
@@ -219,7 +309,31 @@ async fn handle_menu_action(app_handle: &AppHandle, action: MenuAction) -> Resul
         }
         MenuAction::OpenInBrowser(resource_id) => {
             println!("Opening resource in browser: {}", resource_id);
-            handle_open_in_browser(app_handle, &resource_id).await?;
+            handle_open_in_browser(app_handle, &resource_id, None).await?;
+        }
+        MenuAction::CopyAlias(resource_id, alias) => {
+            println!("Copying alias {} of resource: {}", alias, resource_id);
+            handle_copy_address(app_handle, &resource_id, Some(&alias)).await?;
+        }
+        MenuAction::OpenAlias(resource_id, alias) => {
+            println!("Opening alias {} of resource in browser: {}", alias, resource_id);
+            handle_open_in_browser(app_handle, &resource_id, Some(&alias)).await?;
+        }
+        MenuAction::SetDefaultAlias(resource_id, alias) => {
+            println!("Using alias {} by default for resource: {}", alias, resource_id);
+            aliases::set_default_alias(app_handle, &resource_id, Some(&alias)).await?;
+        }
+        MenuAction::ClearDefaultAlias(resource_id) => {
+            println!("Clearing default alias for resource: {}", resource_id);
+            aliases::set_default_alias(app_handle, &resource_id, None).await?;
+        }
+        MenuAction::TestConnection(resource_id) => {
+            println!("Testing connection to resource: {}", resource_id);
+            probe::test_connection(app_handle, &resource_id).await?;
+        }
+        MenuAction::Launch(resource_id, index) => {
+            println!("Running launch action {} for resource: {}", index, resource_id);
+            launch::run(app_handle, &resource_id, index).await?;
         }
         MenuAction::OpenAuthUrl => {
             println!("Opening authentication URL...");
@@ -229,6 +343,26 @@ async fn handle_menu_action(app_handle: &AppHandle, action: MenuAction) -> Resul
             println!("Copying authentication URL to clipboard...");
             handle_copy_auth_url(app_handle).await?;
         }
+        MenuAction::ShowClientLogs => {
+            println!("Opening client logs window...");
+            journal::show_logs_window(app_handle)?;
+        }
+        MenuAction::PinResource(resource_id) => {
+            println!("Pinning resource: {}", resource_id);
+            pins::set_pinned(app_handle, &resource_id, true).await?;
+        }
+        MenuAction::UnpinResource(resource_id) => {
+            println!("Unpinning resource: {}", resource_id);
+            pins::set_pinned(app_handle, &resource_id, false).await?;
+        }
+        MenuAction::ShowLauncher => {
+            println!("Showing launcher window...");
+            launcher::show_launcher_window(app_handle)?;
+        }
+        MenuAction::Refresh => {
+            println!("Refreshing network data...");
+            handle_refresh(app_handle).await?;
+        }
         MenuAction::Unknown(event_id) => {
             eprintln!("Warning: Unhandled menu item: {}", event_id);
         }
@@ -253,21 +387,47 @@ fn create_menu_event_handler(builder: TrayIconBuilder<tauri::Wry>) -> TrayIconBu
     })
 }
 
+pub use cli::run_cli;
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
-        .plugin(single_instance_init(|_app, _argv, _cwd| {
-            println!("Second instance attempted - ignoring");
+        .plugin(single_instance_init(|app, argv, _cwd| {
+            println!("Second instance started with {:?}", argv);
+            args::handle_launch_args(app, argv);
         }))
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_opener::init())
         .manage(AppStateType::new(AppState::new()))
-        .invoke_handler(tauri::generate_handler![greet])
+        .manage(journal::ClientLogs::default())
+        .invoke_handler(tauri::generate_handler![
+            get_status,
+            list_resources,
+            start_service,
+            stop_service,
+            authenticate_resource,
+            copy_address,
+            open_resource,
+            follow_client_logs,
+            search_resources,
+            hide_launcher
+        ])
         .setup(|app| {
             println!("Initializing Twingate Linux application...");
             log::info!("Starting Twingate Linux application setup");
 
             let app_handle = app.app_handle().clone();
+            let config = AppConfig::load(&app_handle);
+            log::debug!("Loaded configuration: {:?}", config);
+            app.manage(config.clone());
+            StateManager::with_state_mut(&app_handle, |state| {
+                state.set_pins(pins::Pins::load(&app_handle));
+                state.set_default_aliases(aliases::DefaultAliases::load(&app_handle));
+                state.set_grouping(grouping::Grouping::from_config(&config));
+                state.set_recent(recent::RecentResources::load(&app_handle));
+                state.set_recent_count(config.recent_resources);
+                state.set_launch_actions(launch::LaunchActions::from_config(&config));
+            });
             
             log::debug!("Attempting to retrieve initial network data");
             
@@ -279,7 +439,7 @@ pub fn run() {
                 log::debug!("Starting initial network data retrieval with extended timeout");
                 
                 // Use extended retry count for initial startup
-                match get_network_data_with_retry(&app_handle, 10).await {
+                match get_network_data_with_retry(&CliBackend::new(&app_handle), 10).await {
                     Ok(data) => {
                         // Initialize state with network data
                         StateManager::update_network(&app_handle, data.clone());
@@ -323,7 +483,7 @@ pub fn run() {
                             log::debug!("Starting background network data retry");
                             tokio::time::sleep(std::time::Duration::from_millis(5000)).await;
                             
-                            match get_network_data_with_retry(&retry_app_handle, 5).await {
+                            match get_network_data_with_retry(&CliBackend::new(&retry_app_handle), 5).await {
                                 Ok(Some(network)) => {
                                     log::debug!("Background retry successful - updating state and rebuilding tray");
                                     let retry_app_handle_clone = retry_app_handle.clone();
@@ -377,22 +537,18 @@ pub fn run() {
                 }
             };
 
-            log::debug!("Getting default window icon");
-            let icon = app
-                .default_window_icon()
-                .ok_or_else(|| {
-                    log::error!("No default window icon found");
-                    eprintln!("Error: No default window icon found");
-                    tauri::Error::InvalidIcon(std::io::Error::new(
-                        std::io::ErrorKind::NotFound,
-                        "No default window icon",
-                    ))
-                })?
-                .clone();
+            let icon_kind = StateManager::with_state(app.app_handle(), TrayIconKind::from_state);
+            log::debug!("Loading {:?} tray icon", icon_kind);
+            let icon = icon_kind.image(config.tray_icon_style).map_err(|e| {
+                log::error!("Failed to load tray icon: {}", e);
+                eprintln!("Error: Failed to load tray icon: {}", e);
+                e
+            })?;
 
             log::debug!("Building tray icon");
             let tray_builder = TrayIconBuilder::with_id(TWINGATE_TRAY_ID)
                 .icon(icon)
+                .tooltip(icon_kind.tooltip())
                 .menu(&menu)
                 .show_menu_on_left_click(true);
 
@@ -400,6 +556,7 @@ pub fn run() {
 
             match tray_builder.build(app) {
                 Ok(_) => {
+                    StateManager::with_state_mut(app.app_handle(), |state| state.set_rendered_icon(icon_kind));
                     log::info!("Successfully created tray icon");
                     println!("Twingate Linux application initialized successfully");
                 }
@@ -410,6 +567,47 @@ pub fn run() {
                 }
             }
 
+            match config.poll_interval() {
+                Some(interval) => monitor::spawn_monitor(app_handle.clone(), interval),
+                None => {
+                    log::info!("Background status polling disabled in config");
+                    println!("Background status polling disabled");
+                }
+            }
+
+            match config.service_unit() {
+                Some(unit) => systemd::spawn_unit_watcher(app_handle.clone(), unit.to_string()),
+                None => log::info!("systemd unit watching disabled in config"),
+            }
+
+            match config.probe_interval() {
+                Some(interval) => probe::spawn_pinned_probes(app_handle.clone(), interval),
+                None => log::info!("Connection tests of pinned resources disabled in config"),
+            }
+
+            match config.auth_expiry_warning() {
+                Some(warn_before) => expiry::spawn_expiry_watcher(app_handle.clone(), warn_before),
+                None => log::info!("Auth expiry notifications disabled in config"),
+            }
+
+            if let Err(e) = launcher::keep_launcher_on_close(&app_handle) {
+                log::warn!("Failed to set up launcher window: {}", e);
+            }
+
+            args::handle_launch_args(app.app_handle(), std::env::args().collect());
+
+            if config.control_socket {
+                control::spawn_control_socket(app_handle.clone());
+            } else {
+                log::info!("Control socket disabled in config");
+            }
+
+            if config.dbus_service {
+                dbus_service::spawn_dbus_service(app_handle.clone());
+            } else {
+                log::info!("D-Bus service disabled in config");
+            }
+
             #[cfg(debug_assertions)]
             {
                 if let Some(window) = app.get_webview_window("main") {
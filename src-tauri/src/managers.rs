@@ -1,14 +1,20 @@
+use crate::backend::{CliBackend, TwingateBackend};
+use crate::config::AppConfig;
+use crate::dto::StatusDto;
 use crate::error::{Result, TwingateError};
+use crate::events::{emit_status_if_changed, AppEvent, AuthStarted, NetworkUpdated};
+use crate::icons::TrayIconKind;
 use crate::models::Network;
 use crate::network::get_network_data;
+use crate::notifications::{notify_resource_changes, ResourceChanges};
 use crate::state::AppState;
-use crate::tray::{build_tray_menu, TWINGATE_TRAY_ID};
+use crate::menu::MenuModel;
+use crate::tray::{render_menu, TWINGATE_TRAY_ID};
 use crate::utils::{extract_url_from_text, extract_url_with_pattern};
-use std::str;
 use std::sync::Mutex;
 use std::time::Duration;
 use tauri::{AppHandle, Manager};
-use tauri_plugin_shell::{ShellExt, process::Output};
+use tauri_plugin_shell::{ShellExt, process::{CommandEvent, Output}};
 use tokio::time::sleep;
 
 /// Manages network data fetching with caching and refresh logic
@@ -38,13 +44,10 @@ impl<'a> NetworkDataManager<'a> {
 
         if needs_refresh {
             log::debug!("Network data is stale, refreshing...");
-            match get_network_data(self.app_handle).await {
+            match get_network_data(&CliBackend::new(self.app_handle)).await {
                 Ok(fresh_data) => {
                     // Update state with fresh data
-                    {
-                        let mut state_guard = state.lock().unwrap();
-                        state_guard.update_network(fresh_data.clone());
-                    }
+                    StateManager::update_network(self.app_handle, fresh_data.clone());
                     log::debug!("Successfully refreshed network data");
                     Ok(fresh_data)
                 }
@@ -101,18 +104,41 @@ impl StateManager {
         })
     }
 
+    /// Apply `f` to the state, emitting `status-changed` if the reported status changed
+    pub fn update_state<F, R>(app_handle: &AppHandle, f: F) -> R
+    where
+        F: FnOnce(&mut AppState) -> R,
+    {
+        let (before, result, after) = Self::with_state_mut(app_handle, |state| {
+            let before = StatusDto::from_state(state);
+            let result = f(state);
+            (before, result, StatusDto::from_state(state))
+        });
+        emit_status_if_changed(app_handle, &before, after);
+        result
+    }
+
     /// Set the application to authenticating state
     pub fn set_authenticating(app_handle: &AppHandle, auth_url: String) {
-        Self::with_state_mut(app_handle, |state| {
-            state.set_authenticating(auth_url);
+        Self::update_state(app_handle, |state| {
+            state.set_authenticating(auth_url.clone());
         });
+        AppEvent::AuthStarted(AuthStarted {
+            resource_id: None,
+            auth_url: Some(auth_url),
+        })
+        .emit(app_handle);
     }
 
-    /// Update network data in state
+    /// Update network data in state, announcing any added or removed resources
     pub fn update_network(app_handle: &AppHandle, network: Option<Network>) {
-        Self::with_state_mut(app_handle, |state| {
+        let (changes, updated) = Self::update_state(app_handle, |state| {
+            let changes = ResourceChanges::between(state.network(), network.as_ref());
             state.update_network(network);
+            (changes.clone(), NetworkUpdated::from_state(state, changes))
         });
+        AppEvent::NetworkUpdated(updated).emit(app_handle);
+        notify_resource_changes(app_handle, changes);
     }
 }
 
@@ -145,15 +171,10 @@ impl AuthStateManager {
     }
 
     /// Check service status and extract auth URL if available
-    pub async fn check_auth_status(app_handle: &AppHandle) -> Result<Option<String>> {
-        let shell = app_handle.shell();
-        let status_output = shell
-            .command("twingate")
-            .args(["status"])
-            .output()
-            .await?;
+    pub async fn check_auth_status<B: TwingateBackend>(backend: &B) -> Result<Option<String>> {
+        let status_output = backend.status().await?;
 
-        let status_str = str::from_utf8(&status_output.stdout)?;
+        let status_str = status_output.stdout_str()?;
         log::debug!("Service status output: {}", status_str);
 
         if Self::is_auth_required(status_str) {
@@ -212,6 +233,41 @@ impl<'a> CommandExecutor<'a> {
         }
     }
 
+    /// Start a program without waiting for it to exit, logging its stderr and
+    /// any failure once it does
+    pub fn launch(&self, command: &str, args: &[String]) -> Result<()> {
+        log::debug!("Launching command: {} {}", command, args.join(" "));
+
+        let (mut events, _child) = self
+            .app_handle
+            .shell()
+            .command(command)
+            .args(args)
+            .spawn()
+            .map_err(|e| {
+                log::error!("Failed to launch command '{}': {}", command, e);
+                TwingateError::from(e)
+            })?;
+
+        let command = command.to_string();
+        tauri::async_runtime::spawn(async move {
+            while let Some(event) = events.recv().await {
+                match event {
+                    CommandEvent::Stderr(line) => {
+                        log::warn!("{}: {}", command, String::from_utf8_lossy(&line).trim_end());
+                    }
+                    CommandEvent::Error(e) => log::error!("{}: {}", command, e),
+                    CommandEvent::Terminated(payload) => match payload.code {
+                        Some(0) => log::debug!("Command '{}' exited", command),
+                        code => log::warn!("Command '{}' exited with {:?}", command, code),
+                    },
+                    _ => {}
+                }
+            }
+        });
+        Ok(())
+    }
+
     /// Execute a Twingate command (convenience method)
     pub async fn execute_twingate(&self, args: &[&str]) -> Result<Output> {
         self.execute("twingate", args).await
@@ -225,6 +281,9 @@ impl<'a> CommandExecutor<'a> {
     }
 }
 
+const MAX_REBUILD_RETRIES: u32 = 3;
+const REBUILD_RETRY_DELAY_MS: u64 = 3000;
+
 /// Manages tray operations with centralized logic
 pub struct TrayManager;
 
@@ -233,13 +292,13 @@ impl TrayManager {
     pub async fn rebuild_tray_now(app_handle: &AppHandle) -> Result<()> {
         log::debug!("Rebuilding tray menu immediately");
         
-        // Get current network data from state
-        let network_data = StateManager::with_state(app_handle, |state| {
-            state.network().cloned()
-        });
+        // Lay out the menu from current state
+        let now = chrono::Local::now();
+        let model = StateManager::with_state(app_handle, |state| MenuModel::from_state(state, &now));
+        log::debug!("Tray menu layout:\n{}", model.to_outline());
 
         // Build and set the tray menu
-        match build_tray_menu(app_handle, network_data).await {
+        match render_menu(app_handle, &model) {
             Ok(menu) => match app_handle.tray_by_id(TWINGATE_TRAY_ID) {
                 Some(tray) => {
                     if let Err(e) = tray.set_menu(Some(menu)) {
@@ -247,6 +306,10 @@ impl TrayManager {
                         Err(TwingateError::from(e))
                     } else {
                         log::debug!("Successfully updated tray menu");
+                        StateManager::with_state_mut(app_handle, |state| state.set_rendered_menu(model));
+                        if let Err(e) = Self::update_icon(app_handle) {
+                            log::warn!("Failed to update tray icon: {}", e);
+                        }
                         Ok(())
                     }
                 }
@@ -262,82 +325,126 @@ impl TrayManager {
         }
     }
 
+    /// Switch the tray icon to match the current state, if it changed
+    pub fn update_icon(app_handle: &AppHandle) -> Result<()> {
+        let (kind, shown) = StateManager::with_state(app_handle, |state| {
+            (TrayIconKind::from_state(state), state.rendered_icon())
+        });
+        if shown == Some(kind) {
+            return Ok(());
+        }
+
+        let style = app_handle
+            .try_state::<AppConfig>()
+            .map(|config| config.tray_icon_style)
+            .unwrap_or_default();
+        let tray = app_handle.tray_by_id(TWINGATE_TRAY_ID).ok_or_else(|| {
+            log::error!("Tray icon not found with ID: {}", TWINGATE_TRAY_ID);
+            TwingateError::ServiceNotRunning
+        })?;
+
+        log::debug!("Switching tray icon to {:?}", kind);
+        tray.set_icon(Some(kind.image(style)?))?;
+        tray.set_tooltip(Some(kind.tooltip()))?;
+        StateManager::with_state_mut(app_handle, |state| state.set_rendered_icon(kind));
+        Ok(())
+    }
+
     /// Rebuild tray menu after a delay with retry logic  
     pub fn rebuild_tray_after_delay(app_handle: AppHandle) {
         tauri::async_runtime::spawn(async move {
             // Use longer initial delay during authentication flow
             sleep(Duration::from_millis(2000)).await;
 
-            let mut retry_count = 0;
-            const MAX_REBUILD_RETRIES: u32 = 3;
-            const REBUILD_RETRY_DELAY_MS: u64 = 3000;
-
-            loop {
-                log::debug!(
-                    "Attempting tray rebuild (attempt {} of {})",
-                    retry_count + 1,
-                    MAX_REBUILD_RETRIES + 1
-                );
-
-                let _network_data = match get_network_data(&app_handle).await {
-                    Ok(data) => {
-                        // Update state with fresh data
-                        StateManager::update_network(&app_handle, data.clone());
-
-                        match &data {
-                            Some(network) => {
-                                log::debug!(
-                                    "Successfully refreshed network data for tray menu - User: {}",
-                                    network.user.email
-                                );
-                            }
-                            None => {
-                                log::debug!("Twingate service is not running - showing disconnected menu");
-                            }
+            match Self::fetch_network_for_rebuild(&CliBackend::new(&app_handle)).await {
+                Ok(data) => {
+                    // Update state with fresh data
+                    StateManager::update_network(&app_handle, data.clone());
+
+                    match &data {
+                        Some(network) => {
+                            log::debug!(
+                                "Successfully refreshed network data for tray menu - User: {}",
+                                network.user.email
+                            );
                         }
-                        data
-                    }
-                    Err(TwingateError::ServiceConnecting) | Err(TwingateError::AuthenticationRequired) => {
-                        log::debug!("Service in transitional state during tray rebuild, will retry");
-
-                        if retry_count >= MAX_REBUILD_RETRIES {
-                            log::warn!("Exhausted retries for tray rebuild during authentication flow");
-                            None
-                        } else {
-                            retry_count += 1;
-                            log::debug!("Waiting {}ms before retry", REBUILD_RETRY_DELAY_MS);
-                            sleep(Duration::from_millis(REBUILD_RETRY_DELAY_MS)).await;
-                            continue;
+                        None => {
+                            log::debug!("Twingate service is not running - showing disconnected menu");
                         }
                     }
-                    Err(TwingateError::RetryLimitExceeded { .. }) => {
-                        log::warn!(
-                            "Network data retrieval retry limit exceeded during tray rebuild"
-                        );
-                        None
-                    }
-                    Err(e) => {
-                        log::error!("Error getting network data for tray rebuild: {}", e);
-                        None
-                    }
-                };
-
-                // Build and set the tray menu
-                if let Err(e) = Self::rebuild_tray_now(&app_handle).await {
-                    log::error!("Failed to rebuild tray menu: {}", e);
                 }
+                Err(TwingateError::ServiceConnecting) | Err(TwingateError::AuthenticationRequired) => {
+                    log::debug!("Service still settling - keeping current tray state");
+                }
+                Err(TwingateError::RetryLimitExceeded { .. }) => {
+                    log::warn!(
+                        "Network data retrieval retry limit exceeded during tray rebuild"
+                    );
+                }
+                Err(e) => {
+                    log::error!("Error getting network data for tray rebuild: {}", e);
+                }
+            }
 
-                break;
+            // Build and set the tray menu
+            if let Err(e) = Self::rebuild_tray_now(&app_handle).await {
+                log::error!("Failed to rebuild tray menu: {}", e);
             }
         });
     }
+
+    /// Fetch network data for a tray rebuild, retrying while the service is
+    /// still connecting or waiting for authentication
+    pub async fn fetch_network_for_rebuild<B: TwingateBackend>(backend: &B) -> Result<Option<Network>> {
+        let mut retry_count = 0;
+
+        loop {
+            log::debug!(
+                "Attempting tray rebuild (attempt {} of {})",
+                retry_count + 1,
+                MAX_REBUILD_RETRIES + 1
+            );
+
+            match get_network_data(backend).await {
+                Err(e @ (TwingateError::ServiceConnecting | TwingateError::AuthenticationRequired)) => {
+                    log::debug!("Service in transitional state during tray rebuild, will retry");
+
+                    if retry_count >= MAX_REBUILD_RETRIES {
+                        log::warn!("Exhausted retries for tray rebuild during authentication flow");
+                        return Err(e);
+                    }
+
+                    retry_count += 1;
+                    log::debug!("Waiting {}ms before retry", REBUILD_RETRY_DELAY_MS);
+                    sleep(Duration::from_millis(REBUILD_RETRY_DELAY_MS)).await;
+                }
+                result => return result,
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backend::scripted::{block_on_paused, BackendCall, ScenarioBackend, ScriptedBackend};
     use crate::models::{Network, User, InternetSecurity};
 
+    const RESOURCES_JSON: &str = r#"{
+        "admin_url": "https://admin.twingate.com",
+        "full_tunnel_time_limit": 0,
+        "internet_security": { "mode": 0, "status": 0 },
+        "resources": [],
+        "user": {
+            "avatar_url": "",
+            "email": "test@example.com",
+            "first_name": "Test",
+            "id": "user-123",
+            "is_admin": false,
+            "last_name": "User"
+        }
+    }"#;
+
     fn create_test_network() -> Network {
         Network {
             admin_url: "https://admin.twingate.com".to_string(),
@@ -372,4 +479,83 @@ mod tests {
         // These would be integration tested with a real AppHandle
         assert!(std::mem::size_of::<StateManager>() == 0); // Zero-sized type
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_check_auth_status_extracts_url() {
+        let backend = ScriptedBackend::new().reply(
+            BackendCall::Status,
+            &["User authentication is required. Please visit: https://example.twingate.com/auth/abc123"],
+        );
+
+        let url = block_on_paused(AuthStateManager::check_auth_status(&backend)).unwrap();
+
+        assert_eq!(url, Some("https://example.twingate.com/auth/abc123".to_string()));
+    }
+
+    #[test]
+    fn test_check_auth_status_required_without_url() {
+        let backend = ScriptedBackend::new().reply(BackendCall::Status, &["authentication is required"]);
+
+        let url = block_on_paused(AuthStateManager::check_auth_status(&backend)).unwrap();
+
+        assert_eq!(url, None);
+    }
+
+    #[test]
+    fn test_check_auth_status_not_required() {
+        let backend = ScriptedBackend::new().reply(BackendCall::Status, &["online"]);
+
+        let url = block_on_paused(AuthStateManager::check_auth_status(&backend)).unwrap();
+
+        assert_eq!(url, None);
+    }
+
+    #[test]
+    fn test_check_auth_status_propagates_backend_error() {
+        let backend = ScriptedBackend::new().fail(BackendCall::Status, "twingate: not found");
+
+        let result = block_on_paused(AuthStateManager::check_auth_status(&backend));
+
+        assert!(matches!(result, Err(TwingateError::CommandFailed { .. })));
+    }
+
+    #[test]
+    fn test_fetch_network_for_rebuild_waits_out_authentication() {
+        let backend = ScenarioBackend::from_json(&format!(
+            r#"{{
+                "steps": [
+                    {{ "status": "starting", "calls": 2 }},
+                    {{ "status": "User authentication is required", "calls": 2 }},
+                    {{ "status": "online", "resources": {} }}
+                ]
+            }}"#,
+            RESOURCES_JSON
+        ));
+
+        let network = block_on_paused(TrayManager::fetch_network_for_rebuild(&backend))
+            .unwrap()
+            .expect("Expected network data after authentication");
+
+        assert_eq!(network.user.email, "test@example.com");
+        assert_eq!(backend.cursor().step, 2);
+    }
+
+    #[test]
+    fn test_fetch_network_for_rebuild_gives_up_while_auth_required() {
+        let backend = ScriptedBackend::new().reply(BackendCall::Status, &["authentication is required"]);
+
+        let result = block_on_paused(TrayManager::fetch_network_for_rebuild(&backend));
+
+        assert!(matches!(result, Err(TwingateError::AuthenticationRequired)));
+        assert_eq!(backend.call_count(BackendCall::Status), (MAX_REBUILD_RETRIES + 1) as usize);
+    }
+
+    #[test]
+    fn test_fetch_network_for_rebuild_not_running() {
+        let backend = ScenarioBackend::from_json(r#"{ "steps": [{ "status": "not-running" }] }"#);
+
+        let result = block_on_paused(TrayManager::fetch_network_for_rebuild(&backend));
+
+        assert!(matches!(result, Ok(None)));
+    }
+}
@@ -0,0 +1,954 @@
+use crate::aliases::{alias_open_url, DefaultAliases};
+use crate::grouping::Grouping;
+use crate::launch::LaunchActions;
+use crate::models::{Network, Resource};
+use crate::pins::Pins;
+use crate::probe::ProbeResult;
+use crate::recent::RecentResources;
+use crate::state::{AppState, ServiceStatus};
+use crate::tray::MenuId;
+use crate::utils::format_duration_short;
+use chrono::{DateTime, TimeZone};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+/// Absolute expiry times are shown in the time zone of the `now` passed in
+const EXPIRY_TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// A single entry in a tray menu, independent of any UI toolkit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MenuNode {
+    Item {
+        id: MenuId,
+        label: String,
+        enabled: bool,
+    },
+    Separator,
+    Submenu {
+        id: MenuId,
+        label: String,
+        enabled: bool,
+        children: Vec<MenuNode>,
+    },
+}
+
+impl MenuNode {
+    pub fn item(id: MenuId, label: impl Into<String>, enabled: bool) -> Self {
+        Self::Item {
+            id,
+            label: label.into(),
+            enabled,
+        }
+    }
+
+    pub fn submenu(id: MenuId, label: impl Into<String>, children: Vec<MenuNode>) -> Self {
+        Self::Submenu {
+            id,
+            label: label.into(),
+            enabled: true,
+            children,
+        }
+    }
+
+    fn write_outline(&self, out: &mut String, depth: usize) {
+        let indent = "  ".repeat(depth);
+        match self {
+            MenuNode::Item { id, label, enabled } => {
+                out.push_str(&format!("{}{} [{}]", indent, label, id.encode()));
+                if !enabled {
+                    out.push_str(" (disabled)");
+                }
+                out.push('\n');
+            }
+            MenuNode::Separator => {
+                out.push_str(&format!("{}---\n", indent));
+            }
+            MenuNode::Submenu {
+                id,
+                label,
+                enabled,
+                children,
+            } => {
+                out.push_str(&format!("{}{} [{}] >", indent, label, id.encode()));
+                if !enabled {
+                    out.push_str(" (disabled)");
+                }
+                out.push('\n');
+                for child in children {
+                    child.write_outline(out, depth + 1);
+                }
+            }
+        }
+    }
+}
+
+/// User choices, and what the tray found out itself, that shape the connected menu
+#[derive(Debug, Clone, Default)]
+pub struct MenuLayout {
+    pub pins: Pins,
+    pub default_aliases: DefaultAliases,
+    pub grouping: Grouping,
+    pub recent: RecentResources,
+    /// Most resources in the "Recently Used" section
+    pub recent_count: usize,
+    pub launch_actions: LaunchActions,
+    /// Latest connection test of each resource tested, by resource id
+    pub probes: BTreeMap<String, ProbeResult>,
+}
+
+impl MenuLayout {
+    /// Ids in the "Recently Used" section: visible resources by last use,
+    /// leaving out pinned ones, which are already at the top
+    pub fn recent_section_ids(&self, network: Option<&Network>) -> Vec<String> {
+        let Some(network) = network else {
+            return Vec::new();
+        };
+        self.recent
+            .resource_ids()
+            .filter(|id| !self.pins.contains(id))
+            .filter(|id| network.resources.iter().any(|r| r.id == *id && r.client_visibility != 0))
+            .take(self.recent_count)
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+/// The complete tray menu layout for one application state
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MenuModel {
+    pub items: Vec<MenuNode>,
+}
+
+impl MenuModel {
+    /// Build the menu matching the current application state at time `now`
+    pub fn from_state<Tz: TimeZone>(state: &AppState, now: &DateTime<Tz>) -> Self
+    where
+        Tz::Offset: Display,
+    {
+        Self::build(state.service_status(), state.network(), state.menu_layout(), now)
+    }
+
+    pub fn build<Tz: TimeZone>(
+        service_status: &ServiceStatus,
+        network: Option<&Network>,
+        layout: &MenuLayout,
+        now: &DateTime<Tz>,
+    ) -> Self
+    where
+        Tz::Offset: Display,
+    {
+        match service_status {
+            ServiceStatus::Authenticating(auth_url) => authenticating_menu(auth_url),
+            _ => match network {
+                Some(n) => connected_menu(n, layout, now),
+                None => disconnected_menu(),
+            },
+        }
+    }
+
+    /// Render the menu as an indented text outline, one entry per line
+    pub fn to_outline(&self) -> String {
+        let mut out = String::new();
+        for item in &self.items {
+            item.write_outline(&mut out, 0);
+        }
+        out
+    }
+}
+
+pub fn connected_menu<Tz: TimeZone>(network: &Network, layout: &MenuLayout, now: &DateTime<Tz>) -> MenuModel
+where
+    Tz::Offset: Display,
+{
+    let visible_resources: Vec<_> = network
+        .resources
+        .iter()
+        .filter(|r| r.client_visibility != 0)
+        .collect();
+
+    let mut items = user_status_section(network);
+    items.push(MenuNode::Separator);
+    items.extend(pinned_section(&visible_resources, layout, now));
+    items.extend(recent_section(network, &visible_resources, layout, now));
+    items.extend(resources_section(&visible_resources, layout, now));
+    items.extend(footer_section());
+
+    MenuModel { items }
+}
+
+pub fn disconnected_menu() -> MenuModel {
+    let mut items = vec![MenuNode::item(MenuId::StartService, "Start Twingate", true)];
+    items.extend(footer_section());
+
+    MenuModel { items }
+}
+
+pub fn authenticating_menu(_auth_url: &str) -> MenuModel {
+    let mut items = vec![
+        MenuNode::item(MenuId::AuthStatus, "Authenticating...", false),
+        MenuNode::Separator,
+        MenuNode::item(MenuId::OpenAuthUrl, "Open Authentication URL", true),
+        MenuNode::item(MenuId::CopyAuthUrl, "Copy Authentication URL", true),
+    ];
+    items.extend(footer_section());
+
+    MenuModel { items }
+}
+
+/// Entries at the bottom of every menu
+fn footer_section() -> Vec<MenuNode> {
+    vec![
+        MenuNode::Separator,
+        MenuNode::item(MenuId::ShowClientLogs, "Show Client Logs", true),
+        MenuNode::item(MenuId::Quit, "Close Tray", true),
+    ]
+}
+
+pub fn resource_menu<Tz: TimeZone>(resource: &Resource, layout: &MenuLayout, now: &DateTime<Tz>) -> MenuNode
+where
+    Tz::Offset: Display,
+{
+    let mut children = vec![
+        MenuNode::item(
+            MenuId::ResourceAddress(resource.id.clone()),
+            layout.default_aliases.address(resource),
+            false,
+        ),
+        MenuNode::item(MenuId::CopyAddress(resource.id.clone()), "Copy Address", true),
+    ];
+
+    // Add "Open in Browser" menu item if resource supports it
+    if layout.default_aliases.open_url(resource).is_some() {
+        children.push(MenuNode::item(
+            MenuId::OpenInBrowser(resource.id.clone()),
+            "Open in Browser...",
+            true,
+        ));
+    }
+
+    children.extend(aliases_menu(resource, &layout.default_aliases));
+    children.extend(layout.launch_actions.for_resource(resource, &layout.default_aliases).map(|(index, action)| {
+        MenuNode::item(MenuId::Launch(resource.id.clone(), index), &action.label, true)
+    }));
+
+    children.push(MenuNode::Separator);
+    children.extend(auth_menu(resource, now));
+
+    children.push(MenuNode::Separator);
+    if let Some(result) = layout.probes.get(&resource.id) {
+        children.push(MenuNode::item(
+            MenuId::ConnectionStatus(resource.id.clone()),
+            result.summary(),
+            false,
+        ));
+    }
+    children.push(MenuNode::item(MenuId::TestConnection(resource.id.clone()), "Test Connection", true));
+
+    children.push(MenuNode::Separator);
+    children.push(if layout.pins.contains(&resource.id) {
+        MenuNode::item(MenuId::UnpinResource(resource.id.clone()), "Unpin from Top", true)
+    } else {
+        MenuNode::item(MenuId::PinResource(resource.id.clone()), "Pin to Top", true)
+    });
+
+    MenuNode::submenu(MenuId::Resource(resource.id.clone()), &resource.name, children)
+}
+
+/// Every alias of a resource with its own actions, marking the chosen default
+fn aliases_menu(resource: &Resource, default_aliases: &DefaultAliases) -> Option<MenuNode> {
+    let default = default_aliases.default_alias(resource).map(|alias| alias.address.as_str());
+    let children: Vec<_> = resource
+        .aliases
+        .iter()
+        .filter(|alias| !alias.address.is_empty())
+        .map(|alias| {
+            let id = resource.id.clone();
+            let address = alias.address.clone();
+            let is_default = default == Some(alias.address.as_str());
+
+            let mut items = vec![MenuNode::item(
+                MenuId::CopyAlias(id.clone(), address.clone()),
+                "Copy Address",
+                true,
+            )];
+            if alias_open_url(resource, alias).is_some() {
+                items.push(MenuNode::item(
+                    MenuId::OpenAlias(id.clone(), address.clone()),
+                    "Open in Browser...",
+                    true,
+                ));
+            }
+            items.push(MenuNode::Separator);
+            items.push(if is_default {
+                MenuNode::item(MenuId::ClearDefaultAlias(id.clone()), "Stop Using as Default", true)
+            } else {
+                MenuNode::item(MenuId::SetDefaultAlias(id.clone(), address.clone()), "Use as Default", true)
+            });
+
+            let label = if is_default {
+                format!("{} (default)", address)
+            } else {
+                address.clone()
+            };
+            MenuNode::submenu(MenuId::Alias(id, address), label, items)
+        })
+        .collect();
+
+    (!children.is_empty()).then(|| MenuNode::submenu(MenuId::ResourceAliases(resource.id.clone()), "Aliases", children))
+}
+
+pub fn auth_menu<Tz: TimeZone>(resource: &Resource, now: &DateTime<Tz>) -> Vec<MenuNode>
+where
+    Tz::Offset: Display,
+{
+    let status_id = MenuId::ResourceAuthStatus(resource.id.clone());
+    let authenticate = MenuNode::item(MenuId::Authenticate(resource.id.clone()), "Authenticate...", true);
+
+    let Some(expires_at) = resource.auth_expires_at else {
+        return vec![
+            MenuNode::item(status_id, "Authentication Required", false),
+            authenticate,
+        ];
+    };
+
+    let remaining = expires_at.signed_duration_since(now);
+    let local_expiry = expires_at.with_timezone(&now.timezone()).format(EXPIRY_TIME_FORMAT);
+
+    if remaining > chrono::Duration::zero() {
+        vec![
+            MenuNode::item(
+                status_id,
+                format!("Auth expires in {}", format_duration_short(remaining)),
+                false,
+            ),
+            MenuNode::item(
+                MenuId::ResourceAuthExpiry(resource.id.clone()),
+                format!("Expires {}", local_expiry),
+                false,
+            ),
+        ]
+    } else {
+        vec![
+            MenuNode::item(
+                status_id,
+                format!("Auth expired {} ago", format_duration_short(remaining)),
+                false,
+            ),
+            MenuNode::item(
+                MenuId::ResourceAuthExpiry(resource.id.clone()),
+                format!("Expired {}", local_expiry),
+                false,
+            ),
+            authenticate,
+        ]
+    }
+}
+
+fn user_status_section(network: &Network) -> Vec<MenuNode> {
+    let mut items = vec![MenuNode::item(MenuId::UserStatus, &network.user.email, true)];
+
+    if network.internet_security.mode > 0 {
+        items.push(MenuNode::item(MenuId::SecurityMode, "Security Enabled", false));
+    }
+
+    items.push(MenuNode::item(MenuId::StopService, "Log Out and Disconnect", true));
+
+    items
+}
+
+/// Pinned resources that are currently visible, in pin order, then a separator
+fn pinned_section<Tz: TimeZone>(visible_resources: &[&Resource], layout: &MenuLayout, now: &DateTime<Tz>) -> Vec<MenuNode>
+where
+    Tz::Offset: Display,
+{
+    let pinned: Vec<_> = layout
+        .pins
+        .resource_ids
+        .iter()
+        .filter_map(|id| visible_resources.iter().find(|r| &r.id == id))
+        .collect();
+    if pinned.is_empty() {
+        return Vec::new();
+    }
+
+    let mut items = vec![MenuNode::item(MenuId::PinnedHeader, "Pinned", false)];
+    items.extend(pinned.iter().map(|r| resource_menu(r, layout, now)));
+    items.push(MenuNode::Separator);
+    items
+}
+
+/// The most recently used resources that are not pinned, then a separator
+fn recent_section<Tz: TimeZone>(
+    network: &Network,
+    visible_resources: &[&Resource],
+    layout: &MenuLayout,
+    now: &DateTime<Tz>,
+) -> Vec<MenuNode>
+where
+    Tz::Offset: Display,
+{
+    let recent: Vec<_> = layout
+        .recent_section_ids(Some(network))
+        .iter()
+        .filter_map(|id| visible_resources.iter().find(|r| &r.id == id))
+        .map(|r| resource_menu(r, layout, now))
+        .collect();
+    if recent.is_empty() {
+        return Vec::new();
+    }
+
+    let mut items = vec![MenuNode::item(MenuId::RecentHeader, "Recently Used", false)];
+    items.extend(recent);
+    items.push(MenuNode::Separator);
+    items
+}
+
+/// Every visible resource, in group submenus if grouping is on
+fn resources_section<Tz: TimeZone>(
+    visible_resources: &[&Resource],
+    layout: &MenuLayout,
+    now: &DateTime<Tz>,
+) -> Vec<MenuNode>
+where
+    Tz::Offset: Display,
+{
+    let mut items = vec![
+        MenuNode::item(
+            MenuId::ResourceCount,
+            format!("{} Resources", visible_resources.len()),
+            false,
+        ),
+        MenuNode::item(MenuId::ShowLauncher, "Search Resources...", true),
+    ];
+
+    let max_items = layout.grouping.max_items;
+    let resource_menus = |resources: &[&Resource]| -> Vec<MenuNode> {
+        resources
+            .iter()
+            .map(|r| resource_menu(r, layout, now))
+            .collect()
+    };
+
+    let entries = match layout.grouping.group(visible_resources) {
+        None => resource_menus(visible_resources),
+        Some(groups) => groups
+            .into_iter()
+            .map(|(group, members)| {
+                let children = with_overflow(&group, resource_menus(&members), max_items);
+                MenuNode::submenu(
+                    MenuId::ResourceGroup(group.clone()),
+                    format!("{} ({})", group, members.len()),
+                    children,
+                )
+            })
+            .collect(),
+    };
+    items.extend(with_overflow("", entries, max_items));
+
+    items
+}
+
+/// Cap a menu level at `max_items` entries, moving the rest into a nested
+/// "More..." submenu that is capped the same way
+fn with_overflow(group: &str, entries: Vec<MenuNode>, max_items: Option<usize>) -> Vec<MenuNode> {
+    fn cap(group: &str, mut entries: Vec<MenuNode>, offset: usize, max_items: usize) -> Vec<MenuNode> {
+        if entries.len() <= max_items {
+            return entries;
+        }
+        let shown = max_items - 1;
+        let rest = entries.split_off(shown);
+        let more_offset = offset + shown;
+        entries.push(MenuNode::submenu(
+            MenuId::MoreResources(group.to_string(), more_offset),
+            "More...",
+            cap(group, rest, more_offset, max_items),
+        ));
+        entries
+    }
+
+    match max_items {
+        Some(max_items) => cap(group, entries, 0, max_items.max(2)),
+        None => entries,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+    use crate::grouping::GroupBy;
+    use crate::test_support::{network, resource, ResourceBuilder};
+    use crate::tray::{ALIAS_ID, RESOURCE_ALIASES_ID};
+    use chrono::Utc;
+
+    /// 3h 12m before the test resource's authentication expires
+    fn now() -> DateTime<Utc> {
+        DateTime::from_timestamp(1640983680, 0).unwrap()
+    }
+
+    /// Authenticated until 3h 12m after `now()`, with an alias that opens in a browser
+    fn server(id: &str) -> ResourceBuilder {
+        resource(id)
+            .name("My Server")
+            .address("192.168.1.100")
+            .alias("my-server")
+            .with_alias("server.internal", "https://server.internal")
+            .open_in_browser()
+            .auth_expires_at(DateTime::from_timestamp(1640995200, 0))
+    }
+
+    fn create_test_resource() -> Resource {
+        server("resource-123").build()
+    }
+
+    fn create_auth_required_resource() -> Resource {
+        resource("resource-124").name("Database Server").address("192.168.1.101").build()
+    }
+
+    fn create_test_network(resources: Vec<Resource>) -> Network {
+        network(resources).email("test@example.com").internet_security(1).build()
+    }
+
+    #[test]
+    fn test_disconnected_menu_snapshot() {
+        assert_eq!(
+            disconnected_menu().to_outline(),
+            "\
+Start Twingate [start_service]
+---
+Show Client Logs [show_client_logs]
+Close Tray [quit]
+"
+        );
+    }
+
+    #[test]
+    fn test_authenticating_menu_snapshot() {
+        assert_eq!(
+            authenticating_menu("https://auth.example.com").to_outline(),
+            "\
+Authenticating... [auth_status] (disabled)
+---
+Open Authentication URL [open_auth_url]
+Copy Authentication URL [copy_auth_url]
+---
+Show Client Logs [show_client_logs]
+Close Tray [quit]
+"
+        );
+    }
+
+    #[test]
+    fn test_connected_menu_snapshot() {
+        let hidden = resource("resource-hidden").name("Database Server").hidden().build();
+        let network = create_test_network(vec![
+            create_test_resource(),
+            create_auth_required_resource(),
+            hidden,
+        ]);
+
+        assert_eq!(
+            connected_menu(&network, &MenuLayout::default(), &now()).to_outline(),
+            "\
+test@example.com [user_status]
+Security Enabled [security_mode] (disabled)
+Log Out and Disconnect [stop_service]
+---
+2 Resources [resource_total_count] (disabled)
+Search Resources... [show_launcher]
+My Server [resource:resource-123] >
+  my-server [resource_address:resource-123] (disabled)
+  Copy Address [copy_address:resource-123]
+  Open in Browser... [open_in_browser:resource-123]
+  Aliases [resource_aliases:resource-123] >
+    server.internal [alias:resource-123:server.internal] >
+      Copy Address [copy_alias:resource-123:server.internal]
+      Open in Browser... [open_alias:resource-123:server.internal]
+      ---
+      Use as Default [set_default_alias:resource-123:server.internal]
+  ---
+  Auth expires in 3h 12m [resource_auth_status:resource-123] (disabled)
+  Expires 2022-01-01 00:00 [resource_auth_expiry:resource-123] (disabled)
+  ---
+  Test Connection [test_connection:resource-123]
+  ---
+  Pin to Top [pin_resource:resource-123]
+Database Server [resource:resource-124] >
+  192.168.1.101 [resource_address:resource-124] (disabled)
+  Copy Address [copy_address:resource-124]
+  ---
+  Authentication Required [resource_auth_status:resource-124] (disabled)
+  Authenticate... [authenticate:resource-124]
+  ---
+  Test Connection [test_connection:resource-124]
+  ---
+  Pin to Top [pin_resource:resource-124]
+---
+Show Client Logs [show_client_logs]
+Close Tray [quit]
+"
+        );
+    }
+
+    #[test]
+    fn test_connected_menu_without_security_or_resources_snapshot() {
+        let mut network = create_test_network(vec![]);
+        network.internet_security.mode = 0;
+
+        assert_eq!(
+            connected_menu(&network, &MenuLayout::default(), &now()).to_outline(),
+            "\
+test@example.com [user_status]
+Log Out and Disconnect [stop_service]
+---
+0 Resources [resource_total_count] (disabled)
+Search Resources... [show_launcher]
+---
+Show Client Logs [show_client_logs]
+Close Tray [quit]
+"
+        );
+    }
+
+    #[test]
+    fn test_auth_required_resource_menu_snapshot() {
+        let model = MenuModel {
+            items: vec![resource_menu(&create_auth_required_resource(), &MenuLayout::default(), &now())],
+        };
+
+        assert_eq!(
+            model.to_outline(),
+            "\
+Database Server [resource:resource-124] >
+  192.168.1.101 [resource_address:resource-124] (disabled)
+  Copy Address [copy_address:resource-124]
+  ---
+  Authentication Required [resource_auth_status:resource-124] (disabled)
+  Authenticate... [authenticate:resource-124]
+  ---
+  Test Connection [test_connection:resource-124]
+  ---
+  Pin to Top [pin_resource:resource-124]
+"
+        );
+    }
+
+    #[test]
+    fn test_expired_resource_menu_snapshot() {
+        let now = now() + chrono::Duration::minutes(3 * 60 + 17);
+        let model = MenuModel {
+            items: vec![resource_menu(&create_test_resource(), &MenuLayout::default(), &now)],
+        };
+
+        assert_eq!(
+            model.to_outline(),
+            "\
+My Server [resource:resource-123] >
+  my-server [resource_address:resource-123] (disabled)
+  Copy Address [copy_address:resource-123]
+  Open in Browser... [open_in_browser:resource-123]
+  Aliases [resource_aliases:resource-123] >
+    server.internal [alias:resource-123:server.internal] >
+      Copy Address [copy_alias:resource-123:server.internal]
+      Open in Browser... [open_alias:resource-123:server.internal]
+      ---
+      Use as Default [set_default_alias:resource-123:server.internal]
+  ---
+  Auth expired 5m ago [resource_auth_status:resource-123] (disabled)
+  Expired 2022-01-01 00:00 [resource_auth_expiry:resource-123] (disabled)
+  Authenticate... [authenticate:resource-123]
+  ---
+  Test Connection [test_connection:resource-123]
+  ---
+  Pin to Top [pin_resource:resource-123]
+"
+        );
+    }
+
+    #[test]
+    fn test_pinned_section_snapshot() {
+        let network = create_test_network(vec![create_test_resource(), create_auth_required_resource()]);
+        // a pinned resource missing from the network stays pinned but is not shown
+        let layout = MenuLayout {
+            pins: Pins {
+                resource_ids: vec!["resource-gone".to_string(), "resource-124".to_string()],
+            },
+            ..MenuLayout::default()
+        };
+        let outline = connected_menu(&network, &layout, &now()).to_outline();
+
+        assert!(outline.contains(
+            "\
+Log Out and Disconnect [stop_service]
+---
+Pinned [pinned_header] (disabled)
+Database Server [resource:resource-124] >
+"
+        ));
+        assert!(outline.contains(
+            "\
+  Authenticate... [authenticate:resource-124]
+  ---
+  Test Connection [test_connection:resource-124]
+  ---
+  Unpin from Top [unpin_resource:resource-124]
+---
+2 Resources [resource_total_count] (disabled)
+"
+        ));
+        assert!(outline.contains("Pin to Top [pin_resource:resource-123]"));
+        assert_eq!(outline.matches("Unpin from Top [unpin_resource:resource-124]").count(), 2);
+        assert!(!outline.contains("resource-gone"));
+    }
+
+    #[test]
+    fn test_recent_section() {
+        let hidden = server("resource-hidden").hidden().build();
+        let extra = server("resource-125").name("Extra").build();
+        let network = create_test_network(vec![create_test_resource(), create_auth_required_resource(), extra, hidden]);
+
+        let mut layout = MenuLayout {
+            recent_count: 2,
+            ..MenuLayout::default()
+        };
+        for id in ["resource-125", "resource-123", "resource-gone", "resource-hidden", "resource-124"] {
+            layout.recent.record(id, Utc::now());
+        }
+        layout.pins.pin("resource-123");
+
+        // pinned, missing and hidden resources are skipped, not counted
+        assert_eq!(layout.recent_section_ids(Some(&network)), vec!["resource-124", "resource-125"]);
+        assert!(submenu_outline(&connected_menu(&network, &layout, &now())).starts_with(
+            "\
+My Server [resource:resource-123] >
+Database Server [resource:resource-124] >
+Extra [resource:resource-125] >
+My Server [resource:resource-123] >
+"
+        ));
+        let outline = connected_menu(&network, &layout, &now()).to_outline();
+        assert!(outline.contains("---\nRecently Used [recent_header] (disabled)\nDatabase Server"));
+
+        layout.recent_count = 0;
+        assert!(layout.recent_section_ids(Some(&network)).is_empty());
+        assert!(layout.recent_section_ids(None).is_empty());
+    }
+
+    #[test]
+    fn test_aliases_submenu_marks_default() {
+        let resource = server("resource-123").with_alias("kb.internal", "").build();
+        let mut layout = MenuLayout::default();
+        layout.default_aliases.set("resource-123", Some("kb.internal"));
+
+        let outline = MenuModel {
+            items: vec![resource_menu(&resource, &layout, &now())],
+        }
+        .to_outline();
+        assert!(outline.contains("  kb.internal [resource_address:resource-123] (disabled)\n"));
+        // the alias has no URL of its own, so the client's default still opens
+        assert!(outline.contains("  Open in Browser... [open_in_browser:resource-123]\n"));
+        assert!(outline.contains(
+            "\
+    server.internal [alias:resource-123:server.internal] >
+      Copy Address [copy_alias:resource-123:server.internal]
+      Open in Browser... [open_alias:resource-123:server.internal]
+      ---
+      Use as Default [set_default_alias:resource-123:server.internal]
+    kb.internal (default) [alias:resource-123:kb.internal] >
+      Copy Address [copy_alias:resource-123:kb.internal]
+      ---
+      Stop Using as Default [clear_default_alias:resource-123]
+"
+        ));
+    }
+
+    #[test]
+    fn test_connection_status_line() {
+        let mut layout = MenuLayout::default();
+        layout.probes.insert(
+            "resource-124".to_string(),
+            ProbeResult {
+                address: "192.168.1.101".to_string(),
+                ports: vec![443],
+                outcome: crate::probe::ProbeOutcome::Unreachable,
+                auth_required: true,
+            },
+        );
+
+        let outline = MenuModel {
+            items: vec![resource_menu(&create_auth_required_resource(), &layout, &now())],
+        }
+        .to_outline();
+        assert!(outline.contains(
+            "\
+  ---
+  No response on port 443 (authentication required) [connection_status:resource-124] (disabled)
+  Test Connection [test_connection:resource-124]
+  ---
+"
+        ));
+    }
+
+    /// Only the submenu lines of an outline, leaving out each resource's entries
+    fn submenu_outline(model: &MenuModel) -> String {
+        model
+            .to_outline()
+            .lines()
+            .filter(|line| line.ends_with('>'))
+            .filter(|line| {
+                ![RESOURCE_ALIASES_ID, ALIAS_ID]
+                    .iter()
+                    .any(|id| line.contains(&format!("[{}:", id)))
+            })
+            .map(|line| format!("{}\n", line))
+            .collect()
+    }
+
+    fn numbered_resources(count: usize, resource_type: &str) -> Vec<Resource> {
+        (1..=count)
+            .map(|n| {
+                server(&format!("{}-{}", resource_type, n))
+                    .name(&format!("{} {}", resource_type, n))
+                    .resource_type(resource_type)
+                    .build()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_long_resource_list_overflows_into_more() {
+        let network = create_test_network(numbered_resources(5, "dns"));
+        let layout = MenuLayout {
+            grouping: Grouping::from_config(&AppConfig {
+                max_menu_items: 2,
+                ..AppConfig::default()
+            }),
+            ..MenuLayout::default()
+        };
+
+        assert_eq!(
+            submenu_outline(&connected_menu(&network, &layout, &now())),
+            "\
+dns 1 [resource:dns-1] >
+More... [more_resources::1] >
+  dns 2 [resource:dns-2] >
+  More... [more_resources::2] >
+    dns 3 [resource:dns-3] >
+    More... [more_resources::3] >
+      dns 4 [resource:dns-4] >
+      dns 5 [resource:dns-5] >
+"
+        );
+    }
+
+    #[test]
+    fn test_grouped_menu_snapshot() {
+        let mut resources = numbered_resources(4, "ip");
+        resources.extend(numbered_resources(1, "dns"));
+        let network = create_test_network(resources);
+        let layout = MenuLayout {
+            grouping: Grouping::from_config(&AppConfig {
+                group_by: GroupBy::Type,
+                max_menu_items: 3,
+                ..AppConfig::default()
+            }),
+            ..MenuLayout::default()
+        };
+        let menu = connected_menu(&network, &layout, &now());
+
+        assert!(menu.to_outline().contains("5 Resources [resource_total_count] (disabled)"));
+        assert_eq!(
+            submenu_outline(&menu),
+            "\
+dns (1) [resource_group:dns] >
+  dns 1 [resource:dns-1] >
+ip (4) [resource_group:ip] >
+  ip 1 [resource:ip-1] >
+  ip 2 [resource:ip-2] >
+  More... [more_resources:ip:2] >
+    ip 3 [resource:ip-3] >
+    ip 4 [resource:ip-4] >
+"
+        );
+    }
+
+    #[test]
+    fn test_auth_menu_sub_hour_expiry() {
+        let resource = create_test_resource();
+        let labels = |now: DateTime<Utc>| {
+            auth_menu(&resource, &now)
+                .iter()
+                .filter_map(|node| match node {
+                    MenuNode::Item { label, .. } => Some(label.clone()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        let expires_at = resource.auth_expires_at.unwrap();
+
+        assert_eq!(
+            labels(expires_at - chrono::Duration::minutes(45)),
+            vec!["Auth expires in 45m", "Expires 2022-01-01 00:00"]
+        );
+        assert_eq!(
+            labels(expires_at - chrono::Duration::seconds(20)),
+            vec!["Auth expires in less than a minute", "Expires 2022-01-01 00:00"]
+        );
+        // Expiry is inclusive: at the exact instant access is gone
+        assert_eq!(labels(expires_at).last().unwrap(), "Authenticate...");
+    }
+
+    #[test]
+    fn test_build_selects_menu_for_status() {
+        let network = create_test_network(vec![]);
+
+        assert_eq!(
+            MenuModel::build(&ServiceStatus::NotRunning, None, &MenuLayout::default(), &now()),
+            disconnected_menu()
+        );
+        assert_eq!(
+            MenuModel::build(&ServiceStatus::Connected, Some(&network), &MenuLayout::default(), &now()),
+            connected_menu(&network, &MenuLayout::default(), &now())
+        );
+        // Authenticating wins even if stale network data is passed in
+        assert_eq!(
+            MenuModel::build(
+                &ServiceStatus::Authenticating("https://auth.example.com".to_string()),
+                Some(&network),
+                &MenuLayout::default(),
+                &now()
+            ),
+            authenticating_menu("https://auth.example.com")
+        );
+    }
+
+    #[test]
+    fn test_from_state() {
+        let mut state = AppState::new();
+        assert_eq!(MenuModel::from_state(&state, &now()), disconnected_menu());
+
+        let network = create_test_network(vec![create_test_resource()]);
+        state.update_network(Some(network.clone()));
+        assert_eq!(MenuModel::from_state(&state, &now()), connected_menu(&network, &MenuLayout::default(), &now()));
+
+        state.set_authenticating("https://auth.example.com".to_string());
+        assert_eq!(
+            MenuModel::from_state(&state, &now()),
+            authenticating_menu("https://auth.example.com")
+        );
+    }
+
+    #[test]
+    fn test_disabled_submenu_outline() {
+        let model = MenuModel {
+            items: vec![MenuNode::Submenu {
+                id: MenuId::Resource("group".to_string()),
+                label: "Group".to_string(),
+                enabled: false,
+                children: vec![MenuNode::Separator],
+            }],
+        };
+
+        assert_eq!(model.to_outline(), "Group [resource:group] > (disabled)\n  ---\n");
+    }
+}
@@ -1,4 +1,9 @@
-use serde::Deserialize;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer};
+
+/// Timestamps below this are read as seconds rather than milliseconds; as
+/// milliseconds they would all fall within the first days of 1973
+const SECONDS_TIMESTAMP_LIMIT: i64 = 100_000_000_000;
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Network {
@@ -27,7 +32,10 @@ pub struct Resource {
     pub alias: Option<String>,
     #[serde(default)]
     pub aliases: Vec<Alias>,
-    pub auth_expires_at: i64,
+    /// When authentication to the resource runs out, `None` if the user
+    /// has never authenticated (reported by the client as 0)
+    #[serde(deserialize_with = "deserialize_auth_expiry")]
+    pub auth_expires_at: Option<DateTime<Utc>>,
     #[allow(dead_code)]
     pub auth_flow_id: String,
     #[allow(dead_code)]
@@ -43,9 +51,32 @@ pub struct Resource {
     pub resource_type: String,
 }
 
+impl Resource {
+    /// Whether the user has to authenticate before using the resource
+    pub fn requires_auth(&self, now: DateTime<Utc>) -> bool {
+        self.auth_expires_at.is_none_or(|expires_at| expires_at <= now)
+    }
+}
+
+/// Convert the client's epoch timestamp, in milliseconds (or seconds from
+/// older clients), to a point in time; 0 means no expiry is known
+pub fn auth_expiry_from_timestamp(timestamp: i64) -> Option<DateTime<Utc>> {
+    match timestamp {
+        0 => None,
+        t if t.abs() < SECONDS_TIMESTAMP_LIMIT => DateTime::from_timestamp(t, 0),
+        t => DateTime::from_timestamp_millis(t),
+    }
+}
+
+fn deserialize_auth_expiry<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(auth_expiry_from_timestamp(i64::deserialize(deserializer)?))
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct Alias {
-    #[allow(dead_code)]
     pub address: String,
     pub open_url: String,
 }
@@ -142,7 +173,10 @@ mod tests {
         assert_eq!(resource.alias, Some("my-server".to_string()));
         assert_eq!(resource.aliases.len(), 1);
         assert_eq!(resource.aliases[0].open_url, "https://server.internal");
-        assert_eq!(resource.auth_expires_at, 1640995200);
+        assert_eq!(
+            resource.auth_expires_at,
+            Some("2022-01-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap())
+        );
         assert!(resource.can_open_in_browser);
         assert_eq!(resource.client_visibility, 1);
         assert_eq!(resource.id, "resource-123");
@@ -175,6 +209,51 @@ mod tests {
         assert_eq!(resource.name, "My Server");
     }
 
+    #[test]
+    fn test_auth_expiry_from_timestamp() {
+        let expected = "2022-01-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+
+        assert_eq!(auth_expiry_from_timestamp(0), None);
+        assert_eq!(auth_expiry_from_timestamp(1640995200000), Some(expected));
+        assert_eq!(auth_expiry_from_timestamp(1640995200), Some(expected));
+        assert_eq!(
+            auth_expiry_from_timestamp(1640995200123).unwrap().timestamp_subsec_millis(),
+            123
+        );
+    }
+
+    #[test]
+    fn test_resource_requires_auth() {
+        let json = r#"{
+            "address": "192.168.1.100",
+            "admin_url": "",
+            "auth_expires_at": 0,
+            "auth_flow_id": "",
+            "auth_state": "",
+            "can_open_in_browser": false,
+            "client_visibility": 1,
+            "id": "resource-123",
+            "name": "My Server",
+            "open_url": "",
+            "type": "tcp"
+        }"#;
+        let mut resource: Resource = serde_json::from_str(json).unwrap();
+        let now = "2022-01-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+
+        // Never authenticated
+        assert_eq!(resource.auth_expires_at, None);
+        assert!(resource.requires_auth(now));
+
+        // Expired
+        resource.auth_expires_at = Some(now - chrono::Duration::minutes(5));
+        assert!(resource.requires_auth(now));
+        assert!(resource.requires_auth(now + chrono::Duration::minutes(10)));
+
+        // Still valid
+        resource.auth_expires_at = Some(now + chrono::Duration::minutes(30));
+        assert!(!resource.requires_auth(now));
+    }
+
     #[test]
     fn test_network_deserialization() {
         let json = r#"{
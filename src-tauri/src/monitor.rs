@@ -0,0 +1,403 @@
+use crate::backend::{CliBackend, TwingateBackend};
+use crate::dto::StatusDto;
+use crate::error::{Result, TwingateError};
+use crate::events::{emit_status_if_changed, AppEvent, NetworkUpdated};
+use crate::icons::TrayIconKind;
+use crate::managers::TrayManager;
+use crate::menu::MenuModel;
+use crate::models::Network;
+use crate::network::get_network_data_with_retry;
+use crate::notifications::{notify_resource_changes, ResourceChanges};
+use crate::state::{AppState, ServiceStatus};
+use chrono::{DateTime, Local};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::time::{interval_at, Instant, MissedTickBehavior};
+
+/// Retries per poll; transitional states are simply picked up by the next poll
+const POLL_RETRIES: u32 = 1;
+
+/// What a poll found different from the state and menu the tray shows
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StateChange {
+    pub status_changed: bool,
+    pub menu_changed: bool,
+    pub icon_changed: bool,
+    pub resources: ResourceChanges,
+}
+
+impl StateChange {
+    /// Compare the current state with freshly fetched network data at `now`.
+    ///
+    /// The menu is compared with the one last rendered rather than rebuilt from
+    /// the old state, so relative labels like "expires in 5m" are kept current.
+    pub fn between(state: &AppState, network: Option<&Network>, now: &DateTime<Local>) -> Self {
+        let mut next = AppState::new();
+        next.update_network(network.cloned());
+
+        Self {
+            status_changed: state.service_status() != next.service_status(),
+            menu_changed: state.rendered_menu()
+                != Some(&MenuModel::build(next.service_status(), network, state.menu_layout(), now)),
+            icon_changed: state.rendered_icon() != Some(TrayIconKind::from_state(&next)),
+            resources: ResourceChanges::between(state.network(), network),
+        }
+    }
+
+    /// Whether the tray needs rebuilding
+    pub fn is_meaningful(&self) -> bool {
+        self.status_changed || self.menu_changed || self.icon_changed
+    }
+}
+
+/// Fetch the current network, store it and report what changed.
+///
+/// A client still starting or connecting once the poll's retries run out leaves
+/// the state untouched for the next poll, as does an authentication flow in
+/// progress, which owns the state until it finishes. A client waiting for
+/// authentication is shown as disconnected with the reason, and other failures
+/// are recorded on the state so the tray can show them.
+pub async fn poll_state<B: TwingateBackend>(backend: &B, state: &Mutex<AppState>) -> Result<StateChange> {
+    if is_authenticating(state) {
+        log::debug!("Authentication in progress, skipping status poll");
+        return Ok(StateChange::default());
+    }
+
+    let (network, error) = match get_network_data_with_retry(backend, POLL_RETRIES).await {
+        Ok(network) => (network, None),
+        Err(TwingateError::RetryLimitExceeded { .. }) => {
+            log::debug!("Service still connecting during poll");
+            return Ok(StateChange::default());
+        }
+        Err(e @ TwingateError::AuthenticationRequired) => {
+            log::debug!("Service requires authentication, showing it as disconnected");
+            (None, Some(e))
+        }
+        Err(e) => {
+            state.lock().unwrap().set_error(e.to_string());
+            return Err(e);
+        }
+    };
+
+    let mut state_guard = state.lock().unwrap();
+    if matches!(state_guard.service_status(), ServiceStatus::Authenticating(_)) {
+        log::debug!("Authentication started during poll, discarding result");
+        return Ok(StateChange::default());
+    }
+
+    let mut change = StateChange::between(&state_guard, network.as_ref(), &Local::now());
+    state_guard.update_network(network);
+    if let Some(e) = error {
+        state_guard.set_error(e.to_string());
+        change.icon_changed = state_guard.rendered_icon() != Some(TrayIconKind::from_state(&state_guard));
+    }
+    Ok(change)
+}
+
+fn is_authenticating(state: &Mutex<AppState>) -> bool {
+    matches!(
+        state.lock().unwrap().service_status(),
+        ServiceStatus::Authenticating(_)
+    )
+}
+
+/// Poll the client in the background and rebuild the tray when its state changes
+pub fn spawn_monitor(app_handle: AppHandle, interval: Duration) {
+    log::info!("Starting background status monitor every {:?}", interval);
+
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = interval_at(Instant::now() + interval, interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+
+            let state = app_handle.state::<Mutex<AppState>>();
+            let status_before = StatusDto::from_state(&state.lock().unwrap());
+            let result = poll_state(&CliBackend::new(&app_handle), &state).await;
+            let status_after = StatusDto::from_state(&state.lock().unwrap());
+            emit_status_if_changed(&app_handle, &status_before, status_after);
+
+            match result {
+                Ok(change) if change.is_meaningful() => {
+                    log::debug!("Detected state change: {:?}, rebuilding tray", change);
+                    if let Err(e) = TrayManager::rebuild_tray_now(&app_handle).await {
+                        log::warn!("Failed to rebuild tray after state change: {}", e);
+                    }
+                    let updated = NetworkUpdated::from_state(&state.lock().unwrap(), change.resources.clone());
+                    AppEvent::NetworkUpdated(updated).emit(&app_handle);
+                    notify_resource_changes(&app_handle, change.resources);
+                }
+                Ok(_) => log::debug!("Status poll found no changes"),
+                Err(e) => {
+                    log::warn!("Background status poll failed: {}", e);
+                    if let Err(e) = TrayManager::update_icon(&app_handle) {
+                        log::warn!("Failed to show error tray icon: {}", e);
+                    }
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::scripted::{block_on_paused, BackendCall, ScriptedBackend};
+
+    fn network_json(email: &str, resource_name: &str) -> String {
+        format!(
+            r#"{{
+                "admin_url": "https://admin.twingate.com",
+                "full_tunnel_time_limit": 3600,
+                "internet_security": {{ "mode": 0, "status": 0 }},
+                "resources": [
+                    {{
+                        "address": "10.0.0.5",
+                        "admin_url": "https://admin.twingate.com/resource/1",
+                        "auth_expires_at": 0,
+                        "auth_flow_id": "",
+                        "auth_state": "",
+                        "can_open_in_browser": false,
+                        "client_visibility": 1,
+                        "id": "UmVzb3VyY2U6MQ==",
+                        "name": "{}",
+                        "open_url": "",
+                        "type": "tcp"
+                    }}
+                ],
+                "user": {{
+                    "avatar_url": "",
+                    "email": "{}",
+                    "first_name": "Test",
+                    "id": "user-1",
+                    "is_admin": false,
+                    "last_name": "User"
+                }}
+            }}"#,
+            resource_name, email
+        )
+    }
+
+    /// State for `network` with its menu shown in the tray
+    fn shown_state(network: Option<Network>, now: &DateTime<Local>) -> AppState {
+        let mut state = AppState::new();
+        state.update_network(network);
+        let menu = MenuModel::from_state(&state, now);
+        state.set_rendered_menu(menu);
+        state.set_rendered_icon(TrayIconKind::from_state(&state));
+        state
+    }
+
+    fn connected_state(json: &str) -> Mutex<AppState> {
+        Mutex::new(shown_state(Some(serde_json::from_str(json).unwrap()), &Local::now()))
+    }
+
+    fn online_backend(json: &str) -> ScriptedBackend {
+        ScriptedBackend::new()
+            .reply(BackendCall::Status, &["online"])
+            .reply(BackendCall::Resources, &[json])
+    }
+
+    #[test]
+    fn test_state_change_between() {
+        let network: Network = serde_json::from_str(&network_json("user@example.com", "Database")).unwrap();
+        let now = Local::now();
+
+        let state = shown_state(None, &now);
+        let change = StateChange::between(&state, Some(&network), &now);
+        assert!(change.status_changed);
+        assert!(change.menu_changed);
+
+        let state = shown_state(Some(network.clone()), &now);
+        assert_eq!(StateChange::between(&state, Some(&network), &now), StateChange::default());
+
+        let change = StateChange::between(&state, None, &now);
+        assert!(change.status_changed && change.is_meaningful());
+
+        // pins and the rest of the layout carry over to the new menu
+        let mut state = AppState::new();
+        state.update_network(Some(network.clone()));
+        state.set_pins(crate::pins::Pins {
+            resource_ids: vec![network.resources[0].id.clone()],
+        });
+        state.set_rendered_menu(MenuModel::from_state(&state, &now));
+        assert!(!StateChange::between(&state, Some(&network), &now).menu_changed);
+    }
+
+    #[test]
+    fn test_state_change_ignores_fields_not_shown() {
+        let network: Network = serde_json::from_str(&network_json("user@example.com", "Database")).unwrap();
+        let now = Local::now();
+        let state = shown_state(Some(network.clone()), &now);
+
+        let mut updated = network.clone();
+        updated.user.avatar_url = "https://example.com/new.png".to_string();
+        updated.full_tunnel_time_limit = 60;
+        assert!(!StateChange::between(&state, Some(&updated), &now).is_meaningful());
+
+        updated.resources[0].name = "Renamed".to_string();
+        let change = StateChange::between(&state, Some(&updated), &now);
+        assert!(!change.status_changed);
+        assert!(change.menu_changed);
+    }
+
+    #[test]
+    fn test_state_change_refreshes_stale_expiry_labels() {
+        let mut network: Network = serde_json::from_str(&network_json("user@example.com", "Database")).unwrap();
+        let now = Local::now();
+        network.resources[0].auth_expires_at = Some((now + chrono::Duration::hours(3)).to_utc());
+        let state = shown_state(Some(network.clone()), &now);
+
+        assert!(!StateChange::between(&state, Some(&network), &now).is_meaningful());
+
+        let later = now + chrono::Duration::minutes(10);
+        let change = StateChange::between(&state, Some(&network), &later);
+        assert!(!change.status_changed);
+        assert!(change.menu_changed);
+    }
+
+    #[test]
+    fn test_state_change_without_rendered_menu() {
+        let network: Network = serde_json::from_str(&network_json("user@example.com", "Database")).unwrap();
+        let mut state = AppState::new();
+        state.update_network(Some(network.clone()));
+
+        let change = StateChange::between(&state, Some(&network), &Local::now());
+        assert!(!change.status_changed);
+        assert!(change.menu_changed, "Nothing rendered yet, so the tray needs building");
+    }
+
+    #[test]
+    fn test_poll_state_detects_resource_changes() {
+        let state = connected_state(&network_json("user@example.com", "Database"));
+
+        let backend = online_backend(&network_json("user@example.com", "Database"));
+        let change = block_on_paused(poll_state(&backend, &state)).unwrap();
+        assert!(!change.is_meaningful());
+
+        let backend = online_backend(&network_json("user@example.com", "Warehouse"));
+        let change = block_on_paused(poll_state(&backend, &state)).unwrap();
+        assert!(change.menu_changed);
+        assert!(change.resources.is_empty(), "Same resource id, only renamed");
+        assert_eq!(
+            state.lock().unwrap().network().unwrap().resources[0].name,
+            "Warehouse"
+        );
+    }
+
+    #[test]
+    fn test_poll_state_detects_service_drop() {
+        let state = connected_state(&network_json("user@example.com", "Database"));
+        let backend = ScriptedBackend::new().reply(BackendCall::Status, &["not-running"]);
+
+        let change = block_on_paused(poll_state(&backend, &state)).unwrap();
+
+        assert!(change.status_changed);
+        assert_eq!(state.lock().unwrap().service_status(), &ServiceStatus::NotRunning);
+        assert!(state.lock().unwrap().network().is_none());
+    }
+
+    #[test]
+    fn test_poll_state_retries_while_connecting() {
+        let json = network_json("user@example.com", "Database");
+        let state = connected_state(&json);
+        let backend = ScriptedBackend::new()
+            .reply(BackendCall::Status, &["connecting", "online"])
+            .reply(BackendCall::Resources, &[&json]);
+
+        let change = block_on_paused(poll_state(&backend, &state)).unwrap();
+
+        assert!(!change.is_meaningful());
+        assert_eq!(state.lock().unwrap().service_status(), &ServiceStatus::Connected);
+        assert!(state.lock().unwrap().last_error().is_none());
+    }
+
+    #[test]
+    fn test_poll_state_leaves_state_while_still_connecting() {
+        let state = connected_state(&network_json("user@example.com", "Database"));
+        let backend = ScriptedBackend::new().reply(BackendCall::Status, &["starting", "connecting"]);
+
+        let change = block_on_paused(poll_state(&backend, &state)).unwrap();
+
+        assert!(!change.is_meaningful());
+        let state = state.lock().unwrap();
+        assert_eq!(state.service_status(), &ServiceStatus::Connected);
+        assert!(state.last_error().is_none());
+        assert_eq!(TrayIconKind::from_state(&state), TrayIconKind::Connected);
+    }
+
+    #[test]
+    fn test_poll_state_shows_authentication_required() {
+        let state = connected_state(&network_json("user@example.com", "Database"));
+        let backend = ScriptedBackend::new().reply(BackendCall::Status, &["User authentication is required"]);
+
+        let change = block_on_paused(poll_state(&backend, &state)).unwrap();
+
+        assert!(change.status_changed && change.menu_changed && change.icon_changed);
+        {
+            let state = state.lock().unwrap();
+            assert_eq!(state.service_status(), &ServiceStatus::NotRunning);
+            assert!(state.network().is_none());
+            assert_eq!(state.last_error(), Some("Service requires authentication"));
+            assert_eq!(TrayIconKind::from_state(&state), TrayIconKind::Error);
+        }
+
+        // once shown, later polls find nothing new
+        {
+            let mut state = state.lock().unwrap();
+            let menu = MenuModel::from_state(&state, &Local::now());
+            state.set_rendered_menu(menu);
+            state.set_rendered_icon(TrayIconKind::Error);
+        }
+        let change = block_on_paused(poll_state(&backend, &state)).unwrap();
+        assert!(!change.is_meaningful());
+    }
+
+    #[test]
+    fn test_poll_state_skips_while_authenticating() {
+        let mut auth_state = AppState::new();
+        auth_state.set_authenticating("https://auth.example.com".to_string());
+        let state = Mutex::new(auth_state);
+        let backend = ScriptedBackend::new().reply(BackendCall::Status, &["not-running"]);
+
+        let change = block_on_paused(poll_state(&backend, &state)).unwrap();
+
+        assert!(!change.is_meaningful());
+        assert_eq!(backend.call_count(BackendCall::Status), 0);
+        assert!(state.lock().unwrap().auth_url().is_some());
+    }
+
+    #[test]
+    fn test_poll_state_propagates_errors() {
+        let state = connected_state(&network_json("user@example.com", "Database"));
+        let backend = ScriptedBackend::new()
+            .reply(BackendCall::Status, &["online"])
+            .reply(BackendCall::Resources, &["{ broken"]);
+
+        assert!(matches!(
+            block_on_paused(poll_state(&backend, &state)),
+            Err(TwingateError::JsonError { .. })
+        ));
+        assert_eq!(state.lock().unwrap().service_status(), &ServiceStatus::Connected);
+        assert!(state.lock().unwrap().last_error().is_some());
+    }
+
+    #[test]
+    fn test_poll_state_clears_error_icon() {
+        let json = network_json("user@example.com", "Database");
+        let state = connected_state(&json);
+        {
+            let mut state = state.lock().unwrap();
+            state.set_error("status poll failed".to_string());
+            state.set_rendered_icon(TrayIconKind::Error);
+        }
+
+        let change = block_on_paused(poll_state(&online_backend(&json), &state)).unwrap();
+
+        assert!(!change.status_changed && !change.menu_changed);
+        assert!(change.icon_changed && change.is_meaningful());
+        assert!(state.lock().unwrap().last_error().is_none());
+    }
+}
@@ -1,10 +1,9 @@
+use crate::backend::TwingateBackend;
 use crate::error::{Result, TwingateError};
 use crate::models::Network;
 use serde_json::from_slice;
-use std::str;
 use std::time::Duration;
-use tauri_plugin_shell::ShellExt;
-use tokio::time::sleep;
+use tokio::time::{sleep, Instant};
 
 const MAX_RETRIES: u32 = 8;
 const BASE_DELAY_MS: u64 = 1000;
@@ -63,13 +62,12 @@ impl ServiceState {
     }
 }
 
-async fn get_service_state(app_handle: &tauri::AppHandle) -> Result<ServiceState> {
+async fn get_service_state<B: TwingateBackend>(backend: &B) -> Result<ServiceState> {
     log::debug!("Checking Twingate service status");
-    let shell = app_handle.shell();
     
-    let status_output = shell.command("twingate").args(["status"]).output().await?;
+    let status_output = backend.status().await?;
     
-    let status = std::str::from_utf8(&status_output.stdout)?;
+    let status = status_output.stdout_str()?;
     
     log::debug!("Raw twingate status output: '{}'", status.trim());
     
@@ -79,17 +77,12 @@ async fn get_service_state(app_handle: &tauri::AppHandle) -> Result<ServiceState
     Ok(state)
 }
 
-async fn try_get_resources_data(app_handle: &tauri::AppHandle) -> Result<Option<Network>> {
+async fn try_get_resources_data<B: TwingateBackend>(backend: &B) -> Result<Option<Network>> {
     log::debug!("Attempting to fetch resources data");
-    let shell = app_handle.shell();
     
-    let resources_output = shell
-        .command("twingate-notifier")
-        .args(["resources"])
-        .output()
-        .await?;
+    let resources_output = backend.resources().await?;
     
-    let output_str = str::from_utf8(&resources_output.stdout)?;
+    let output_str = resources_output.stdout_str()?;
     
     let trimmed_output = output_str.trim();
     log::debug!("Raw resources command output (length: {}): '{}'", trimmed_output.len(), trimmed_output);
@@ -150,11 +143,11 @@ async fn try_get_resources_data(app_handle: &tauri::AppHandle) -> Result<Option<
     }
 }
 
-pub async fn get_network_data(app_handle: &tauri::AppHandle) -> Result<Option<Network>> {
-    get_network_data_with_retry(app_handle, MAX_RETRIES).await
+pub async fn get_network_data<B: TwingateBackend>(backend: &B) -> Result<Option<Network>> {
+    get_network_data_with_retry(backend, MAX_RETRIES).await
 }
 
-pub async fn get_network_data_with_retry(app_handle: &tauri::AppHandle, max_retries: u32) -> Result<Option<Network>> {
+pub async fn get_network_data_with_retry<B: TwingateBackend>(backend: &B, max_retries: u32) -> Result<Option<Network>> {
     let mut retry_count = 0;
     let mut delay_ms = BASE_DELAY_MS;
     
@@ -165,7 +158,7 @@ pub async fn get_network_data_with_retry(app_handle: &tauri::AppHandle, max_retr
         
 
         // First check the service state for better decision making
-        match get_service_state(app_handle).await {
+        match get_service_state(backend).await {
             Ok(ServiceState::NotRunning) => {
                 log::debug!("Service not running - returning None");
                 return Ok(None);
@@ -177,7 +170,7 @@ pub async fn get_network_data_with_retry(app_handle: &tauri::AppHandle, max_retr
             Ok(ServiceState::Connected) => {
                 log::debug!("Service reports connected state, attempting to get resources");
                 // Service claims to be connected, try to get resources
-                match try_get_resources_data(app_handle).await {
+                match try_get_resources_data(backend).await {
                     Ok(network) => {
                         log::debug!("Successfully retrieved network data on attempt {}", retry_count + 1);
                         return Ok(network);
@@ -203,7 +196,7 @@ pub async fn get_network_data_with_retry(app_handle: &tauri::AppHandle, max_retr
             Err(e) => {
                 log::warn!("Failed to get service state: {}. Attempting resources as fallback", e);
                 // If we can't get status, try resources anyway as a fallback
-                match try_get_resources_data(app_handle).await {
+                match try_get_resources_data(backend).await {
                     Ok(network) => {
                         log::debug!("Fallback resources retrieval successful on attempt {}", retry_count + 1);
                         return Ok(network);
@@ -229,7 +222,7 @@ pub async fn get_network_data_with_retry(app_handle: &tauri::AppHandle, max_retr
         // Check if we've exhausted retries
         if retry_count >= max_retries {
             log::warn!("Exhausted {} retries attempting to get network data", max_retries);
-            log::debug!("Final service state before giving up: {:?}", get_service_state(app_handle).await);
+            log::debug!("Final service state before giving up: {:?}", get_service_state(backend).await);
             return Err(TwingateError::RetryLimitExceeded { 
                 attempts: max_retries + 1 
             });
@@ -244,14 +237,14 @@ pub async fn get_network_data_with_retry(app_handle: &tauri::AppHandle, max_retr
     }
 }
 
-pub async fn wait_for_service_ready(app_handle: &tauri::AppHandle, timeout_seconds: u64) -> Result<()> {
-    let start_time = std::time::Instant::now();
+pub async fn wait_for_service_ready<B: TwingateBackend>(backend: &B, timeout_seconds: u64) -> Result<()> {
+    let start_time = Instant::now();
     let timeout_duration = Duration::from_secs(timeout_seconds);
     
     log::debug!("Waiting for service to be ready (timeout: {}s)", timeout_seconds);
     
     while start_time.elapsed() < timeout_duration {
-        match get_service_state(app_handle).await {
+        match get_service_state(backend).await {
             Ok(ServiceState::Connected) => {
                 log::debug!("Service is ready");
                 return Ok(());
@@ -274,6 +267,36 @@ pub async fn wait_for_service_ready(app_handle: &tauri::AppHandle, timeout_secon
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backend::scripted::{block_on_paused, BackendCall, ScenarioBackend, ScriptedBackend};
+
+    const NETWORK_JSON: &str = r#"{
+        "admin_url": "https://admin.twingate.com",
+        "full_tunnel_time_limit": 3600,
+        "internet_security": { "mode": 0, "status": 0 },
+        "resources": [
+            {
+                "address": "10.0.0.5",
+                "admin_url": "https://admin.twingate.com/resource/1",
+                "auth_expires_at": 0,
+                "auth_flow_id": "",
+                "auth_state": "",
+                "can_open_in_browser": false,
+                "client_visibility": 1,
+                "id": "UmVzb3VyY2U6MQ==",
+                "name": "Database",
+                "open_url": "",
+                "type": "tcp"
+            }
+        ],
+        "user": {
+            "avatar_url": "",
+            "email": "user@example.com",
+            "first_name": "Test",
+            "id": "user-1",
+            "is_admin": false,
+            "last_name": "User"
+        }
+    }"#;
 
     #[test]
     fn test_service_state_from_status_output_not_running() {
@@ -476,14 +499,14 @@ mod tests {
 
     #[test]
     fn test_service_state_whitespace_handling() {
-        let outputs_with_whitespace = vec![
+        let outputs_with_whitespace = [
             "  authentication is required  ",
             "\tconnected\t",
             "\nstarting\n",
             "   not-running   ",
         ];
 
-        let expected = vec![
+        let expected = [
             ServiceState::AuthRequired,
             ServiceState::Connected,
             ServiceState::Starting,
@@ -495,4 +518,165 @@ mod tests {
             assert_eq!(&state, expected_state, "Failed for output with whitespace: '{}'", output);
         }
     }
+
+    #[test]
+    fn test_get_network_data_retries_until_connected() {
+        let backend = ScriptedBackend::new()
+            .reply(BackendCall::Status, &["starting", "connecting", "online"])
+            .reply(BackendCall::Resources, &[NETWORK_JSON]);
+
+        let network = block_on_paused(get_network_data_with_retry(&backend, 5))
+            .unwrap()
+            .expect("Expected network data once connected");
+
+        assert_eq!(network.user.email, "user@example.com");
+        assert_eq!(network.resources.len(), 1);
+        assert_eq!(backend.call_count(BackendCall::Status), 3);
+        assert_eq!(backend.call_count(BackendCall::Resources), 1);
+    }
+
+    #[test]
+    fn test_get_network_data_retries_while_resources_not_ready() {
+        let backend = ScriptedBackend::new()
+            .reply(BackendCall::Status, &["online"])
+            .reply(BackendCall::Resources, &["", "not ready", NETWORK_JSON]);
+
+        let network = block_on_paused(get_network_data_with_retry(&backend, 5)).unwrap();
+
+        assert!(network.is_some());
+        assert_eq!(backend.call_count(BackendCall::Resources), 3);
+    }
+
+    #[test]
+    fn test_get_network_data_not_running() {
+        let backend = ScriptedBackend::new().reply(BackendCall::Status, &["not-running"]);
+
+        let network = block_on_paused(get_network_data_with_retry(&backend, 5)).unwrap();
+
+        assert!(network.is_none());
+        assert_eq!(backend.call_count(BackendCall::Resources), 0);
+    }
+
+    #[test]
+    fn test_get_network_data_auth_required_from_status() {
+        let backend = ScriptedBackend::new().reply(BackendCall::Status, &["starting", "authentication is required"]);
+
+        let result = block_on_paused(get_network_data_with_retry(&backend, 5));
+
+        assert!(matches!(result, Err(TwingateError::AuthenticationRequired)));
+        assert_eq!(backend.call_count(BackendCall::Status), 2);
+    }
+
+    #[test]
+    fn test_get_network_data_auth_required_from_resources() {
+        let backend = ScriptedBackend::new()
+            .reply(BackendCall::Status, &["online"])
+            .reply(BackendCall::Resources, &["Please authenticate to continue"]);
+
+        let result = block_on_paused(get_network_data_with_retry(&backend, 5));
+
+        assert!(matches!(result, Err(TwingateError::AuthenticationRequired)));
+    }
+
+    #[test]
+    fn test_get_network_data_exhausts_retries() {
+        let backend = ScriptedBackend::new().reply(BackendCall::Status, &["starting"]);
+
+        let result = block_on_paused(get_network_data_with_retry(&backend, 3));
+
+        match result {
+            Err(TwingateError::RetryLimitExceeded { attempts }) => assert_eq!(attempts, 4),
+            other => panic!("Expected RetryLimitExceeded, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_get_network_data_falls_back_to_resources_when_status_fails() {
+        let backend = ScriptedBackend::new()
+            .fail(BackendCall::Status, "twingate: command not found")
+            .reply(BackendCall::Resources, &[NETWORK_JSON]);
+
+        let network = block_on_paused(get_network_data_with_retry(&backend, 5)).unwrap();
+
+        assert!(network.is_some());
+    }
+
+    #[test]
+    fn test_get_network_data_returns_status_error_when_both_fail() {
+        let backend = ScriptedBackend::new()
+            .fail(BackendCall::Status, "status failed")
+            .fail(BackendCall::Resources, "resources failed");
+
+        let result = block_on_paused(get_network_data_with_retry(&backend, 5));
+
+        match result {
+            Err(TwingateError::CommandFailed { stderr, .. }) => assert_eq!(stderr, "status failed"),
+            other => panic!("Expected status CommandFailed, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_get_network_data_invalid_json() {
+        let backend = ScriptedBackend::new()
+            .reply(BackendCall::Status, &["online"])
+            .reply(BackendCall::Resources, &["{\"resources\": 5}"]);
+
+        let result = block_on_paused(get_network_data_with_retry(&backend, 5));
+
+        assert!(matches!(result, Err(TwingateError::JsonError { .. })));
+    }
+
+    #[test]
+    fn test_wait_for_service_ready() {
+        let backend = ScriptedBackend::new().reply(BackendCall::Status, &["starting", "connecting", "online"]);
+
+        assert!(block_on_paused(wait_for_service_ready(&backend, 30)).is_ok());
+        assert_eq!(backend.call_count(BackendCall::Status), 3);
+    }
+
+    #[test]
+    fn test_wait_for_service_ready_timeout() {
+        let backend = ScriptedBackend::new().reply(BackendCall::Status, &["authentication is required"]);
+
+        let result = block_on_paused(wait_for_service_ready(&backend, 5));
+
+        assert!(matches!(result, Err(TwingateError::AuthenticationTimeout { seconds: 5 })));
+    }
+
+    #[test]
+    fn test_scenario_start_authenticate_connect_stop() {
+        let backend = ScenarioBackend::shipped("connect-with-auth.json");
+
+        block_on_paused(async {
+            assert!(get_network_data(&backend).await.unwrap().is_none());
+
+            backend.start().await.unwrap();
+            assert!(matches!(
+                get_network_data(&backend).await,
+                Err(TwingateError::AuthenticationRequired)
+            ));
+
+            backend.auth(None).await.unwrap();
+            let network = get_network_data(&backend).await.unwrap().expect("Expected connected network");
+            assert_eq!(network.user.email, "dev@example.com");
+            assert_eq!(network.resources.len(), 2);
+
+            backend.stop().await.unwrap();
+            assert!(get_network_data(&backend).await.unwrap().is_none());
+        });
+    }
+
+    #[test]
+    fn test_scenario_starting_outlasts_retries() {
+        let backend = ScenarioBackend::from_json(r#"{
+            "steps": [
+                { "status": "Twingate is starting... Please wait.", "calls": 10 },
+                { "status": "online" }
+            ]
+        }"#);
+
+        let result = block_on_paused(get_network_data_with_retry(&backend, 3));
+
+        assert!(matches!(result, Err(TwingateError::RetryLimitExceeded { attempts: 4 })));
+    }
 }
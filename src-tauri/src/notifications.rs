@@ -0,0 +1,341 @@
+use crate::config::AppConfig;
+use crate::error::Result;
+use crate::models::Network;
+use futures_util::stream::{self, StreamExt};
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::OnceCell;
+use tokio::time::timeout;
+use zbus::{proxy, zvariant::Value, Connection};
+
+const APP_NAME: &str = "Twingate";
+const NOTIFICATION_ICON: &str = "network-vpn";
+/// Let the notification server pick how long the popup stays up
+const DEFAULT_EXPIRE_TIMEOUT: i32 = -1;
+/// How long a notification with an action stays up, in milliseconds
+const ACTION_EXPIRE_TIMEOUT_MS: i32 = 60_000;
+/// How long to wait for a click on an action, a little past its expiry in
+/// case the server never reports the notification closed
+const ACTION_WAIT: Duration = Duration::from_millis(ACTION_EXPIRE_TIMEOUT_MS as u64 + 5_000);
+/// Names listed per section before the rest are summarised as "and N more"
+const MAX_LISTED_NAMES: usize = 5;
+
+#[proxy(
+    interface = "org.freedesktop.Notifications",
+    default_service = "org.freedesktop.Notifications",
+    default_path = "/org/freedesktop/Notifications"
+)]
+trait Notifications {
+    #[allow(clippy::too_many_arguments)]
+    fn notify(
+        &self,
+        app_name: &str,
+        replaces_id: u32,
+        app_icon: &str,
+        summary: &str,
+        body: &str,
+        actions: &[&str],
+        hints: HashMap<&str, Value<'_>>,
+        expire_timeout: i32,
+    ) -> zbus::Result<u32>;
+
+    #[zbus(signal)]
+    fn action_invoked(&self, id: u32, action_key: String) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn notification_closed(&self, id: u32, reason: u32) -> zbus::Result<()>;
+}
+
+/// A button shown on a notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotificationAction {
+    pub key: &'static str,
+    pub label: &'static str,
+}
+
+enum NotificationEvent {
+    Invoked(u32, String),
+    Closed(u32),
+}
+
+/// Resources that appeared or disappeared between two network snapshots
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResourceChanges {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl ResourceChanges {
+    /// Diff the visible resources of two snapshots by id.
+    ///
+    /// Only a connected-to-connected transition counts; connecting or
+    /// disconnecting would otherwise report every resource at once.
+    pub fn between(old: Option<&Network>, new: Option<&Network>) -> Self {
+        let (Some(old), Some(new)) = (old, new) else {
+            return Self::default();
+        };
+
+        let visible_names = |network: &Network| -> Vec<(String, String)> {
+            network
+                .resources
+                .iter()
+                .filter(|r| r.client_visibility != 0)
+                .map(|r| (r.id.clone(), r.name.clone()))
+                .collect()
+        };
+        let old_resources = visible_names(old);
+        let new_resources = visible_names(new);
+
+        let missing_from = |resources: &[(String, String)], others: &[(String, String)]| {
+            resources
+                .iter()
+                .filter(|(id, _)| !others.iter().any(|(other_id, _)| other_id == id))
+                .map(|(_, name)| name.clone())
+                .collect()
+        };
+
+        Self {
+            added: missing_from(&new_resources, &old_resources),
+            removed: missing_from(&old_resources, &new_resources),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+
+    pub fn summary(&self) -> String {
+        match (self.added.len(), self.removed.len()) {
+            (1, 0) => "New Twingate resource available".to_string(),
+            (added, 0) => format!("{} new Twingate resources available", added),
+            (0, 1) => "Twingate resource removed".to_string(),
+            (0, removed) => format!("{} Twingate resources removed", removed),
+            _ => "Twingate resources changed".to_string(),
+        }
+    }
+
+    pub fn body(&self) -> String {
+        let mut lines = Vec::new();
+        if !self.added.is_empty() {
+            lines.push(format!("Added: {}", list_names(&self.added)));
+        }
+        if !self.removed.is_empty() {
+            lines.push(format!("Removed: {}", list_names(&self.removed)));
+        }
+        lines.join("\n")
+    }
+}
+
+fn list_names(names: &[String]) -> String {
+    let listed = names
+        .iter()
+        .take(MAX_LISTED_NAMES)
+        .map(|name| escape_markup(name))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    match names.len().saturating_sub(MAX_LISTED_NAMES) {
+        0 => listed,
+        more => format!("{} and {} more", listed, more),
+    }
+}
+
+/// Notification bodies may be rendered as markup, so escape resource names
+pub(crate) fn escape_markup(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Session bus connection shared by every notification
+static SESSION_BUS: OnceCell<Connection> = OnceCell::const_new();
+
+async fn session_bus() -> Result<&'static Connection> {
+    Ok(SESSION_BUS.get_or_try_init(Connection::session).await?)
+}
+
+/// Show a desktop notification through the session bus
+pub async fn send_notification(summary: &str, body: &str) -> Result<u32> {
+    let proxy = NotificationsProxy::new(session_bus().await?).await?;
+
+    let id = proxy
+        .notify(
+            APP_NAME,
+            0,
+            NOTIFICATION_ICON,
+            summary,
+            body,
+            &[],
+            HashMap::new(),
+            DEFAULT_EXPIRE_TIMEOUT,
+        )
+        .await?;
+
+    log::debug!("Sent notification {}: {}", id, summary);
+    Ok(id)
+}
+
+/// Show a notification with an action button and wait until it is clicked
+/// or dismissed; returns whether the action was invoked
+pub async fn send_actionable_notification(
+    summary: &str,
+    body: &str,
+    action: NotificationAction,
+) -> Result<bool> {
+    let proxy = NotificationsProxy::new(session_bus().await?).await?;
+
+    // Subscribe before sending so a quick click is not missed
+    let invoked = proxy.receive_action_invoked().await?.filter_map(|signal| async move {
+        let args = signal.args().ok()?;
+        Some(NotificationEvent::Invoked(*args.id(), args.action_key().clone()))
+    });
+    let closed = proxy.receive_notification_closed().await?.filter_map(|signal| async move {
+        let args = signal.args().ok()?;
+        Some(NotificationEvent::Closed(*args.id()))
+    });
+    let mut events = stream::select(Box::pin(invoked), Box::pin(closed));
+
+    let id = proxy
+        .notify(
+            APP_NAME,
+            0,
+            NOTIFICATION_ICON,
+            summary,
+            body,
+            &[action.key, action.label],
+            HashMap::new(),
+            ACTION_EXPIRE_TIMEOUT_MS,
+        )
+        .await?;
+    log::debug!("Sent notification {} with action '{}': {}", id, action.key, summary);
+
+    let invoked = timeout(ACTION_WAIT, async {
+        while let Some(event) = events.next().await {
+            match event {
+                NotificationEvent::Invoked(event_id, key) if event_id == id => return key == action.key,
+                NotificationEvent::Closed(event_id) if event_id == id => return false,
+                _ => {}
+            }
+        }
+        false
+    })
+    .await;
+
+    match invoked {
+        Ok(invoked) => Ok(invoked),
+        Err(_) => {
+            log::debug!("No response to notification {} within {:?}", id, ACTION_WAIT);
+            Ok(false)
+        }
+    }
+}
+
+/// Tell the user about added or removed resources, unless disabled in config
+pub fn notify_resource_changes(app_handle: &AppHandle, changes: ResourceChanges) {
+    if changes.is_empty() {
+        return;
+    }
+
+    let enabled = app_handle
+        .try_state::<AppConfig>()
+        .map(|config| config.notify_resource_changes)
+        .unwrap_or(true);
+    if !enabled {
+        log::debug!("Resource change notifications disabled: {:?}", changes);
+        return;
+    }
+
+    println!("{}: {}", changes.summary(), changes.body());
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = send_notification(&changes.summary(), &changes.body()).await {
+            log::warn!("Failed to show resource change notification: {}", e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Resource;
+    use crate::test_support::{network, resource};
+
+    fn named(id: &str, name: &str) -> Resource {
+        resource(id).name(name).build()
+    }
+
+    #[test]
+    fn test_resource_changes_between() {
+        let old = network(vec![named("r1", "Database"), named("r2", "Wiki")]).build();
+        let new = network(vec![named("r2", "Wiki"), named("r3", "Grafana")]).build();
+
+        let changes = ResourceChanges::between(Some(&old), Some(&new));
+
+        assert_eq!(changes.added, vec!["Grafana"]);
+        assert_eq!(changes.removed, vec!["Database"]);
+    }
+
+    #[test]
+    fn test_resource_changes_matches_by_id() {
+        let old = network(vec![named("r1", "Database")]).build();
+        let new = network(vec![named("r1", "Database (primary)")]).build();
+
+        assert!(ResourceChanges::between(Some(&old), Some(&new)).is_empty());
+    }
+
+    #[test]
+    fn test_resource_changes_ignores_connect_and_disconnect() {
+        let connected = network(vec![named("r1", "Database")]).build();
+
+        assert!(ResourceChanges::between(None, Some(&connected)).is_empty());
+        assert!(ResourceChanges::between(Some(&connected), None).is_empty());
+    }
+
+    #[test]
+    fn test_resource_changes_ignores_hidden_resources() {
+        let old = network(vec![]).build();
+        let new = network(vec![resource("r1").name("Hidden").hidden().build()]).build();
+
+        assert!(ResourceChanges::between(Some(&old), Some(&new)).is_empty());
+    }
+
+    #[test]
+    fn test_resource_changes_summary() {
+        let changes = |added: &[&str], removed: &[&str]| ResourceChanges {
+            added: added.iter().map(|s| s.to_string()).collect(),
+            removed: removed.iter().map(|s| s.to_string()).collect(),
+        };
+
+        assert_eq!(changes(&["a"], &[]).summary(), "New Twingate resource available");
+        assert_eq!(changes(&["a", "b"], &[]).summary(), "2 new Twingate resources available");
+        assert_eq!(changes(&[], &["a"]).summary(), "Twingate resource removed");
+        assert_eq!(changes(&[], &["a", "b"]).summary(), "2 Twingate resources removed");
+        assert_eq!(changes(&["a"], &["b"]).summary(), "Twingate resources changed");
+    }
+
+    #[test]
+    fn test_resource_changes_body() {
+        let changes = ResourceChanges {
+            added: vec!["Grafana".to_string(), "R&D <lab>".to_string()],
+            removed: vec!["Database".to_string()],
+        };
+
+        assert_eq!(
+            changes.body(),
+            "Added: Grafana, R&amp;D &lt;lab&gt;\nRemoved: Database"
+        );
+    }
+
+    #[test]
+    fn test_resource_changes_body_truncates_long_lists() {
+        let changes = ResourceChanges {
+            added: (1..=8).map(|i| format!("res{}", i)).collect(),
+            removed: vec![],
+        };
+
+        assert_eq!(
+            changes.body(),
+            "Added: res1, res2, res3, res4, res5 and 3 more"
+        );
+    }
+}
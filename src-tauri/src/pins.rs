@@ -0,0 +1,126 @@
+use crate::config::{load_json, save_json};
+use crate::error::{Result, TwingateError};
+use crate::managers::{StateManager, TrayManager};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+const PINS_FILE_NAME: &str = "pins.json";
+
+/// Resources pinned to the top of the menu, by id in the order they were pinned.
+///
+/// Ids stay pinned while their resource is missing from the network, so a
+/// resource that disappears for a while comes back pinned.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Pins {
+    pub resource_ids: Vec<String>,
+}
+
+impl Pins {
+    /// Load the pins for this app, starting empty if they are missing or invalid
+    pub fn load(app_handle: &AppHandle) -> Self {
+        let Some(path) = pins_path(app_handle) else {
+            log::warn!("Could not resolve config directory, starting without pins");
+            return Self::default();
+        };
+
+        match Self::load_from(&path) {
+            Ok(pins) => pins,
+            Err(e) => {
+                log::warn!("Failed to load {}: {} - starting without pins", path.display(), e);
+                eprintln!("Warning: Failed to load {}: {}", path.display(), e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Read a pins file; a missing file means nothing is pinned
+    pub fn load_from(path: &Path) -> Result<Self> {
+        load_json(path)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        save_json(self, path)
+    }
+
+    pub fn contains(&self, resource_id: &str) -> bool {
+        self.resource_ids.iter().any(|id| id == resource_id)
+    }
+
+    /// Pin a resource below the ones already pinned
+    pub fn pin(&mut self, resource_id: &str) {
+        if !self.contains(resource_id) {
+            self.resource_ids.push(resource_id.to_string());
+        }
+    }
+
+    pub fn unpin(&mut self, resource_id: &str) {
+        self.resource_ids.retain(|id| id != resource_id);
+    }
+}
+
+/// Location of `pins.json` under the XDG config directory
+pub fn pins_path(app_handle: &AppHandle) -> Option<PathBuf> {
+    app_handle
+        .path()
+        .app_config_dir()
+        .ok()
+        .map(|dir| dir.join(PINS_FILE_NAME))
+}
+
+/// Pin or unpin a resource, save the pins and show the change in the menu
+pub async fn set_pinned(app_handle: &AppHandle, resource_id: &str, pinned: bool) -> Result<()> {
+    let pins = StateManager::with_state_mut(app_handle, |state| {
+        let mut pins = state.pins().clone();
+        if pinned {
+            pins.pin(resource_id);
+        } else {
+            pins.unpin(resource_id);
+        }
+        state.set_pins(pins.clone());
+        pins
+    });
+
+    let path = pins_path(app_handle)
+        .ok_or_else(|| TwingateError::config_error("cannot resolve the config directory"))?;
+    pins.save_to(&path)?;
+    TrayManager::rebuild_tray_now(app_handle).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_pin_and_unpin() {
+        let mut pins = Pins::default();
+        pins.pin("r2");
+        pins.pin("r1");
+        pins.pin("r2");
+        assert_eq!(pins.resource_ids, vec!["r2", "r1"]);
+        assert!(pins.contains("r1"));
+
+        pins.unpin("r2");
+        pins.unpin("missing");
+        assert_eq!(pins.resource_ids, vec!["r1"]);
+        assert!(!pins.contains("r2"));
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("twingate-tray").join(PINS_FILE_NAME);
+        assert_eq!(Pins::load_from(&path).unwrap(), Pins::default());
+
+        let mut pins = Pins::default();
+        pins.pin("UmVzb3VyY2U6MQ==");
+        pins.pin("r-2");
+        pins.save_to(&path).unwrap();
+        assert_eq!(Pins::load_from(&path).unwrap(), pins);
+
+        fs::write(&path, "[").unwrap();
+        assert!(matches!(Pins::load_from(&path), Err(TwingateError::JsonError { .. })));
+    }
+}
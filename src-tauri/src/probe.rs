@@ -0,0 +1,332 @@
+use crate::aliases::DefaultAliases;
+use crate::config::AppConfig;
+use crate::error::{Result, TwingateError};
+use crate::managers::{NetworkDataManager, StateManager, TrayManager};
+use crate::models::Resource;
+use crate::notifications::send_notification;
+use chrono::Utc;
+use futures_util::future::join_all;
+use std::io::ErrorKind;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::net::{lookup_host, TcpStream};
+use tokio::time::{interval_at, timeout, Instant, MissedTickBehavior};
+
+/// How long to wait for the address to resolve, and for each connection
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+/// Resolved addresses tried on each port
+const MAX_PROBED_ADDRESSES: usize = 2;
+
+/// What a connection test found out about an address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProbeOutcome {
+    /// A port accepted the connection
+    Reachable { port: u16, latency: Duration },
+    /// The host answered, but refused every port
+    Refused { latency: Duration },
+    /// Nothing answered in time
+    Unreachable,
+    /// The address did not resolve
+    DnsFailed(String),
+    /// Wildcards and ranges name no single host to connect to
+    NotTestable,
+}
+
+/// A connection test of one resource
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProbeResult {
+    pub address: String,
+    pub ports: Vec<u16>,
+    pub outcome: ProbeOutcome,
+    /// The resource needed authentication when it was tested, which explains
+    /// most failures
+    pub auth_required: bool,
+}
+
+impl ProbeResult {
+    pub fn is_reachable(&self) -> bool {
+        matches!(self.outcome, ProbeOutcome::Reachable { .. })
+    }
+
+    /// One line for the resource's submenu
+    pub fn summary(&self) -> String {
+        let summary = match &self.outcome {
+            ProbeOutcome::Reachable { port, latency } => {
+                format!("Reachable on port {} ({})", port, format_latency(*latency))
+            }
+            ProbeOutcome::Refused { latency } => {
+                format!("Host up, {} closed ({})", port_list(&self.ports), format_latency(*latency))
+            }
+            ProbeOutcome::Unreachable => format!("No response on {}", port_list(&self.ports)),
+            ProbeOutcome::DnsFailed(_) => "DNS lookup failed".to_string(),
+            ProbeOutcome::NotTestable => "Address range cannot be tested".to_string(),
+        };
+
+        if self.auth_required && !self.is_reachable() {
+            format!("{} (authentication required)", summary)
+        } else {
+            summary
+        }
+    }
+
+    /// Title and body of a notification about the result
+    pub fn notification(&self, resource_name: &str) -> (String, String) {
+        let title = match self.outcome {
+            ProbeOutcome::Reachable { .. } => format!("{} is reachable", resource_name),
+            ProbeOutcome::NotTestable => format!("{} cannot be tested", resource_name),
+            _ => format!("{} is not reachable", resource_name),
+        };
+        let body = match &self.outcome {
+            ProbeOutcome::DnsFailed(error) => format!("{}: DNS lookup failed: {}", self.address, error),
+            _ => format!("{}: {}", self.address, self.summary()),
+        };
+        (title, body)
+    }
+}
+
+fn format_latency(latency: Duration) -> String {
+    match latency.as_millis() {
+        0 => "<1 ms".to_string(),
+        ms => format!("{} ms", ms),
+    }
+}
+
+fn port_list(ports: &[u16]) -> String {
+    let ports: Vec<String> = ports.iter().map(u16::to_string).collect();
+    match ports.as_slice() {
+        [port] => format!("port {}", port),
+        _ => format!("ports {}", ports.join(", ")),
+    }
+}
+
+/// Resolve `address` and try a TCP connection to each port, waiting at most
+/// `wait` for the lookup and for each connection.
+///
+/// Only TCP is tried: an ICMP ping needs a raw socket, which takes privileges
+/// the tray does not have.
+pub async fn probe(address: &str, ports: &[u16], wait: Duration) -> ProbeOutcome {
+    let host = address.trim();
+    if host.is_empty() || host.contains(['/', '*']) {
+        return ProbeOutcome::NotTestable;
+    }
+
+    let resolved: Vec<IpAddr> = match timeout(wait, lookup_host((host, 0))).await {
+        Ok(Ok(addrs)) => {
+            let mut ips: Vec<IpAddr> = Vec::new();
+            for addr in addrs {
+                if !ips.contains(&addr.ip()) {
+                    ips.push(addr.ip());
+                }
+            }
+            ips.truncate(MAX_PROBED_ADDRESSES);
+            ips
+        }
+        Ok(Err(e)) => return ProbeOutcome::DnsFailed(e.to_string()),
+        Err(_) => return ProbeOutcome::DnsFailed(format!("no answer within {}s", wait.as_secs())),
+    };
+    if resolved.is_empty() {
+        return ProbeOutcome::DnsFailed("no addresses".to_string());
+    }
+
+    let attempts = ports.iter().flat_map(|&port| {
+        resolved.iter().map(move |&ip| async move {
+            let started = Instant::now();
+            let result = timeout(wait, TcpStream::connect(SocketAddr::new(ip, port))).await;
+            (port, started.elapsed(), result)
+        })
+    });
+
+    let mut refused: Option<Duration> = None;
+    let mut reachable: Option<(u16, Duration)> = None;
+    // Connecting to every port at once keeps a test to one timeout; results
+    // come back in port order, so the first configured port that answers wins
+    for (port, latency, result) in join_all(attempts).await {
+        match result {
+            Ok(Ok(_)) => {
+                reachable.get_or_insert((port, latency));
+            }
+            Ok(Err(e)) if e.kind() == ErrorKind::ConnectionRefused => {
+                refused = Some(refused.map_or(latency, |fastest| fastest.min(latency)));
+            }
+            Ok(Err(e)) => log::debug!("Connecting to {} port {} failed: {}", host, port, e),
+            Err(_) => log::debug!("Connecting to {} port {} timed out", host, port),
+        }
+    }
+
+    match (reachable, refused) {
+        (Some((port, latency)), _) => ProbeOutcome::Reachable { port, latency },
+        (None, Some(latency)) => ProbeOutcome::Refused { latency },
+        (None, None) => ProbeOutcome::Unreachable,
+    }
+}
+
+/// Test the address a resource copies, by default its chosen alias
+pub async fn probe_resource(resource: &Resource, default_aliases: &DefaultAliases, ports: &[u16]) -> ProbeResult {
+    let address = default_aliases.address(resource).to_string();
+    let outcome = probe(&address, ports, PROBE_TIMEOUT).await;
+    ProbeResult {
+        address,
+        ports: ports.to_vec(),
+        outcome,
+        auth_required: resource.requires_auth(Utc::now()),
+    }
+}
+
+fn probe_ports(app_handle: &AppHandle) -> Vec<u16> {
+    app_handle
+        .try_state::<AppConfig>()
+        .map(|config| config.probe_ports())
+        .unwrap_or_else(|| AppConfig::default().probe_ports())
+}
+
+fn notify(result: &ProbeResult, resource_name: &str) {
+    let (title, body) = result.notification(resource_name);
+    println!("{}: {}", title, body);
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = send_notification(&title, &body).await {
+            log::warn!("Failed to show connection test notification: {}", e);
+        }
+    });
+}
+
+/// Test a resource now, show the result in its submenu and as a notification
+pub async fn test_connection(app_handle: &AppHandle, resource_id: &str) -> Result<()> {
+    let network_manager = NetworkDataManager::new(app_handle, Duration::from_secs(30));
+    let network = network_manager.get_network_or_error().await?;
+    let resource = network
+        .resources
+        .iter()
+        .find(|r| r.id == resource_id)
+        .ok_or_else(|| TwingateError::resource_not_found(resource_id))?;
+
+    let default_aliases = StateManager::with_state(app_handle, |state| state.default_aliases().clone());
+    let result = probe_resource(resource, &default_aliases, &probe_ports(app_handle)).await;
+    notify(&result, &resource.name);
+
+    StateManager::with_state_mut(app_handle, |state| state.set_probe_result(resource_id, result));
+    TrayManager::rebuild_tray_now(app_handle).await
+}
+
+/// Test the pinned resources in the background, and tell the user when one
+/// becomes reachable or stops being reachable
+pub fn spawn_pinned_probes(app_handle: AppHandle, interval: Duration) {
+    log::info!("Testing connections to pinned resources every {:?}", interval);
+
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = interval_at(Instant::now() + interval, interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+
+            let (pinned, default_aliases) = StateManager::with_state(&app_handle, |state| {
+                let pinned: Vec<Resource> = state
+                    .network()
+                    .map(|network| {
+                        network
+                            .resources
+                            .iter()
+                            .filter(|r| r.client_visibility != 0 && state.pins().contains(&r.id))
+                            .cloned()
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                (pinned, state.default_aliases().clone())
+            });
+            if pinned.is_empty() {
+                continue;
+            }
+
+            let ports = probe_ports(&app_handle);
+            for resource in &pinned {
+                let result = probe_resource(resource, &default_aliases, &ports).await;
+                let previous = StateManager::with_state_mut(&app_handle, |state| {
+                    state.set_probe_result(&resource.id, result.clone())
+                });
+                if previous.is_some_and(|previous| previous.is_reachable() != result.is_reachable()) {
+                    notify(&result, &resource.name);
+                }
+            }
+
+            if let Err(e) = TrayManager::rebuild_tray_now(&app_handle).await {
+                log::warn!("Failed to show connection test results: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    fn result(outcome: ProbeOutcome, auth_required: bool) -> ProbeResult {
+        ProbeResult {
+            address: "db.corp.internal".to_string(),
+            ports: vec![443, 22],
+            outcome,
+            auth_required,
+        }
+    }
+
+    #[test]
+    fn test_probe_local_listener() {
+        block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let open = listener.local_addr().unwrap().port();
+            let closed = {
+                let spare = TcpListener::bind("127.0.0.1:0").await.unwrap();
+                spare.local_addr().unwrap().port()
+            };
+
+            let outcome = probe("127.0.0.1", &[closed, open], PROBE_TIMEOUT).await;
+            assert!(matches!(outcome, ProbeOutcome::Reachable { port, .. } if port == open));
+
+            let outcome = probe("localhost", &[closed], PROBE_TIMEOUT).await;
+            assert!(matches!(outcome, ProbeOutcome::Refused { .. }), "{:?}", outcome);
+        });
+    }
+
+    #[test]
+    fn test_probe_untestable_and_unresolvable_addresses() {
+        block_on(async {
+            assert_eq!(probe("10.0.0.0/8", &[443], PROBE_TIMEOUT).await, ProbeOutcome::NotTestable);
+            assert_eq!(probe("*.corp.internal", &[443], PROBE_TIMEOUT).await, ProbeOutcome::NotTestable);
+            assert!(matches!(
+                probe("missing.invalid", &[443], PROBE_TIMEOUT).await,
+                ProbeOutcome::DnsFailed(_)
+            ));
+        });
+    }
+
+    #[test]
+    fn test_result_summary() {
+        let reachable = ProbeOutcome::Reachable {
+            port: 22,
+            latency: Duration::from_millis(12),
+        };
+        assert_eq!(result(reachable.clone(), true).summary(), "Reachable on port 22 (12 ms)");
+        assert_eq!(
+            result(ProbeOutcome::Refused { latency: Duration::from_micros(300) }, false).summary(),
+            "Host up, ports 443, 22 closed (<1 ms)"
+        );
+        assert_eq!(
+            result(ProbeOutcome::Unreachable, true).summary(),
+            "No response on ports 443, 22 (authentication required)"
+        );
+
+        let (title, body) = result(ProbeOutcome::DnsFailed("no such host".to_string()), false).notification("Db");
+        assert_eq!(title, "Db is not reachable");
+        assert_eq!(body, "db.corp.internal: DNS lookup failed: no such host");
+        assert_eq!(result(reachable, false).notification("Db").0, "Db is reachable");
+    }
+}
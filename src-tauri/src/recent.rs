@@ -0,0 +1,163 @@
+use crate::config::{load_json, save_json};
+use crate::error::Result;
+use crate::managers::{StateManager, TrayManager};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+const RECENT_FILE_NAME: &str = "recent.json";
+/// Uses remembered beyond the ones shown, so resources that are missing for
+/// a while or hidden by pins still keep their place
+const MAX_REMEMBERED: usize = 50;
+
+/// When a resource was last copied, opened or authenticated to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecentUse {
+    pub resource_id: String,
+    /// RFC 3339 time of the last use
+    pub used_at: String,
+}
+
+/// Resources by last use, most recent first
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecentResources {
+    pub uses: Vec<RecentUse>,
+}
+
+impl RecentResources {
+    /// Load the history for this app, starting empty if it is missing or invalid
+    pub fn load(app_handle: &AppHandle) -> Self {
+        let Some(path) = recent_path(app_handle) else {
+            log::warn!("Could not resolve data directory, starting without recent resources");
+            return Self::default();
+        };
+
+        match Self::load_from(&path) {
+            Ok(recent) => recent,
+            Err(e) => {
+                log::warn!("Failed to load {}: {} - starting without recent resources", path.display(), e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Read a history file; a missing file means nothing was used yet
+    pub fn load_from(path: &Path) -> Result<Self> {
+        load_json(path)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        save_json(self, path)
+    }
+
+    /// Move a resource to the front, forgetting the oldest uses past the limit
+    pub fn record(&mut self, resource_id: &str, now: DateTime<Utc>) {
+        self.uses.retain(|u| u.resource_id != resource_id);
+        self.uses.insert(
+            0,
+            RecentUse {
+                resource_id: resource_id.to_string(),
+                used_at: now.to_rfc3339(),
+            },
+        );
+        self.uses.truncate(MAX_REMEMBERED);
+    }
+
+    /// Ids of the resources used, most recent first
+    pub fn resource_ids(&self) -> impl Iterator<Item = &str> {
+        self.uses.iter().map(|u| u.resource_id.as_str())
+    }
+}
+
+/// Location of `recent.json` under the XDG data directory
+pub fn recent_path(app_handle: &AppHandle) -> Option<PathBuf> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join(RECENT_FILE_NAME))
+}
+
+/// Remember that a resource was just used, and move it up the "Recently
+/// Used" section if that changes what the section shows.
+///
+/// Failing to save only loses the history, so errors are logged rather than
+/// failing the action that used the resource.
+pub async fn record_use(app_handle: &AppHandle, resource_id: &str) {
+    let (recent, menu_changed) = StateManager::with_state_mut(app_handle, |state| {
+        if state.menu_layout().recent_count == 0 {
+            return (None, false);
+        }
+        let before = state.menu_layout().recent_section_ids(state.network());
+        let mut recent = state.menu_layout().recent.clone();
+        recent.record(resource_id, Utc::now());
+        state.set_recent(recent.clone());
+        let after = state.menu_layout().recent_section_ids(state.network());
+        (Some(recent), before != after)
+    });
+    let Some(recent) = recent else {
+        return;
+    };
+
+    match recent_path(app_handle) {
+        Some(path) => {
+            if let Err(e) = recent.save_to(&path) {
+                log::warn!("Failed to save recent resources: {}", e);
+            }
+        }
+        None => log::warn!("Could not resolve data directory, recent resources not saved"),
+    }
+
+    if menu_changed {
+        if let Err(e) = TrayManager::rebuild_tray_now(app_handle).await {
+            log::warn!("Failed to show recent resources in the menu: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(recent: &RecentResources) -> Vec<&str> {
+        recent.resource_ids().collect()
+    }
+
+    #[test]
+    fn test_record_moves_to_front() {
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let mut recent = RecentResources::default();
+        recent.record("r1", now);
+        recent.record("r2", now);
+        recent.record("r1", now + chrono::Duration::minutes(5));
+
+        assert_eq!(ids(&recent), vec!["r1", "r2"]);
+        assert_eq!(recent.uses[0].used_at, "2023-11-14T22:18:20+00:00");
+    }
+
+    #[test]
+    fn test_record_forgets_oldest() {
+        let mut recent = RecentResources::default();
+        for n in 0..MAX_REMEMBERED + 3 {
+            recent.record(&format!("r{}", n), Utc::now());
+        }
+
+        assert_eq!(recent.uses.len(), MAX_REMEMBERED);
+        assert_eq!(recent.uses[0].resource_id, format!("r{}", MAX_REMEMBERED + 2));
+        assert!(!ids(&recent).contains(&"r2"));
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("twingate-tray").join(RECENT_FILE_NAME);
+        assert_eq!(RecentResources::load_from(&path).unwrap(), RecentResources::default());
+
+        let mut recent = RecentResources::default();
+        recent.record("UmVzb3VyY2U6MQ==", Utc::now());
+        recent.save_to(&path).unwrap();
+        assert_eq!(RecentResources::load_from(&path).unwrap(), recent);
+    }
+}
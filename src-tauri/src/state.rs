@@ -1,4 +1,12 @@
+use crate::aliases::DefaultAliases;
+use crate::icons::TrayIconKind;
+use crate::probe::ProbeResult;
+use crate::grouping::Grouping;
+use crate::launch::LaunchActions;
+use crate::menu::{MenuLayout, MenuModel};
 use crate::models::Network;
+use crate::pins::Pins;
+use crate::recent::RecentResources;
 use std::time::{Duration, Instant};
 
 /// Service connection status
@@ -29,6 +37,14 @@ pub struct AppState {
     last_update: Option<Instant>,
     /// Whether a refresh operation is currently in progress
     refreshing: bool,
+    /// Menu most recently shown in the tray
+    rendered_menu: Option<MenuModel>,
+    /// Icon most recently shown in the tray
+    rendered_icon: Option<TrayIconKind>,
+    /// Why the last status refresh failed, cleared by the next successful one
+    last_error: Option<String>,
+    /// Pins, grouping, recent resources and other extras of the connected menu
+    menu_layout: MenuLayout,
 }
 
 impl AppState {
@@ -46,6 +62,30 @@ impl AppState {
         &self.service_status
     }
     
+    pub fn rendered_menu(&self) -> Option<&MenuModel> {
+        self.rendered_menu.as_ref()
+    }
+    
+    pub fn rendered_icon(&self) -> Option<TrayIconKind> {
+        self.rendered_icon
+    }
+    
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+    
+    pub fn menu_layout(&self) -> &MenuLayout {
+        &self.menu_layout
+    }
+    
+    pub fn pins(&self) -> &Pins {
+        &self.menu_layout.pins
+    }
+    
+    pub fn default_aliases(&self) -> &DefaultAliases {
+        &self.menu_layout.default_aliases
+    }
+    
     pub fn auth_url(&self) -> Option<&str> {
         match &self.service_status {
             ServiceStatus::Authenticating(url) => Some(url),
@@ -65,6 +105,50 @@ impl AppState {
         };
         self.last_update = Some(Instant::now());
         self.refreshing = false;
+        self.last_error = None;
+    }
+    
+    pub fn set_rendered_menu(&mut self, menu: MenuModel) {
+        self.rendered_menu = Some(menu);
+    }
+    
+    pub fn set_rendered_icon(&mut self, icon: TrayIconKind) {
+        self.rendered_icon = Some(icon);
+    }
+    
+    /// Record a failed refresh; the last known network data is kept
+    pub fn set_error(&mut self, error: String) {
+        self.last_error = Some(error);
+        self.refreshing = false;
+    }
+    
+    pub fn set_pins(&mut self, pins: Pins) {
+        self.menu_layout.pins = pins;
+    }
+    
+    pub fn set_default_aliases(&mut self, default_aliases: DefaultAliases) {
+        self.menu_layout.default_aliases = default_aliases;
+    }
+    
+    pub fn set_grouping(&mut self, grouping: Grouping) {
+        self.menu_layout.grouping = grouping;
+    }
+    
+    pub fn set_recent(&mut self, recent: RecentResources) {
+        self.menu_layout.recent = recent;
+    }
+    
+    pub fn set_recent_count(&mut self, count: usize) {
+        self.menu_layout.recent_count = count;
+    }
+    
+    /// Remember a resource's latest connection test, returning the one before
+    pub fn set_probe_result(&mut self, resource_id: &str, result: ProbeResult) -> Option<ProbeResult> {
+        self.menu_layout.probes.insert(resource_id.to_string(), result)
+    }
+    
+    pub fn set_launch_actions(&mut self, launch_actions: LaunchActions) {
+        self.menu_layout.launch_actions = launch_actions;
     }
     
     pub fn set_authenticating(&mut self, auth_url: String) {
@@ -72,6 +156,7 @@ impl AppState {
         self.network = None;
         self.last_update = Some(Instant::now());
         self.refreshing = false;
+        self.last_error = None;
     }
     
     
@@ -145,6 +230,7 @@ mod tests {
         assert!(state.auth_url().is_none());
         assert!(state.last_update.is_none());
         assert!(!state.refreshing);
+        assert!(state.rendered_menu().is_none());
     }
 
     #[test]
@@ -322,5 +408,32 @@ mod tests {
         let cloned = status.clone();
         assert_eq!(status, cloned);
     }
-}
 
+    #[test]
+    fn test_rendered_menu_survives_state_updates() {
+        let mut state = AppState::new();
+        let menu = crate::menu::disconnected_menu();
+
+        state.set_rendered_menu(menu.clone());
+        state.update_network(Some(create_test_network()));
+
+        assert_eq!(state.rendered_menu(), Some(&menu));
+    }
+
+    #[test]
+    fn test_error_cleared_by_successful_update() {
+        let mut state = AppState::new();
+        state.update_network(Some(create_test_network()));
+
+        state.set_error("status poll failed".to_string());
+        assert_eq!(state.last_error(), Some("status poll failed"));
+        assert!(state.network().is_some(), "Last known network is kept");
+
+        state.update_network(Some(create_test_network()));
+        assert!(state.last_error().is_none());
+
+        state.set_error("status poll failed".to_string());
+        state.set_authenticating("https://auth.example.com".to_string());
+        assert!(state.last_error().is_none());
+    }
+}
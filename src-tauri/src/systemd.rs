@@ -0,0 +1,450 @@
+use crate::error::Result;
+use crate::managers::{StateManager, TrayManager};
+use crate::state::{AppState, ServiceStatus};
+use futures_util::stream::{self, StreamExt};
+use tauri::AppHandle;
+use zbus::{proxy, zvariant::OwnedObjectPath, Connection, PropertyStream};
+
+const ACTIVE: &str = "active";
+const INACTIVE: &str = "inactive";
+const FAILED: &str = "failed";
+
+#[proxy(
+    interface = "org.freedesktop.systemd1.Manager",
+    default_service = "org.freedesktop.systemd1",
+    default_path = "/org/freedesktop/systemd1"
+)]
+trait SystemdManager {
+    fn load_unit(&self, name: &str) -> zbus::Result<OwnedObjectPath>;
+
+    /// systemd only emits unit change signals while some client is subscribed
+    fn subscribe(&self) -> zbus::Result<()>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.systemd1.Unit",
+    default_service = "org.freedesktop.systemd1"
+)]
+trait SystemdUnit {
+    #[zbus(property)]
+    fn active_state(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn sub_state(&self) -> zbus::Result<String>;
+}
+
+/// The ActiveState/SubState pair systemd reports for a unit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnitState {
+    pub active_state: String,
+    pub sub_state: String,
+}
+
+/// A change of the service unit worth reacting to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnitTransition {
+    Started,
+    Stopped,
+    Failed { sub_state: String },
+}
+
+impl UnitState {
+    /// The transition from `previous` to this state, ignoring the
+    /// activating/deactivating/reloading steps in between
+    pub fn transition_from(&self, previous: &UnitState) -> Option<UnitTransition> {
+        if self.active_state == previous.active_state {
+            return None;
+        }
+
+        match self.active_state.as_str() {
+            ACTIVE => Some(UnitTransition::Started),
+            INACTIVE => Some(UnitTransition::Stopped),
+            FAILED => Some(UnitTransition::Failed {
+                sub_state: self.sub_state.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl UnitTransition {
+    /// The error a transition leaves on the state, if any
+    pub fn error(&self) -> Option<String> {
+        match self {
+            UnitTransition::Failed { sub_state } => Some(format!("Twingate service failed ({})", sub_state)),
+            UnitTransition::Started | UnitTransition::Stopped => None,
+        }
+    }
+}
+
+/// Whether a unit transition disconnects the tray.
+///
+/// A start only changes the state once the client reports the network, so
+/// it is left to the refresh that follows. An authentication flow in
+/// progress owns the state until it finishes.
+pub fn disconnects(state: &AppState, transition: &UnitTransition) -> bool {
+    if matches!(state.service_status(), ServiceStatus::Authenticating(_)) {
+        log::debug!("Authentication in progress, not applying unit transition {:?}", transition);
+        return false;
+    }
+
+    !matches!(transition, UnitTransition::Started)
+}
+
+/// Drop the network after a stop or crash and record why
+fn apply_transition(app_handle: &AppHandle, transition: &UnitTransition) -> bool {
+    if !StateManager::with_state(app_handle, |state| disconnects(state, transition)) {
+        return false;
+    }
+
+    StateManager::update_network(app_handle, None);
+    if let Some(error) = transition.error() {
+        StateManager::update_state(app_handle, |state| state.set_error(error));
+    }
+    true
+}
+
+/// Follows one unit through PropertiesChanged signals
+pub struct UnitWatcher {
+    unit: String,
+    proxy: SystemdUnitProxy<'static>,
+    active_changes: PropertyStream<'static, String>,
+    sub_changes: PropertyStream<'static, String>,
+    current: UnitState,
+}
+
+impl UnitWatcher {
+    /// Look up `unit` and read its current state; signals are subscribed to
+    /// by the time this returns
+    pub async fn new(connection: &Connection, unit: &str) -> Result<Self> {
+        let manager = SystemdManagerProxy::new(connection).await?;
+        let path = manager.load_unit(unit).await?;
+        if let Err(e) = manager.subscribe().await {
+            log::warn!("Failed to subscribe to systemd signals: {}", e);
+        }
+
+        let proxy = SystemdUnitProxy::builder(connection).path(path)?.build().await?;
+        let active_changes = proxy.receive_active_state_changed().await;
+        let sub_changes = proxy.receive_sub_state_changed().await;
+        let current = read_unit_state(&proxy).await?;
+        log::debug!("Watching {} from {:?}", unit, current);
+
+        Ok(Self {
+            unit: unit.to_string(),
+            proxy,
+            active_changes,
+            sub_changes,
+            current,
+        })
+    }
+
+    /// Call `on_transition` for each start, stop or failure until the bus
+    /// connection goes away
+    pub async fn run<F>(mut self, mut on_transition: F) -> Result<()>
+    where
+        F: FnMut(UnitTransition),
+    {
+        let active_changes = self.active_changes.map(|_| ());
+        let sub_changes = self.sub_changes.map(|_| ());
+        let mut changes = stream::select(active_changes, sub_changes);
+
+        while changes.next().await.is_some() {
+            let next = read_unit_state(&self.proxy).await?;
+            if let Some(transition) = next.transition_from(&self.current) {
+                log::debug!("{} changed from {:?} to {:?}", self.unit, self.current, next);
+                on_transition(transition);
+            }
+            self.current = next;
+        }
+
+        Ok(())
+    }
+}
+
+async fn read_unit_state(unit: &SystemdUnitProxy<'_>) -> Result<UnitState> {
+    Ok(UnitState {
+        active_state: unit.active_state().await?,
+        sub_state: unit.sub_state().await?,
+    })
+}
+
+/// Watch the Twingate unit on the system bus and refresh the tray as soon as
+/// the service starts, stops or crashes
+pub fn spawn_unit_watcher(app_handle: AppHandle, unit: String) {
+    log::info!("Watching systemd unit {}", unit);
+
+    tauri::async_runtime::spawn(async move {
+        let connection = match Connection::system().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                log::warn!("Cannot watch {}, system bus unavailable: {}", unit, e);
+                return;
+            }
+        };
+
+        let watcher = match UnitWatcher::new(&connection, &unit).await {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                log::warn!("Cannot watch {}: {}", unit, e);
+                return;
+            }
+        };
+
+        let result = watcher.run(|transition| {
+            println!("Twingate service unit {:?}", transition);
+            let changed = apply_transition(&app_handle, &transition);
+
+            if transition == UnitTransition::Started {
+                TrayManager::rebuild_tray_after_delay(app_handle.clone());
+            } else if changed {
+                let app_handle = app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = TrayManager::rebuild_tray_now(&app_handle).await {
+                        log::warn!("Failed to rebuild tray after unit change: {}", e);
+                    }
+                });
+            }
+        })
+        .await;
+
+        match result {
+            Ok(()) => log::warn!("Stopped watching {}: system bus connection closed", unit),
+            Err(e) => log::warn!("Stopped watching {}: {}", unit, e),
+        }
+    });
+}
+
+/// Throwaway session bus for exercising D-Bus code in tests
+#[cfg(test)]
+pub mod private_bus {
+    use std::io::{BufRead, BufReader};
+    use std::process::{Child, Command, Stdio};
+    use zbus::{Connection, ConnectionBuilder};
+
+    /// Set to skip the private bus tests where dbus-daemon is not installed
+    const SKIP_ENV: &str = "TWINGATE_TRAY_SKIP_DBUS_TESTS";
+
+    /// A dbus-daemon of our own, killed on drop
+    pub struct PrivateBus {
+        daemon: Child,
+        pub address: String,
+    }
+
+    impl PrivateBus {
+        /// Start a private session bus, or `None` if the tests were told to
+        /// skip it; a missing dbus-daemon fails the test otherwise
+        pub fn start() -> Option<Self> {
+            if std::env::var_os(SKIP_ENV).is_some() {
+                eprintln!("{} is set, skipping", SKIP_ENV);
+                return None;
+            }
+
+            let mut daemon = Command::new("dbus-daemon")
+                .args(["--session", "--nofork", "--print-address=1"])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .unwrap_or_else(|e| panic!("Cannot start dbus-daemon ({}); set {} to skip", e, SKIP_ENV));
+
+            let mut address = String::new();
+            BufReader::new(daemon.stdout.take().unwrap())
+                .read_line(&mut address)
+                .unwrap();
+            Some(Self {
+                daemon,
+                address: address.trim().to_string(),
+            })
+        }
+
+        pub async fn connect(&self) -> Connection {
+            ConnectionBuilder::address(self.address.as_str())
+                .unwrap()
+                .build()
+                .await
+                .unwrap()
+        }
+    }
+
+    impl Drop for PrivateBus {
+        fn drop(&mut self) {
+            let _ = self.daemon.kill();
+            let _ = self.daemon.wait();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::private_bus::PrivateBus;
+    use super::*;
+    use crate::test_support::network;
+    use std::collections::HashMap;
+    use std::sync::mpsc;
+    use std::time::Duration;
+    use tauri::async_runtime::block_on;
+    use zbus::fdo::Properties;
+    use zbus::names::InterfaceName;
+    use zbus::zvariant::Value;
+    use zbus::{interface, ConnectionBuilder};
+
+    const UNIT_PATH: &str = "/org/freedesktop/systemd1/unit/twingate_2eservice";
+    const SIGNAL_TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn unit_state(active_state: &str, sub_state: &str) -> UnitState {
+        UnitState {
+            active_state: active_state.to_string(),
+            sub_state: sub_state.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_unit_transitions() {
+        let inactive = unit_state("inactive", "dead");
+        let activating = unit_state("activating", "start");
+        let active = unit_state("active", "running");
+        let failed = unit_state("failed", "failed");
+
+        assert_eq!(activating.transition_from(&inactive), None);
+        assert_eq!(active.transition_from(&activating), Some(UnitTransition::Started));
+        assert_eq!(unit_state("active", "exited").transition_from(&active), None);
+        assert_eq!(inactive.transition_from(&active), Some(UnitTransition::Stopped));
+        assert_eq!(
+            failed.transition_from(&active),
+            Some(UnitTransition::Failed {
+                sub_state: "failed".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_disconnects() {
+        let mut state = AppState::new();
+        state.update_network(Some(network(vec![]).build()));
+        let failed = UnitTransition::Failed {
+            sub_state: "failed".to_string(),
+        };
+
+        assert!(!disconnects(&state, &UnitTransition::Started));
+        assert!(disconnects(&state, &UnitTransition::Stopped));
+        assert!(disconnects(&state, &failed));
+
+        assert_eq!(UnitTransition::Stopped.error(), None);
+        assert_eq!(failed.error().as_deref(), Some("Twingate service failed (failed)"));
+    }
+
+    #[test]
+    fn test_disconnects_while_authenticating() {
+        let mut state = AppState::new();
+        state.set_authenticating("https://auth.example.com".to_string());
+
+        assert!(!disconnects(&state, &UnitTransition::Stopped));
+    }
+
+    /// Stand-in for systemd's manager object
+    struct StandInManager;
+
+    #[interface(name = "org.freedesktop.systemd1.Manager")]
+    impl StandInManager {
+        fn load_unit(&self, _name: &str) -> OwnedObjectPath {
+            OwnedObjectPath::try_from(UNIT_PATH).unwrap()
+        }
+
+        fn subscribe(&self) {}
+    }
+
+    /// Stand-in for the twingate.service unit object
+    struct StandInUnit {
+        active_state: String,
+        sub_state: String,
+    }
+
+    #[interface(name = "org.freedesktop.systemd1.Unit")]
+    impl StandInUnit {
+        #[zbus(property)]
+        fn active_state(&self) -> String {
+            self.active_state.clone()
+        }
+
+        #[zbus(property)]
+        fn sub_state(&self) -> String {
+            self.sub_state.clone()
+        }
+    }
+
+    async fn serve_stand_in(bus: &PrivateBus) -> Connection {
+        let unit = StandInUnit {
+            active_state: "inactive".to_string(),
+            sub_state: "dead".to_string(),
+        };
+
+        ConnectionBuilder::address(bus.address.as_str())
+            .unwrap()
+            .name("org.freedesktop.systemd1")
+            .unwrap()
+            .serve_at("/org/freedesktop/systemd1", StandInManager)
+            .unwrap()
+            .serve_at(UNIT_PATH, unit)
+            .unwrap()
+            .build()
+            .await
+            .unwrap()
+    }
+
+    /// Change both properties in one PropertiesChanged signal, like systemd does
+    async fn set_unit_state(service: &Connection, active_state: &str, sub_state: &str) {
+        let iface = service
+            .object_server()
+            .interface::<_, StandInUnit>(UNIT_PATH)
+            .await
+            .unwrap();
+        {
+            let mut unit = iface.get_mut().await;
+            unit.active_state = active_state.to_string();
+            unit.sub_state = sub_state.to_string();
+        }
+
+        let active = Value::from(active_state);
+        let sub = Value::from(sub_state);
+        let changed = HashMap::from([("ActiveState", &active), ("SubState", &sub)]);
+        Properties::properties_changed(
+            iface.signal_context(),
+            InterfaceName::from_static_str_unchecked("org.freedesktop.systemd1.Unit"),
+            &changed,
+            &[],
+        )
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn test_watch_unit_on_private_bus() {
+        let Some(bus) = PrivateBus::start() else {
+            return;
+        };
+        let service = block_on(serve_stand_in(&bus));
+        let client = block_on(bus.connect());
+
+        let watcher = block_on(UnitWatcher::new(&client, "twingate.service")).unwrap();
+        assert_eq!(watcher.current, unit_state("inactive", "dead"));
+
+        let (tx, rx) = mpsc::channel();
+        tauri::async_runtime::spawn(watcher.run(move |transition| {
+            let _ = tx.send(transition);
+        }));
+
+        block_on(set_unit_state(&service, "activating", "start"));
+        block_on(set_unit_state(&service, "active", "running"));
+        assert_eq!(rx.recv_timeout(SIGNAL_TIMEOUT), Ok(UnitTransition::Started));
+
+        block_on(set_unit_state(&service, "failed", "failed"));
+        assert_eq!(
+            rx.recv_timeout(SIGNAL_TIMEOUT),
+            Ok(UnitTransition::Failed {
+                sub_state: "failed".to_string()
+            })
+        );
+
+        block_on(set_unit_state(&service, "inactive", "dead"));
+        assert_eq!(rx.recv_timeout(SIGNAL_TIMEOUT), Ok(UnitTransition::Stopped));
+    }
+}
@@ -0,0 +1,129 @@
+//! Builders for the client models and DTOs shared by unit tests
+
+use crate::dto::ResourceDto;
+use crate::models::{Alias, InternetSecurity, Network, Resource, User};
+use chrono::{DateTime, Utc};
+
+/// A visible TCP resource at 10.0.0.1, named after its id, that has never
+/// been authenticated to
+pub fn resource(id: &str) -> ResourceBuilder {
+    ResourceBuilder(Resource {
+        address: "10.0.0.1".to_string(),
+        admin_url: String::new(),
+        alias: None,
+        aliases: vec![],
+        auth_expires_at: None,
+        auth_flow_id: String::new(),
+        auth_state: String::new(),
+        can_open_in_browser: false,
+        client_visibility: 1,
+        id: id.to_string(),
+        name: id.to_string(),
+        open_url: String::new(),
+        resource_type: "tcp".to_string(),
+    })
+}
+
+pub struct ResourceBuilder(Resource);
+
+impl ResourceBuilder {
+    pub fn name(mut self, name: &str) -> Self {
+        self.0.name = name.to_string();
+        self
+    }
+
+    pub fn address(mut self, address: &str) -> Self {
+        self.0.address = address.to_string();
+        self
+    }
+
+    pub fn resource_type(mut self, resource_type: &str) -> Self {
+        self.0.resource_type = resource_type.to_string();
+        self
+    }
+
+    /// The client's default alias, copied instead of the address
+    pub fn alias(mut self, alias: &str) -> Self {
+        self.0.alias = Some(alias.to_string());
+        self
+    }
+
+    /// Add an entry to the resource's aliases
+    pub fn with_alias(mut self, address: &str, open_url: &str) -> Self {
+        self.0.aliases.push(Alias {
+            address: address.to_string(),
+            open_url: open_url.to_string(),
+        });
+        self
+    }
+
+    pub fn open_in_browser(mut self) -> Self {
+        self.0.can_open_in_browser = true;
+        self
+    }
+
+    pub fn auth_expires_at(mut self, expires_at: Option<DateTime<Utc>>) -> Self {
+        self.0.auth_expires_at = expires_at;
+        self
+    }
+
+    /// Hide the resource from the user, as the client does with `client_visibility: 0`
+    pub fn hidden(mut self) -> Self {
+        self.0.client_visibility = 0;
+        self
+    }
+
+    pub fn build(self) -> Resource {
+        self.0
+    }
+}
+
+/// A network signed in as user@example.com with Internet Security off
+pub fn network(resources: Vec<Resource>) -> NetworkBuilder {
+    NetworkBuilder(Network {
+        admin_url: String::new(),
+        full_tunnel_time_limit: 0,
+        internet_security: InternetSecurity { mode: 0, status: 0 },
+        resources,
+        user: User {
+            avatar_url: String::new(),
+            email: "user@example.com".to_string(),
+            first_name: String::new(),
+            id: "user-1".to_string(),
+            is_admin: false,
+            last_name: String::new(),
+        },
+    })
+}
+
+pub struct NetworkBuilder(Network);
+
+impl NetworkBuilder {
+    pub fn email(mut self, email: &str) -> Self {
+        self.0.user.email = email.to_string();
+        self
+    }
+
+    pub fn internet_security(mut self, mode: i32) -> Self {
+        self.0.internet_security.mode = mode;
+        self
+    }
+
+    pub fn build(self) -> Network {
+        self.0
+    }
+}
+
+/// A resource as clients of the tray see it, with address `<id>.internal`
+pub fn resource_dto(id: &str, name: &str) -> ResourceDto {
+    ResourceDto {
+        id: id.to_string(),
+        name: name.to_string(),
+        address: format!("{}.internal", id),
+        aliases: vec![],
+        resource_type: "dns".to_string(),
+        open_url: None,
+        auth_required: false,
+        auth_expires_at: None,
+    }
+}
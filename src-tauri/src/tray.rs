@@ -1,9 +1,10 @@
 use crate::error::Result;
+use crate::menu::{disconnected_menu, MenuModel, MenuNode};
 use crate::models::{Network, Resource};
-use crate::state::{AppState, ServiceStatus};
+use crate::state::AppState;
 use std::sync::Mutex;
 use tauri::{
-    menu::{IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
+    menu::{Menu, MenuItem, MenuItemKind, PredefinedMenuItem, Submenu},
     AppHandle, Manager,
 };
 
@@ -14,49 +15,299 @@ pub enum MenuAction {
     CopyAddress(String),
     Authenticate(String),
     OpenInBrowser(String),
+    PinResource(String),
+    UnpinResource(String),
+    CopyAlias(String, String),
+    OpenAlias(String, String),
+    SetDefaultAlias(String, String),
+    ClearDefaultAlias(String),
+    TestConnection(String),
+    /// Run the configured launch action at the index for a resource
+    Launch(String, usize),
     OpenAuthUrl,
     CopyAuthUrl,
+    ShowClientLogs,
+    ShowLauncher,
+    Refresh,
     Quit,
     Unknown(String),
 }
 
 impl MenuAction {
     pub fn from_event_id(event_id: &str) -> Self {
-        match event_id {
-            QUIT_ID => MenuAction::Quit,
-            START_SERVICE_ID => MenuAction::StartService,
-            STOP_SERVICE_ID => MenuAction::StopService,
-            OPEN_AUTH_URL_ID => MenuAction::OpenAuthUrl,
-            COPY_AUTH_URL_ID => MenuAction::CopyAuthUrl,
-            id if id.contains(COPY_ADDRESS_ID) => {
-                let resource_id = id.split("-").last().unwrap_or_default();
-                MenuAction::CopyAddress(resource_id.to_string())
-            }
-            id if id.contains(AUTHENTICATE_ID) => {
-                let resource_id = id.split("-").last().unwrap_or_default();
-                MenuAction::Authenticate(resource_id.to_string())
-            }
-            id if id.contains(OPEN_IN_BROWSER_ID) => {
-                let resource_id = id.split("-").last().unwrap_or_default();
-                MenuAction::OpenInBrowser(resource_id.to_string())
-            }
+        match MenuId::decode(event_id) {
+            Some(MenuId::Quit) => MenuAction::Quit,
+            Some(MenuId::StartService) => MenuAction::StartService,
+            Some(MenuId::StopService) => MenuAction::StopService,
+            Some(MenuId::OpenAuthUrl) => MenuAction::OpenAuthUrl,
+            Some(MenuId::CopyAuthUrl) => MenuAction::CopyAuthUrl,
+            Some(MenuId::ShowClientLogs) => MenuAction::ShowClientLogs,
+            Some(MenuId::ShowLauncher) => MenuAction::ShowLauncher,
+            Some(MenuId::CopyAddress(resource_id)) => MenuAction::CopyAddress(resource_id),
+            Some(MenuId::Authenticate(resource_id)) => MenuAction::Authenticate(resource_id),
+            Some(MenuId::OpenInBrowser(resource_id)) => MenuAction::OpenInBrowser(resource_id),
+            Some(MenuId::PinResource(resource_id)) => MenuAction::PinResource(resource_id),
+            Some(MenuId::UnpinResource(resource_id)) => MenuAction::UnpinResource(resource_id),
+            Some(MenuId::CopyAlias(resource_id, address)) => MenuAction::CopyAlias(resource_id, address),
+            Some(MenuId::OpenAlias(resource_id, address)) => MenuAction::OpenAlias(resource_id, address),
+            Some(MenuId::SetDefaultAlias(resource_id, address)) => MenuAction::SetDefaultAlias(resource_id, address),
+            Some(MenuId::ClearDefaultAlias(resource_id)) => MenuAction::ClearDefaultAlias(resource_id),
+            Some(MenuId::TestConnection(resource_id)) => MenuAction::TestConnection(resource_id),
+            Some(MenuId::Launch(resource_id, index)) => MenuAction::Launch(resource_id, index),
             _ => MenuAction::Unknown(event_id.to_string()),
         }
     }
+
+    /// Menu id of the action without its arguments
+    pub fn name(&self) -> &'static str {
+        match self {
+            MenuAction::StartService => START_SERVICE_ID,
+            MenuAction::StopService => STOP_SERVICE_ID,
+            MenuAction::CopyAddress(_) => COPY_ADDRESS_ID,
+            MenuAction::Authenticate(_) => AUTHENTICATE_ID,
+            MenuAction::OpenInBrowser(_) => OPEN_IN_BROWSER_ID,
+            MenuAction::PinResource(_) => PIN_RESOURCE_ID,
+            MenuAction::UnpinResource(_) => UNPIN_RESOURCE_ID,
+            MenuAction::CopyAlias(..) => COPY_ALIAS_ID,
+            MenuAction::OpenAlias(..) => OPEN_ALIAS_ID,
+            MenuAction::SetDefaultAlias(..) => SET_DEFAULT_ALIAS_ID,
+            MenuAction::ClearDefaultAlias(_) => CLEAR_DEFAULT_ALIAS_ID,
+            MenuAction::TestConnection(_) => TEST_CONNECTION_ID,
+            MenuAction::Launch(..) => LAUNCH_ID,
+            MenuAction::OpenAuthUrl => OPEN_AUTH_URL_ID,
+            MenuAction::CopyAuthUrl => COPY_AUTH_URL_ID,
+            MenuAction::ShowClientLogs => SHOW_CLIENT_LOGS_ID,
+            MenuAction::ShowLauncher => SHOW_LAUNCHER_ID,
+            MenuAction::Refresh => REFRESH_ID,
+            MenuAction::Quit => QUIT_ID,
+            MenuAction::Unknown(_) => "unknown",
+        }
+    }
+
+    /// The resource the action applies to, if any
+    pub fn resource_id(&self) -> Option<&str> {
+        match self {
+            MenuAction::CopyAddress(resource_id)
+            | MenuAction::Authenticate(resource_id)
+            | MenuAction::OpenInBrowser(resource_id)
+            | MenuAction::PinResource(resource_id)
+            | MenuAction::UnpinResource(resource_id)
+            | MenuAction::CopyAlias(resource_id, _)
+            | MenuAction::OpenAlias(resource_id, _)
+            | MenuAction::SetDefaultAlias(resource_id, _)
+            | MenuAction::ClearDefaultAlias(resource_id)
+            | MenuAction::TestConnection(resource_id)
+            | MenuAction::Launch(resource_id, _) => Some(resource_id),
+            _ => None,
+        }
+    }
 }
 
 pub const TWINGATE_TRAY_ID: &str = "twingate_tray";
 pub const USER_STATUS_ID: &str = "user_status";
 pub const START_SERVICE_ID: &str = "start_service";
 pub const STOP_SERVICE_ID: &str = "stop_service";
+pub const RESOURCE_ID: &str = "resource";
 pub const RESOURCE_ADDRESS_ID: &str = "resource_address";
+pub const RESOURCE_AUTH_STATUS_ID: &str = "resource_auth_status";
+pub const RESOURCE_AUTH_EXPIRY_ID: &str = "resource_auth_expiry";
 pub const COPY_ADDRESS_ID: &str = "copy_address";
 pub const AUTHENTICATE_ID: &str = "authenticate";
 pub const OPEN_IN_BROWSER_ID: &str = "open_in_browser";
+pub const PIN_RESOURCE_ID: &str = "pin_resource";
+pub const UNPIN_RESOURCE_ID: &str = "unpin_resource";
+pub const RESOURCE_ALIASES_ID: &str = "resource_aliases";
+pub const ALIAS_ID: &str = "alias";
+pub const COPY_ALIAS_ID: &str = "copy_alias";
+pub const OPEN_ALIAS_ID: &str = "open_alias";
+pub const SET_DEFAULT_ALIAS_ID: &str = "set_default_alias";
+pub const CLEAR_DEFAULT_ALIAS_ID: &str = "clear_default_alias";
+pub const LAUNCH_ID: &str = "launch";
+pub const CONNECTION_STATUS_ID: &str = "connection_status";
+pub const TEST_CONNECTION_ID: &str = "test_connection";
+pub const PINNED_HEADER_ID: &str = "pinned_header";
+pub const RECENT_HEADER_ID: &str = "recent_header";
+pub const RESOURCE_GROUP_ID: &str = "resource_group";
+pub const MORE_RESOURCES_ID: &str = "more_resources";
 pub const OPEN_AUTH_URL_ID: &str = "open_auth_url";
 pub const COPY_AUTH_URL_ID: &str = "copy_auth_url";
+pub const AUTH_STATUS_ID: &str = "auth_status";
+pub const SECURITY_MODE_ID: &str = "security_mode";
+pub const RESOURCE_COUNT_ID: &str = "resource_total_count";
+pub const SHOW_CLIENT_LOGS_ID: &str = "show_client_logs";
+pub const SHOW_LAUNCHER_ID: &str = "show_launcher";
+pub const REFRESH_ID: &str = "refresh";
 pub const QUIT_ID: &str = "quit";
 
+/// Separates the action from its arguments in an encoded menu id
+const ID_SEPARATOR: char = ':';
+
+/// Typed identity of a tray menu entry.
+///
+/// Encodes to `action` or `action:arg` with arguments percent-escaped, so
+/// resource ids containing dashes, colons or anything else survive the round
+/// trip through tauri's string ids intact.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MenuId {
+    Quit,
+    StartService,
+    StopService,
+    UserStatus,
+    SecurityMode,
+    ResourceCount,
+    AuthStatus,
+    OpenAuthUrl,
+    CopyAuthUrl,
+    ShowClientLogs,
+    ShowLauncher,
+    PinnedHeader,
+    RecentHeader,
+    Resource(String),
+    ResourceAddress(String),
+    ResourceAuthStatus(String),
+    ResourceAuthExpiry(String),
+    CopyAddress(String),
+    Authenticate(String),
+    OpenInBrowser(String),
+    PinResource(String),
+    UnpinResource(String),
+    ResourceAliases(String),
+    /// Submenu of one alias of a resource, by alias address
+    Alias(String, String),
+    CopyAlias(String, String),
+    OpenAlias(String, String),
+    SetDefaultAlias(String, String),
+    ClearDefaultAlias(String),
+    ConnectionStatus(String),
+    TestConnection(String),
+    /// Launch action of a resource, by its position in the config
+    Launch(String, usize),
+    ResourceGroup(String),
+    /// Overflow submenu of a group ("" for the top level), holding the
+    /// entries from the given position on
+    MoreResources(String, usize),
+}
+
+impl MenuId {
+    pub fn encode(&self) -> String {
+        match self {
+            MenuId::Quit => QUIT_ID.to_string(),
+            MenuId::StartService => START_SERVICE_ID.to_string(),
+            MenuId::StopService => STOP_SERVICE_ID.to_string(),
+            MenuId::UserStatus => USER_STATUS_ID.to_string(),
+            MenuId::SecurityMode => SECURITY_MODE_ID.to_string(),
+            MenuId::ResourceCount => RESOURCE_COUNT_ID.to_string(),
+            MenuId::AuthStatus => AUTH_STATUS_ID.to_string(),
+            MenuId::OpenAuthUrl => OPEN_AUTH_URL_ID.to_string(),
+            MenuId::CopyAuthUrl => COPY_AUTH_URL_ID.to_string(),
+            MenuId::ShowClientLogs => SHOW_CLIENT_LOGS_ID.to_string(),
+            MenuId::ShowLauncher => SHOW_LAUNCHER_ID.to_string(),
+            MenuId::PinnedHeader => PINNED_HEADER_ID.to_string(),
+            MenuId::RecentHeader => RECENT_HEADER_ID.to_string(),
+            MenuId::Resource(id) => encode_id(RESOURCE_ID, &[id]),
+            MenuId::ResourceAddress(id) => encode_id(RESOURCE_ADDRESS_ID, &[id]),
+            MenuId::ResourceAuthStatus(id) => encode_id(RESOURCE_AUTH_STATUS_ID, &[id]),
+            MenuId::ResourceAuthExpiry(id) => encode_id(RESOURCE_AUTH_EXPIRY_ID, &[id]),
+            MenuId::CopyAddress(id) => encode_id(COPY_ADDRESS_ID, &[id]),
+            MenuId::Authenticate(id) => encode_id(AUTHENTICATE_ID, &[id]),
+            MenuId::OpenInBrowser(id) => encode_id(OPEN_IN_BROWSER_ID, &[id]),
+            MenuId::PinResource(id) => encode_id(PIN_RESOURCE_ID, &[id]),
+            MenuId::UnpinResource(id) => encode_id(UNPIN_RESOURCE_ID, &[id]),
+            MenuId::ResourceAliases(id) => encode_id(RESOURCE_ALIASES_ID, &[id]),
+            MenuId::Alias(id, address) => encode_id(ALIAS_ID, &[id, address]),
+            MenuId::CopyAlias(id, address) => encode_id(COPY_ALIAS_ID, &[id, address]),
+            MenuId::OpenAlias(id, address) => encode_id(OPEN_ALIAS_ID, &[id, address]),
+            MenuId::SetDefaultAlias(id, address) => encode_id(SET_DEFAULT_ALIAS_ID, &[id, address]),
+            MenuId::ClearDefaultAlias(id) => encode_id(CLEAR_DEFAULT_ALIAS_ID, &[id]),
+            MenuId::ConnectionStatus(id) => encode_id(CONNECTION_STATUS_ID, &[id]),
+            MenuId::TestConnection(id) => encode_id(TEST_CONNECTION_ID, &[id]),
+            MenuId::Launch(id, index) => encode_id(LAUNCH_ID, &[id, &index.to_string()]),
+            MenuId::ResourceGroup(group) => encode_id(RESOURCE_GROUP_ID, &[group]),
+            MenuId::MoreResources(group, offset) => encode_id(MORE_RESOURCES_ID, &[group, &offset.to_string()]),
+        }
+    }
+
+    pub fn decode(encoded: &str) -> Option<Self> {
+        let mut parts = encoded.split(ID_SEPARATOR);
+        let action = parts.next()?;
+        let args = parts.map(unescape_id_part).collect::<Option<Vec<_>>>()?;
+
+        match (action, args.as_slice()) {
+            (QUIT_ID, []) => Some(MenuId::Quit),
+            (START_SERVICE_ID, []) => Some(MenuId::StartService),
+            (STOP_SERVICE_ID, []) => Some(MenuId::StopService),
+            (USER_STATUS_ID, []) => Some(MenuId::UserStatus),
+            (SECURITY_MODE_ID, []) => Some(MenuId::SecurityMode),
+            (RESOURCE_COUNT_ID, []) => Some(MenuId::ResourceCount),
+            (AUTH_STATUS_ID, []) => Some(MenuId::AuthStatus),
+            (OPEN_AUTH_URL_ID, []) => Some(MenuId::OpenAuthUrl),
+            (COPY_AUTH_URL_ID, []) => Some(MenuId::CopyAuthUrl),
+            (SHOW_CLIENT_LOGS_ID, []) => Some(MenuId::ShowClientLogs),
+            (SHOW_LAUNCHER_ID, []) => Some(MenuId::ShowLauncher),
+            (PINNED_HEADER_ID, []) => Some(MenuId::PinnedHeader),
+            (RECENT_HEADER_ID, []) => Some(MenuId::RecentHeader),
+            (RESOURCE_ID, [id]) => Some(MenuId::Resource(id.clone())),
+            (RESOURCE_ADDRESS_ID, [id]) => Some(MenuId::ResourceAddress(id.clone())),
+            (RESOURCE_AUTH_STATUS_ID, [id]) => Some(MenuId::ResourceAuthStatus(id.clone())),
+            (RESOURCE_AUTH_EXPIRY_ID, [id]) => Some(MenuId::ResourceAuthExpiry(id.clone())),
+            (COPY_ADDRESS_ID, [id]) => Some(MenuId::CopyAddress(id.clone())),
+            (AUTHENTICATE_ID, [id]) => Some(MenuId::Authenticate(id.clone())),
+            (OPEN_IN_BROWSER_ID, [id]) => Some(MenuId::OpenInBrowser(id.clone())),
+            (PIN_RESOURCE_ID, [id]) => Some(MenuId::PinResource(id.clone())),
+            (UNPIN_RESOURCE_ID, [id]) => Some(MenuId::UnpinResource(id.clone())),
+            (RESOURCE_ALIASES_ID, [id]) => Some(MenuId::ResourceAliases(id.clone())),
+            (ALIAS_ID, [id, address]) => Some(MenuId::Alias(id.clone(), address.clone())),
+            (COPY_ALIAS_ID, [id, address]) => Some(MenuId::CopyAlias(id.clone(), address.clone())),
+            (OPEN_ALIAS_ID, [id, address]) => Some(MenuId::OpenAlias(id.clone(), address.clone())),
+            (SET_DEFAULT_ALIAS_ID, [id, address]) => Some(MenuId::SetDefaultAlias(id.clone(), address.clone())),
+            (CLEAR_DEFAULT_ALIAS_ID, [id]) => Some(MenuId::ClearDefaultAlias(id.clone())),
+            (CONNECTION_STATUS_ID, [id]) => Some(MenuId::ConnectionStatus(id.clone())),
+            (TEST_CONNECTION_ID, [id]) => Some(MenuId::TestConnection(id.clone())),
+            (LAUNCH_ID, [id, index]) => Some(MenuId::Launch(id.clone(), index.parse().ok()?)),
+            (RESOURCE_GROUP_ID, [group]) => Some(MenuId::ResourceGroup(group.clone())),
+            (MORE_RESOURCES_ID, [group, offset]) => Some(MenuId::MoreResources(group.clone(), offset.parse().ok()?)),
+            _ => None,
+        }
+    }
+}
+
+fn encode_id(action: &str, args: &[&str]) -> String {
+    let mut encoded = action.to_string();
+    for arg in args {
+        encoded.push(ID_SEPARATOR);
+        encoded.push_str(&escape_id_part(arg));
+    }
+    encoded
+}
+
+fn escape_id_part(part: &str) -> String {
+    let mut escaped = String::with_capacity(part.len());
+    for c in part.chars() {
+        match c {
+            '%' => escaped.push_str("%25"),
+            ID_SEPARATOR => escaped.push_str("%3A"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape_id_part(part: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(part.len());
+    let mut rest = part;
+    while let Some(pos) = rest.find('%') {
+        unescaped.push_str(&rest[..pos]);
+        match rest.get(pos + 1..pos + 3) {
+            Some("25") => unescaped.push('%'),
+            Some("3A") => unescaped.push(ID_SEPARATOR),
+            _ => return None,
+        }
+        rest = &rest[pos + 3..];
+    }
+    unescaped.push_str(rest);
+    Some(unescaped)
+}
+
 pub fn get_address_from_resource(resource: &Resource) -> &String {
     resource
         .alias
@@ -77,80 +328,38 @@ pub fn get_open_url_from_resource(resource: &Resource) -> Option<&String> {
         .map(|alias| &alias.open_url)
 }
 
-pub fn build_resource_menu(resource: &Resource, app: &AppHandle) -> Result<Submenu<tauri::Wry>> {
-    let submenu = Submenu::with_id(app, &resource.id, &resource.name, true)?;
-
-    let address_to_use = get_address_from_resource(resource);
-
-    submenu.append(&MenuItem::with_id(
-        app,
-        format!("{}-{}", RESOURCE_ADDRESS_ID, &resource.id),
-        address_to_use,
-        false,
-        None::<&str>,
-    )?)?;
-
-    submenu.append(&MenuItem::with_id(
-        app,
-        format!("{}-{}", COPY_ADDRESS_ID, &resource.id),
-        "Copy Address",
-        true,
-        None::<&str>,
-    )?)?;
-
-    // Add "Open in Browser" menu item if resource supports it
-    if let Some(_open_url) = get_open_url_from_resource(resource) {
-        submenu.append(&MenuItem::with_id(
-            app,
-            format!("{}-{}", OPEN_IN_BROWSER_ID, &resource.id),
-            "Open in Browser...",
-            true,
-            None::<&str>,
-        )?)?;
+/// Render a menu model into a tauri tray menu
+pub fn render_menu(app: &AppHandle, model: &MenuModel) -> Result<Menu<tauri::Wry>> {
+    let menu = Menu::new(app)?;
+    for node in &model.items {
+        menu.append(&render_node(app, node)?)?;
     }
-
-    submenu.append(&PredefinedMenuItem::separator(app)?)?;
-
-    let auth_menu_items = build_auth_menu(resource, app)?;
-    let refs: Vec<&dyn IsMenuItem<tauri::Wry>> = auth_menu_items
-        .iter()
-        .map(|item| item as &dyn IsMenuItem<tauri::Wry>)
-        .collect();
-
-    submenu.append_items(&refs)?;
-
-    Ok(submenu)
+    Ok(menu)
 }
 
-pub fn build_auth_menu(resource: &Resource, app: &AppHandle) -> Result<Vec<MenuItem<tauri::Wry>>> {
-    match resource.auth_expires_at == 0 {
-        true => Ok(vec![
-            MenuItem::with_id(
-                app,
-                "auth_required",
-                "Authentication Required",
-                false,
-                None::<&str>,
-            )?,
-            MenuItem::with_id(
-                app,
-                format!("{}-{}", AUTHENTICATE_ID, &resource.id),
-                "Authenticate...",
-                true,
-                None::<&str>,
-            )?,
-        ]),
-        false => Ok(vec![MenuItem::with_id(
+fn render_node(app: &AppHandle, node: &MenuNode) -> Result<MenuItemKind<tauri::Wry>> {
+    Ok(match node {
+        MenuNode::Item { id, label, enabled } => MenuItemKind::MenuItem(MenuItem::with_id(
             app,
-            "resource_auth_header",
-            format!(
-                "Auth expires in {} days",
-                chrono::Duration::milliseconds(resource.auth_expires_at).num_days()
-            ),
-            false,
+            id.encode(),
+            label,
+            *enabled,
             None::<&str>,
-        )?]),
-    }
+        )?),
+        MenuNode::Separator => MenuItemKind::Predefined(PredefinedMenuItem::separator(app)?),
+        MenuNode::Submenu {
+            id,
+            label,
+            enabled,
+            children,
+        } => {
+            let submenu = Submenu::with_id(app, id.encode(), label, *enabled)?;
+            for child in children {
+                submenu.append(&render_node(app, child)?)?;
+            }
+            MenuItemKind::Submenu(submenu)
+        }
+    })
 }
 
 pub async fn build_tray_menu(
@@ -158,170 +367,27 @@ pub async fn build_tray_menu(
     network_data: Option<Network>,
 ) -> Result<Menu<tauri::Wry>> {
     // Check application state to determine if we're in authenticating mode
-    let service_status = {
+    let (service_status, layout) = {
         let app_state = app.state::<Mutex<AppState>>();
         let state_guard = app_state.lock().unwrap();
-        state_guard.service_status().clone()
+        (state_guard.service_status().clone(), state_guard.menu_layout().clone())
     };
-    
-    match service_status {
-        ServiceStatus::Authenticating(auth_url) => build_authenticating_menu(app, &auth_url).await,
-        _ => match network_data {
-            Some(n) => build_connected_menu(app, &n).await,
-            None => build_disconnected_menu(app).await,
-        }
-    }
-}
-
-async fn build_connected_menu(app: &AppHandle, network: &Network) -> Result<Menu<tauri::Wry>> {
-    let visible_resources: Vec<_> = network
-        .resources
-        .iter()
-        .filter(|r| r.client_visibility != 0)
-        .collect();
-
-    let mut menu_items: Vec<&dyn IsMenuItem<tauri::Wry>> = Vec::new();
-
-    // User status section
-    let user_status_items = build_user_status_section(app, network)?;
-    for item in &user_status_items {
-        menu_items.push(item);
-    }
-
-    // Separator
-    let separator = PredefinedMenuItem::separator(app)?;
-    menu_items.push(&separator);
-
-    // Resources section
-    let (resource_count_item, resource_submenus) =
-        build_resources_section(app, &visible_resources)?;
-    menu_items.push(&resource_count_item);
-
-    for submenu in &resource_submenus {
-        menu_items.push(submenu);
-    }
-
-    // Final separator and quit
-    menu_items.push(&separator);
-    let quit_item = MenuItem::with_id(app, QUIT_ID, "Close Tray", true, None::<&str>)?;
-    menu_items.push(&quit_item);
-
-    Ok(Menu::with_items(app, &menu_items[..])?)
-}
-
-pub async fn build_disconnected_menu(app: &AppHandle) -> Result<Menu<tauri::Wry>> {
-    let start_item =
-        MenuItem::with_id(app, START_SERVICE_ID, "Start Twingate", true, None::<&str>)?;
-    
-    let separator = PredefinedMenuItem::separator(app)?;
-    let quit_item = MenuItem::with_id(app, QUIT_ID, "Close Tray", true, None::<&str>)?;
-
-    Ok(Menu::with_items(app, &[&start_item, &separator, &quit_item])?)
-}
-
-pub async fn build_authenticating_menu(app: &AppHandle, _auth_url: &str) -> Result<Menu<tauri::Wry>> {
-    let auth_status = MenuItem::with_id(
-        app,
-        "auth_status",
-        "Authenticating...",
-        false,
-        None::<&str>,
-    )?;
-
-    let separator1 = PredefinedMenuItem::separator(app)?;
-
-    let open_auth_url_item = MenuItem::with_id(
-        app,
-        OPEN_AUTH_URL_ID,
-        "Open Authentication URL",
-        true,
-        None::<&str>,
-    )?;
-
-    let copy_auth_url_item = MenuItem::with_id(
-        app,
-        COPY_AUTH_URL_ID,
-        "Copy Authentication URL",
-        true,
-        None::<&str>,
-    )?;
-
-    let separator2 = PredefinedMenuItem::separator(app)?;
-    let quit_item = MenuItem::with_id(app, QUIT_ID, "Close Tray", true, None::<&str>)?;
-
-    Ok(Menu::with_items(app, &[
-        &auth_status,
-        &separator1,
-        &open_auth_url_item,
-        &copy_auth_url_item,
-        &separator2,
-        &quit_item
-    ])?)
-}
 
-fn build_user_status_section(
-    app: &AppHandle,
-    network: &Network,
-) -> Result<Vec<MenuItem<tauri::Wry>>> {
-    let mut items = Vec::new();
-
-    let user_status_item = MenuItem::with_id(
-        app,
-        USER_STATUS_ID,
-        network.user.email.clone(),
-        true,
-        None::<&str>,
-    )?;
-    items.push(user_status_item);
-
-    if network.internet_security.mode > 0 {
-        let security_item = MenuItem::with_id(
-            app,
-            "security_mode",
-            "Security Enabled",
-            false,
-            None::<&str>,
-        )?;
-        items.push(security_item);
-    }
-
-    let stop_service_item = MenuItem::with_id(
+    render_menu(
         app,
-        STOP_SERVICE_ID,
-        "Log Out and Disconnect",
-        true,
-        None::<&str>,
-    )?;
-    items.push(stop_service_item);
-
-    Ok(items)
+        &MenuModel::build(&service_status, network_data.as_ref(), &layout, &chrono::Local::now()),
+    )
 }
 
-fn build_resources_section(
-    app: &AppHandle,
-    visible_resources: &[&Resource],
-) -> Result<(MenuItem<tauri::Wry>, Vec<Submenu<tauri::Wry>>)> {
-    let total_resources_item = MenuItem::with_id(
-        app,
-        "resource_total_count",
-        format!("{} Resources", visible_resources.len()),
-        false,
-        None::<&str>,
-    )?;
-
-    let resource_submenus: Result<Vec<_>> = visible_resources
-        .iter()
-        .map(|r| build_resource_menu(r, app))
-        .collect();
-    let resource_submenus = resource_submenus?;
-
-    Ok((total_resources_item, resource_submenus))
+pub async fn build_disconnected_menu(app: &AppHandle) -> Result<Menu<tauri::Wry>> {
+    render_menu(app, &disconnected_menu())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::models::Alias;
+    use chrono::DateTime;
 
     fn create_test_resource() -> Resource {
         Resource {
@@ -334,7 +400,7 @@ mod tests {
                     open_url: "https://server.internal".to_string(),
                 }
             ],
-            auth_expires_at: 1640995200,
+            auth_expires_at: DateTime::from_timestamp(1640995200, 0),
             auth_flow_id: "flow-123".to_string(),
             auth_state: "authenticated".to_string(),
             can_open_in_browser: true,
@@ -352,7 +418,7 @@ mod tests {
             admin_url: "https://admin.twingate.com/resource/124".to_string(),
             alias: None,
             aliases: vec![],
-            auth_expires_at: 0, // Requires authentication
+            auth_expires_at: None, // Requires authentication
             auth_flow_id: "flow-124".to_string(),
             auth_state: "not_authenticated".to_string(),
             can_open_in_browser: false,
@@ -371,14 +437,16 @@ mod tests {
         assert!(matches!(MenuAction::from_event_id(STOP_SERVICE_ID), MenuAction::StopService));
         assert!(matches!(MenuAction::from_event_id(OPEN_AUTH_URL_ID), MenuAction::OpenAuthUrl));
         assert!(matches!(MenuAction::from_event_id(COPY_AUTH_URL_ID), MenuAction::CopyAuthUrl));
+        assert!(matches!(MenuAction::from_event_id(SHOW_CLIENT_LOGS_ID), MenuAction::ShowClientLogs));
+        assert!(matches!(MenuAction::from_event_id(SHOW_LAUNCHER_ID), MenuAction::ShowLauncher));
     }
 
     #[test]
     fn test_menu_action_from_event_id_copy_address() {
-        let event_id = "copy_address-resource-123";
+        let event_id = "copy_address:resource-123";
         match MenuAction::from_event_id(event_id) {
             MenuAction::CopyAddress(resource_id) => {
-                assert_eq!(resource_id, "123"); // split("-").last() returns the last part
+                assert_eq!(resource_id, "resource-123");
             }
             _ => panic!("Expected CopyAddress action"),
         }
@@ -386,10 +454,10 @@ mod tests {
 
     #[test]
     fn test_menu_action_from_event_id_authenticate() {
-        let event_id = "authenticate-resource-456";
+        let event_id = "authenticate:resource-456";
         match MenuAction::from_event_id(event_id) {
             MenuAction::Authenticate(resource_id) => {
-                assert_eq!(resource_id, "456"); // split("-").last() returns the last part
+                assert_eq!(resource_id, "resource-456");
             }
             _ => panic!("Expected Authenticate action"),
         }
@@ -397,10 +465,10 @@ mod tests {
 
     #[test]
     fn test_menu_action_from_event_id_open_in_browser() {
-        let event_id = "open_in_browser-resource-789";
+        let event_id = "open_in_browser:resource-789";
         match MenuAction::from_event_id(event_id) {
             MenuAction::OpenInBrowser(resource_id) => {
-                assert_eq!(resource_id, "789"); // split("-").last() returns the last part
+                assert_eq!(resource_id, "resource-789");
             }
             _ => panic!("Expected OpenInBrowser action"),
         }
@@ -496,28 +564,59 @@ mod tests {
         assert_eq!(USER_STATUS_ID, "user_status");
         assert_eq!(START_SERVICE_ID, "start_service");
         assert_eq!(STOP_SERVICE_ID, "stop_service");
+        assert_eq!(RESOURCE_ID, "resource");
         assert_eq!(RESOURCE_ADDRESS_ID, "resource_address");
+        assert_eq!(RESOURCE_AUTH_STATUS_ID, "resource_auth_status");
+        assert_eq!(RESOURCE_AUTH_EXPIRY_ID, "resource_auth_expiry");
         assert_eq!(COPY_ADDRESS_ID, "copy_address");
         assert_eq!(AUTHENTICATE_ID, "authenticate");
         assert_eq!(OPEN_IN_BROWSER_ID, "open_in_browser");
         assert_eq!(OPEN_AUTH_URL_ID, "open_auth_url");
         assert_eq!(COPY_AUTH_URL_ID, "copy_auth_url");
+        assert_eq!(AUTH_STATUS_ID, "auth_status");
+        assert_eq!(SECURITY_MODE_ID, "security_mode");
+        assert_eq!(RESOURCE_COUNT_ID, "resource_total_count");
+        assert_eq!(SHOW_CLIENT_LOGS_ID, "show_client_logs");
         assert_eq!(QUIT_ID, "quit");
     }
 
     #[test]
     fn test_menu_action_from_event_id_edge_cases() {
-        // Test with malformed IDs
-        match MenuAction::from_event_id("copy_address-") {
+        // An empty resource id is still a valid argument
+        match MenuAction::from_event_id("copy_address:") {
             MenuAction::CopyAddress(id) => assert_eq!(id, ""),
             _ => panic!("Expected CopyAddress with empty ID"),
         }
 
-        // "authenticate" contains "authenticate" so it will be parsed as Authenticate action
-        match MenuAction::from_event_id("authenticate") {
-            MenuAction::Authenticate(id) => assert_eq!(id, "authenticate"), // split("-").last() on "authenticate" returns "authenticate"
-            _ => panic!("Expected Authenticate action"),
-        }
+        // Actions that need a resource id are unknown without one
+        assert!(matches!(
+            MenuAction::from_event_id("authenticate"),
+            MenuAction::Unknown(_)
+        ));
+
+        // Old dash-separated ids are no longer recognised
+        assert!(matches!(
+            MenuAction::from_event_id("copy_address-resource-123"),
+            MenuAction::Unknown(_)
+        ));
+
+        // Actions are matched exactly, not by substring
+        assert!(matches!(
+            MenuAction::from_event_id("not_authenticate:resource-1"),
+            MenuAction::Unknown(_)
+        ));
+
+        // Too many arguments
+        assert!(matches!(
+            MenuAction::from_event_id("authenticate:a:b"),
+            MenuAction::Unknown(_)
+        ));
+
+        // Items without an action are not dispatched
+        assert!(matches!(
+            MenuAction::from_event_id("resource_address:resource-1"),
+            MenuAction::Unknown(_)
+        ));
 
         // Test with empty string
         match MenuAction::from_event_id("") {
@@ -568,22 +667,83 @@ mod tests {
 
     #[test]
     fn test_menu_action_resource_id_extraction() {
-        // Test complex resource IDs
-        let complex_id = "copy_address-very-long-resource-id-with-many-dashes";
-        match MenuAction::from_event_id(complex_id) {
+        // Complex resource IDs are preserved in full
+        let complex_id = MenuId::CopyAddress("very-long-resource-id-with-many-dashes".to_string()).encode();
+        match MenuAction::from_event_id(&complex_id) {
             MenuAction::CopyAddress(resource_id) => {
-                assert_eq!(resource_id, "dashes"); // Should get the last part after split
+                assert_eq!(resource_id, "very-long-resource-id-with-many-dashes");
             }
             _ => panic!("Expected CopyAddress action"),
         }
 
-        // Test single dash
-        let single_dash = "authenticate-single";
-        match MenuAction::from_event_id(single_dash) {
+        let single_dash = MenuId::Authenticate("single".to_string()).encode();
+        match MenuAction::from_event_id(&single_dash) {
             MenuAction::Authenticate(resource_id) => {
                 assert_eq!(resource_id, "single");
             }
             _ => panic!("Expected Authenticate action"),
         }
     }
+
+    #[test]
+    fn test_menu_id_round_trip() {
+        let ids = vec![
+            MenuId::Quit,
+            MenuId::StartService,
+            MenuId::StopService,
+            MenuId::UserStatus,
+            MenuId::SecurityMode,
+            MenuId::ResourceCount,
+            MenuId::AuthStatus,
+            MenuId::OpenAuthUrl,
+            MenuId::CopyAuthUrl,
+            MenuId::ShowClientLogs,
+            MenuId::ShowLauncher,
+            MenuId::PinnedHeader,
+            MenuId::RecentHeader,
+            MenuId::Resource("UmVzb3VyY2U6MTIz".to_string()),
+            MenuId::ResourceAddress("a-b-c".to_string()),
+            MenuId::ResourceAuthStatus("r:1".to_string()),
+            MenuId::ResourceAuthExpiry("r-1".to_string()),
+            MenuId::CopyAddress("100%".to_string()),
+            MenuId::Authenticate("%3A:%25".to_string()),
+            MenuId::OpenInBrowser("".to_string()),
+            MenuId::PinResource("r:1".to_string()),
+            MenuId::UnpinResource("r-1".to_string()),
+            MenuId::ResourceAliases("r-1".to_string()),
+            MenuId::Alias("r:1".to_string(), "db.corp:5432".to_string()),
+            MenuId::CopyAlias("r-1".to_string(), "db.corp.internal".to_string()),
+            MenuId::OpenAlias("r-1".to_string(), "100%".to_string()),
+            MenuId::SetDefaultAlias("r:1".to_string(), "kb.corp.internal".to_string()),
+            MenuId::ClearDefaultAlias("r-1".to_string()),
+            MenuId::ConnectionStatus("r:1".to_string()),
+            MenuId::TestConnection("r-1".to_string()),
+            MenuId::Launch("r:1".to_string(), 2),
+            MenuId::ResourceGroup("corp.internal".to_string()),
+            MenuId::MoreResources("".to_string(), 24),
+            MenuId::MoreResources("a:b".to_string(), 48),
+        ];
+
+        for id in ids {
+            assert_eq!(MenuId::decode(&id.encode()), Some(id.clone()), "Failed for {:?}", id);
+        }
+    }
+
+    #[test]
+    fn test_menu_id_encoding_escapes_separator() {
+        assert_eq!(
+            MenuId::CopyAddress("res:1%".to_string()).encode(),
+            "copy_address:res%3A1%25"
+        );
+        assert_eq!(MenuId::Quit.encode(), "quit");
+    }
+
+    #[test]
+    fn test_menu_id_decode_rejects_invalid_escapes() {
+        assert_eq!(MenuId::decode("copy_address:100%"), None);
+        assert_eq!(MenuId::decode("copy_address:%2"), None);
+        assert_eq!(MenuId::decode("copy_address:%41"), None);
+        assert_eq!(MenuId::decode("quit:extra"), None);
+        assert_eq!(MenuId::decode(""), None);
+    }
 }
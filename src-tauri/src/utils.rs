@@ -50,6 +50,25 @@ pub fn extract_url_with_pattern(text: &str, patterns: &[&str]) -> Option<String>
     extract_url_from_text(text)
 }
 
+/// Format a span of time compactly, e.g. "2d 4h", "3h 12m" or "45m"
+pub fn format_duration_short(duration: chrono::Duration) -> String {
+    let duration = duration.abs();
+    let (days, hours, minutes) = (
+        duration.num_days(),
+        duration.num_hours() % 24,
+        duration.num_minutes() % 60,
+    );
+
+    match (days, hours, minutes) {
+        (0, 0, 0) => "less than a minute".to_string(),
+        (0, 0, m) => format!("{}m", m),
+        (0, h, 0) => format!("{}h", h),
+        (0, h, m) => format!("{}h {}m", h, m),
+        (d, 0, _) => format!("{}d", d),
+        (d, h, _) => format!("{}d {}h", d, h),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -257,4 +276,17 @@ to complete device authentication.
         let url = extract_url_from_line("Visit https://example.com/page#section").unwrap();
         assert_eq!(url, "https://example.com/page#section");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_format_duration_short() {
+        use chrono::Duration;
+
+        assert_eq!(format_duration_short(Duration::seconds(30)), "less than a minute");
+        assert_eq!(format_duration_short(Duration::minutes(45)), "45m");
+        assert_eq!(format_duration_short(Duration::minutes(192)), "3h 12m");
+        assert_eq!(format_duration_short(Duration::hours(2)), "2h");
+        assert_eq!(format_duration_short(Duration::hours(52) + Duration::minutes(5)), "2d 4h");
+        assert_eq!(format_duration_short(Duration::days(18)), "18d");
+        assert_eq!(format_duration_short(Duration::minutes(-5)), "5m");
+    }
+}
@@ -0,0 +1,96 @@
+import { useEffect, useRef, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import "./ClientLogs.css";
+
+type LogLevel = "error" | "warning" | "notice" | "info" | "debug";
+
+interface JournalEntry {
+  timestamp: string;
+  level: LogLevel;
+  identifier: string | null;
+  pid: number | null;
+  message: string;
+}
+
+const LEVELS: LogLevel[] = ["error", "warning", "notice", "info", "debug"];
+/** Entries rendered at once; the backend keeps its own, larger buffer */
+const MAX_SHOWN_ENTRIES = 2000;
+
+function ClientLogs() {
+  const [entries, setEntries] = useState<JournalEntry[]>([]);
+  const [level, setLevel] = useState<LogLevel>("info");
+  const [search, setSearch] = useState("");
+  const [error, setError] = useState<string | null>(null);
+  const [follow, setFollow] = useState(true);
+  const bottomRef = useRef<HTMLDivElement>(null);
+
+  // New entries already pass the filter last sent to the backend
+  useEffect(() => {
+    const unlisten = listen<JournalEntry>("client-log", (event) => {
+      setEntries((current) => [...current, event.payload].slice(-MAX_SHOWN_ENTRIES));
+    });
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, []);
+
+  useEffect(() => {
+    invoke<JournalEntry[]>("follow_client_logs", { filter: { level, search } })
+      .then((matching) => {
+        setEntries(matching.slice(-MAX_SHOWN_ENTRIES));
+        setError(null);
+      })
+      .catch((e) => setError(String(e)));
+  }, [level, search]);
+
+  useEffect(() => {
+    if (follow) {
+      bottomRef.current?.scrollIntoView();
+    }
+  }, [entries, follow]);
+
+  return (
+    <main className="logs">
+      <header className="logs-toolbar">
+        <select value={level} onChange={(e) => setLevel(e.currentTarget.value as LogLevel)}>
+          {LEVELS.map((l) => (
+            <option key={l} value={l}>
+              {l === "debug" ? "All levels" : `${l} and above`}
+            </option>
+          ))}
+        </select>
+        <input
+          type="search"
+          placeholder="Search messages..."
+          value={search}
+          onChange={(e) => setSearch(e.currentTarget.value)}
+        />
+        <label>
+          <input type="checkbox" checked={follow} onChange={(e) => setFollow(e.currentTarget.checked)} />
+          Follow
+        </label>
+      </header>
+
+      {error && <p className="logs-error">{error}</p>}
+
+      <div className="logs-entries">
+        {entries.length === 0 && !error && <p className="logs-empty">No matching log entries yet.</p>}
+        {entries.map((entry, i) => (
+          <div key={i} className={`log-entry log-${entry.level}`}>
+            <span className="log-time">{new Date(entry.timestamp).toLocaleString()}</span>
+            <span className="log-level">{entry.level}</span>
+            <span className="log-source">
+              {entry.identifier ?? ""}
+              {entry.pid !== null ? `[${entry.pid}]` : ""}
+            </span>
+            <span className="log-message">{entry.message}</span>
+          </div>
+        ))}
+        <div ref={bottomRef} />
+      </div>
+    </main>
+  );
+}
+
+export default ClientLogs;
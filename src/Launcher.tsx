@@ -0,0 +1,159 @@
+import { useCallback, useEffect, useRef, useState } from "react";
+import { getCurrentWindow } from "@tauri-apps/api/window";
+import {
+  authenticateResource,
+  copyAddress,
+  hideLauncher,
+  onEvent,
+  openResource,
+  searchResources,
+  type Resource,
+} from "./api";
+import "./Launcher.css";
+
+type LauncherAction = "default" | "copy" | "authenticate";
+
+/** What Enter does: sign in first if needed, otherwise open, otherwise copy */
+function runAction(resource: Resource, action: LauncherAction): Promise<void> {
+  if (action === "copy") {
+    return copyAddress(resource.id);
+  }
+  if (action === "authenticate" || resource.auth_required) {
+    return authenticateResource(resource.id);
+  }
+  return resource.open_url ? openResource(resource.id) : copyAddress(resource.id);
+}
+
+function defaultActionLabel(resource: Resource): string {
+  if (resource.auth_required) {
+    return "Authenticate";
+  }
+  return resource.open_url ? "Open" : "Copy";
+}
+
+function Launcher() {
+  const [query, setQuery] = useState("");
+  const [results, setResults] = useState<Resource[]>([]);
+  const [selected, setSelected] = useState(0);
+  const [error, setError] = useState<string | null>(null);
+  const inputRef = useRef<HTMLInputElement>(null);
+  const selectedRef = useRef<HTMLLIElement>(null);
+  // Answers to older queries can arrive after newer ones
+  const latestSearch = useRef(0);
+
+  const search = useCallback((text: string) => {
+    const id = ++latestSearch.current;
+    searchResources(text)
+      .then((found) => {
+        if (id === latestSearch.current) {
+          setResults(found);
+          setSelected(0);
+          setError(null);
+        }
+      })
+      .catch((e) => {
+        if (id === latestSearch.current) {
+          setResults([]);
+          setError(String(e));
+        }
+      });
+  }, []);
+
+  useEffect(() => search(query), [query, search]);
+
+  // The window is shown and hidden rather than recreated, so start each use afresh
+  useEffect(() => {
+    const unlisten = getCurrentWindow().onFocusChanged(({ payload: focused }) => {
+      if (focused) {
+        inputRef.current?.select();
+        search(inputRef.current?.value ?? "");
+      } else {
+        hideLauncher();
+      }
+    });
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, [search]);
+
+  useEffect(() => {
+    const unlisten = onEvent("network-updated", () => search(inputRef.current?.value ?? ""));
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, [search]);
+
+  useEffect(() => {
+    selectedRef.current?.scrollIntoView({ block: "nearest" });
+  }, [selected]);
+
+  function run(resource: Resource, action: LauncherAction) {
+    hideLauncher();
+    runAction(resource, action).catch((e) => setError(String(e)));
+  }
+
+  function onKeyDown(e: React.KeyboardEvent) {
+    const current = results[selected];
+    switch (e.key) {
+      case "ArrowDown":
+        setSelected((i) => Math.min(i + 1, results.length - 1));
+        break;
+      case "ArrowUp":
+        setSelected((i) => Math.max(i - 1, 0));
+        break;
+      case "Enter":
+        if (current) {
+          run(current, e.ctrlKey ? "copy" : e.shiftKey ? "authenticate" : "default");
+        }
+        break;
+      case "Escape":
+        hideLauncher();
+        break;
+      default:
+        return;
+    }
+    e.preventDefault();
+  }
+
+  return (
+    <main className="launcher" onKeyDown={onKeyDown}>
+      <input
+        ref={inputRef}
+        className="launcher-search"
+        type="search"
+        placeholder="Search resources..."
+        value={query}
+        onChange={(e) => setQuery(e.currentTarget.value)}
+        autoFocus
+      />
+
+      {error && <p className="launcher-error">{error}</p>}
+
+      <ul className="launcher-results">
+        {results.length === 0 && !error && <li className="launcher-empty">No matching resources.</li>}
+        {results.map((resource, i) => (
+          <li
+            key={resource.id}
+            ref={i === selected ? selectedRef : undefined}
+            className={i === selected ? "launcher-result selected" : "launcher-result"}
+            onMouseMove={() => setSelected(i)}
+            onClick={() => run(resource, "default")}
+          >
+            <span className="launcher-name">{resource.name}</span>
+            <span className="launcher-address">
+              {[resource.address, ...resource.aliases.map((alias) => alias.address)].join(", ")}
+            </span>
+            <span className="launcher-action">{defaultActionLabel(resource)}</span>
+          </li>
+        ))}
+      </ul>
+
+      <footer className="launcher-hints">
+        Enter: {results[selected] ? defaultActionLabel(results[selected]) : "Open"} · Ctrl+Enter: Copy address ·
+        Shift+Enter: Authenticate · Esc: Close
+      </footer>
+    </main>
+  );
+}
+
+export default Launcher;
@@ -0,0 +1,81 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+
+// Mirrors the DTOs in src-tauri/src/dto.rs
+
+export type ConnectionStatus = "connected" | "disconnected" | "authenticating" | "error";
+
+export interface Status {
+  status: ConnectionStatus;
+  user_email: string | null;
+  resource_count: number;
+  security_enabled: boolean;
+  auth_url: string | null;
+  error: string | null;
+}
+
+export interface Alias {
+  address: string;
+  open_url: string;
+}
+
+export interface Resource {
+  id: string;
+  name: string;
+  address: string;
+  aliases: Alias[];
+  resource_type: string;
+  open_url: string | null;
+  auth_required: boolean;
+  auth_expires_at: string | null;
+}
+
+export const getStatus = () => invoke<Status>("get_status");
+export const listResources = () => invoke<Resource[]>("list_resources");
+export const startService = () => invoke<void>("start_service");
+export const stopService = () => invoke<void>("stop_service");
+export const authenticateResource = (resourceId: string) =>
+  invoke<void>("authenticate_resource", { resourceId });
+export const copyAddress = (resourceId: string) => invoke<void>("copy_address", { resourceId });
+export const openResource = (resourceId: string) => invoke<void>("open_resource", { resourceId });
+
+// Mirrors the payloads in src-tauri/src/events.rs
+
+export interface NetworkUpdated {
+  resources: Resource[];
+  added: string[];
+  removed: string[];
+}
+
+export interface AuthStarted {
+  resource_id: string | null;
+  auth_url: string | null;
+}
+
+export interface AuthCompleted {
+  resource_id: string | null;
+}
+
+export interface OperationFailed {
+  operation: string;
+  resource_id: string | null;
+  message: string;
+}
+
+export interface AppEvents {
+  "status-changed": Status;
+  "network-updated": NetworkUpdated;
+  "auth-started": AuthStarted;
+  "auth-completed": AuthCompleted;
+  "operation-failed": OperationFailed;
+}
+
+export const onEvent = <K extends keyof AppEvents>(
+  name: K,
+  handler: (payload: AppEvents[K]) => void,
+): Promise<UnlistenFn> => listen<AppEvents[K]>(name, (event) => handler(event.payload));
+
+// Quick launcher
+
+export const searchResources = (query: string) => invoke<Resource[]>("search_resources", { query });
+export const hideLauncher = () => invoke<void>("hide_launcher");
@@ -1,9 +1,11 @@
 import React from "react";
 import ReactDOM from "react-dom/client";
-import App from "./App";
+import ClientLogs from "./ClientLogs";
+import Launcher from "./Launcher";
+
+// Secondary windows load the same bundle and pick their view by URL fragment
+const view = window.location.hash === "#/logs" ? <ClientLogs /> : <Launcher />;
 
 ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
-  <React.StrictMode>
-    <App />
-  </React.StrictMode>,
+  <React.StrictMode>{view}</React.StrictMode>,
 );